@@ -0,0 +1,277 @@
+use crate::frontend::Color;
+use crate::grid::{Grid, WORD_BITS};
+use std::fmt;
+use std::str::FromStr;
+
+/// Number of generations a dead cell stays visible in the fade trail when no
+/// other one is given.
+pub const DEFAULT_TRAIL_LENGTH: u8 = 6;
+
+/// Age at which cells reach the last color of a gradient.
+pub const DEFAULT_GRADIENT_SPAN: u32 = 64;
+
+/// Color of the cells that just died, fading to the background.
+pub const TRAIL_COLOR: Color = Color::new(120, 150, 230);
+
+/// How the live cells are colored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ColorMode {
+    /// Every live cell has the same color.
+    Flat,
+    /// Cells are colored by age along the gradient.
+    Age,
+    /// Cells are colored by age, and the cells that just died fade out.
+    #[default]
+    Trail,
+}
+
+impl ColorMode {
+    /// All color modes, in the order they are cycled through.
+    pub const ALL: [ColorMode; 3] = [ColorMode::Flat, ColorMode::Age, ColorMode::Trail];
+
+    /// Returns true if the cells are colored by age.
+    pub fn shows_age(&self) -> bool {
+        *self != ColorMode::Flat
+    }
+
+    /// Returns true if the cells that just died fade out.
+    pub fn shows_trail(&self) -> bool {
+        *self == ColorMode::Trail
+    }
+
+    /// Returns the color mode that follows this one when cycling through them.
+    pub fn next(&self) -> Self {
+        let index = Self::ALL.iter().position(|m| m == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
+impl fmt::Display for ColorMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ColorMode::Flat => "flat",
+            ColorMode::Age => "age",
+            ColorMode::Trail => "trail",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for ColorMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "flat" => Ok(ColorMode::Flat),
+            "age" => Ok(ColorMode::Age),
+            "trail" => Ok(ColorMode::Trail),
+            other => Err(format!("Unknown color mode '{}'", other)),
+        }
+    }
+}
+
+/// Colors the cells go through as they age, evenly spread from age 1 to the
+/// span of the gradient. Older cells keep the last color.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gradient {
+    stops: Vec<Color>,
+    span: u32,
+}
+
+impl Default for Gradient {
+    /// Newborn cells in orange, turning to the black of the stable debris.
+    fn default() -> Self {
+        Self {
+            stops: vec![
+                Color::new(255, 90, 0),
+                Color::new(230, 170, 0),
+                Color::new(40, 140, 90),
+                Color::new(0, 0, 0),
+            ],
+            span: DEFAULT_GRADIENT_SPAN,
+        }
+    }
+}
+
+impl Gradient {
+    /// Creates a gradient through `stops` reaching the last one at age `span`.
+    pub fn new(stops: Vec<Color>, span: u32) -> Result<Self, String> {
+        if stops.is_empty() {
+            return Err("A gradient needs at least one color".to_string());
+        }
+        Ok(Self {
+            stops,
+            span: span.max(1),
+        })
+    }
+
+    /// Returns the colors of the gradient.
+    pub fn get_stops(&self) -> &[Color] {
+        &self.stops
+    }
+
+    /// Returns the age at which cells reach the last color.
+    pub fn get_span(&self) -> u32 {
+        self.span
+    }
+
+    /// Returns the color of a cell alive for `age` generations.
+    pub fn get_color(&self, age: u32) -> Color {
+        let last = self.stops.len() - 1;
+        if last == 0 || self.span <= 1 {
+            return self.stops[last];
+        }
+        let position = (age.clamp(1, self.span) - 1) as f32 / (self.span - 1) as f32 * last as f32;
+        let index = (position as usize).min(last - 1);
+        self.stops[index].lerp(self.stops[index + 1], position - index as f32)
+    }
+}
+
+impl fmt::Display for Gradient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stops: Vec<String> = self.stops.iter().map(Color::to_string).collect();
+        write!(f, "{}", stops.join(","))
+    }
+}
+
+impl FromStr for Gradient {
+    type Err = String;
+
+    /// Parses colors separated by commas, such as `#ff5a00,#000000`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let stops = s
+            .split(',')
+            .map(str::parse)
+            .collect::<Result<Vec<Color>, _>>()?;
+        Gradient::new(stops, DEFAULT_GRADIENT_SPAN)
+    }
+}
+
+/// Number of generations each cell of the grid has been alive, and of the
+/// cells that died recently, the number of generations left in their trail.
+///
+/// Only the words of the grid holding live or fading cells are visited, so
+/// following the ages costs little more than a generation of the grid.
+#[derive(Debug, Clone, Default)]
+pub struct CellAges {
+    ages: Vec<u32>,
+    fades: Vec<u8>,
+    tracked: Grid,
+}
+
+impl CellAges {
+    /// Creates the ages of `grid`, whose live cells are newborn.
+    pub fn new(grid: &Grid) -> Self {
+        let mut ages = Self::default();
+        ages.reset(grid);
+        ages
+    }
+
+    /// Forgets every age and fade: the live cells of `grid` are newborn.
+    pub fn reset(&mut self, grid: &Grid) {
+        let length = grid.get_width() * grid.get_height();
+        self.ages.clear();
+        self.ages.resize(length, 0);
+        self.fades.clear();
+        self.fades.resize(length, 0);
+        self.tracked = Grid::new(grid.get_width(), grid.get_height());
+        self.sync(grid);
+    }
+
+    /// Returns the number of generations the cell `(x, y)` has been alive, 0 if it is dead.
+    pub fn get_age(&self, x: usize, y: usize) -> u32 {
+        self.index(x, y).map_or(0, |index| self.ages[index])
+    }
+
+    /// Returns the number of generations left in the trail of the cell `(x, y)`.
+    pub fn get_fade(&self, x: usize, y: usize) -> u8 {
+        self.index(x, y).map_or(0, |index| self.fades[index])
+    }
+
+    /// Returns the live cells and their age.
+    pub fn live_cells(&self) -> impl Iterator<Item = (usize, usize, u32)> + '_ {
+        self.tracked
+            .live_cells()
+            .map(|(x, y)| (x, y, self.ages[y * self.tracked.get_width() + x]))
+            .filter(|&(_, _, age)| age > 0)
+    }
+
+    /// Returns the cells fading out and the number of generations left in their trail.
+    pub fn fading_cells(&self) -> impl Iterator<Item = (usize, usize, u8)> + '_ {
+        self.tracked
+            .live_cells()
+            .map(|(x, y)| (x, y, self.fades[y * self.tracked.get_width() + x]))
+            .filter(|&(_, _, fade)| fade > 0)
+    }
+
+    fn index(&self, x: usize, y: usize) -> Option<usize> {
+        (x < self.tracked.get_width() && y < self.tracked.get_height())
+            .then(|| y * self.tracked.get_width() + x)
+    }
+
+    fn same_size(&self, grid: &Grid) -> bool {
+        grid.get_width() == self.tracked.get_width()
+            && grid.get_height() == self.tracked.get_height()
+    }
+
+    /// Calls `update` on the age and fade of every cell alive in `grid` or
+    /// followed by the ages, with its state, then updates the cells followed.
+    fn update(&mut self, grid: &Grid, mut update: impl FnMut(&mut u32, &mut u8, bool)) {
+        let width = grid.get_width();
+        for y in 0..grid.get_height() {
+            for (word_index, &alive) in grid.get_row(y).iter().enumerate() {
+                let mut bits = alive | self.tracked.get_row(y)[word_index];
+                while bits != 0 {
+                    let bit = bits.trailing_zeros() as usize;
+                    bits &= bits - 1;
+                    let x = word_index * WORD_BITS + bit;
+                    let index = y * width + x;
+                    update(
+                        &mut self.ages[index],
+                        &mut self.fades[index],
+                        (alive >> bit) & 1 == 1,
+                    );
+                    let followed = self.ages[index] > 0 || self.fades[index] > 0;
+                    self.tracked.set(x, y, followed);
+                }
+            }
+        }
+    }
+
+    /// Ages the cells by one generation now that `grid` holds the next one.
+    /// Cells that died leave a trail of `trail_length` generations.
+    pub fn advance(&mut self, grid: &Grid, trail_length: u8) {
+        if !self.same_size(grid) {
+            self.reset(grid);
+            return;
+        }
+        self.update(grid, |age, fade, alive| {
+            if alive {
+                *age = age.saturating_add(1);
+                *fade = 0;
+            } else if *age > 0 {
+                *age = 0;
+                *fade = trail_length;
+            } else {
+                *fade = fade.saturating_sub(1);
+            }
+        });
+    }
+
+    /// Follows the changes of `grid` made without computing a generation,
+    /// such as edits: new cells are newborn, removed cells leave no trail.
+    pub fn sync(&mut self, grid: &Grid) {
+        if !self.same_size(grid) {
+            self.reset(grid);
+            return;
+        }
+        self.update(grid, |age, fade, alive| {
+            if alive && *age == 0 {
+                *age = 1;
+                *fade = 0;
+            } else if !alive {
+                *age = 0;
+            }
+        });
+    }
+}
@@ -0,0 +1,98 @@
+use crate::frontend::Point;
+
+/// Closest zoom, in pixels per cell.
+pub const MAX_ZOOM: f32 = 256.0;
+
+/// Farthest zoom, in pixels per cell.
+pub const MIN_ZOOM: f32 = 0.05;
+
+/// Zoom under which the grid lines are not drawn, as they would hide the cells.
+pub const MIN_GRID_LINE_ZOOM: f32 = 4.0;
+
+/// Factor applied to the zoom by one step of the mouse wheel or the zoom keys.
+pub const ZOOM_STEP: f32 = 1.25;
+
+/// Part of the view left around a pattern fitted to it, on each side.
+const FIT_MARGIN: f32 = 0.05;
+
+/// View over the grid: the cell coordinates shown at the top-left corner of
+/// the window and the size of a cell in pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Camera {
+    x: f32,
+    y: f32,
+    zoom: f32,
+}
+
+impl Camera {
+    /// Creates a camera showing cell `(0, 0)` at the top-left corner of the
+    /// window, with cells of `zoom` pixels.
+    pub fn new(zoom: f32) -> Self {
+        Self {
+            x: 0.0,
+            y: 0.0,
+            zoom,
+        }
+    }
+
+    /// Returns the column shown at the left edge of the window.
+    pub fn get_x(&self) -> f32 {
+        self.x
+    }
+
+    /// Returns the row shown at the top edge of the window.
+    pub fn get_y(&self) -> f32 {
+        self.y
+    }
+
+    /// Returns the size of a cell, in pixels.
+    pub fn get_zoom(&self) -> f32 {
+        self.zoom
+    }
+
+    /// Returns the cell under the window point `(x, y)`.
+    pub fn screen_to_cell(&self, x: f32, y: f32) -> (i64, i64) {
+        (
+            (self.x + x / self.zoom).floor() as i64,
+            (self.y + y / self.zoom).floor() as i64,
+        )
+    }
+
+    /// Returns the window point of the top-left corner of cell `(x, y)`.
+    pub fn cell_to_screen(&self, x: f32, y: f32) -> Point {
+        Point::new((x - self.x) * self.zoom, (y - self.y) * self.zoom)
+    }
+
+    /// Moves the view by `(dx, dy)` pixels; the cells move the other way.
+    pub fn pan(&mut self, dx: f32, dy: f32) {
+        self.x += dx / self.zoom;
+        self.y += dy / self.zoom;
+    }
+
+    /// Multiplies the zoom by `factor`, within `MIN_ZOOM` and `MAX_ZOOM`,
+    /// keeping the cell under the window point `(x, y)` in place.
+    pub fn zoom_at(&mut self, factor: f32, x: f32, y: f32) {
+        let zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+        self.x += x / self.zoom - x / zoom;
+        self.y += y / self.zoom - y / zoom;
+        self.zoom = zoom;
+    }
+
+    /// Centers the cells from `(min_x, min_y)` to `(max_x, max_y)` included in a
+    /// view of `view_width` by `view_height` pixels, zoomed as close as they fit.
+    pub fn fit(
+        &mut self,
+        (min_x, min_y, max_x, max_y): (f32, f32, f32, f32),
+        view_width: u32,
+        view_height: u32,
+    ) {
+        let width = max_x - min_x + 1.0;
+        let height = max_y - min_y + 1.0;
+        let usable = 1.0 - 2.0 * FIT_MARGIN;
+        self.zoom = (view_width as f32 * usable / width)
+            .min(view_height as f32 * usable / height)
+            .clamp(MIN_ZOOM, MAX_ZOOM);
+        self.x = min_x + width / 2.0 - view_width as f32 / self.zoom / 2.0;
+        self.y = min_y + height / 2.0 - view_height as f32 / self.zoom / 2.0;
+    }
+}
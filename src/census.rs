@@ -0,0 +1,487 @@
+use crate::cycle::{CycleDetector, CycleKind};
+use crate::engine::Universe;
+use crate::rule::Rule;
+use crate::sparse::SparseLife;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Number of generations the cells are followed to group them into objects,
+/// so that the cells of an oscillator touching in another phase stay together.
+pub const CENSUS_PHASES: u64 = 4;
+
+/// Largest number of generations an object is followed alone to find its period.
+pub const MAX_OBJECT_GENERATIONS: u64 = 1024;
+
+/// Largest population an object followed alone can reach before it is given up.
+pub const MAX_OBJECT_POPULATION: u64 = 4096;
+
+/// Largest width and height of the phases of an object given an apgcode.
+/// Larger objects are only named by kind and population or period.
+pub const MAX_OBJECT_SIZE: i64 = 40;
+
+/// Largest distance between the cells of two pathological objects tried
+/// together, such as the separate quadrants of a pulsar in some phases.
+pub const MERGE_DISTANCE: i64 = 3;
+
+/// Name of the objects that do not settle into a cycle on their own, such as
+/// the parts of a soup that has not settled yet.
+pub const PATHOLOGICAL: &str = "PATHOLOGICAL";
+
+/// Format a census is written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum CensusFormat {
+    /// One line per object type: the apgcode and the count.
+    #[default]
+    Text,
+    /// A JSON object with the rule, the number of objects and the counts.
+    Json,
+}
+
+impl CensusFormat {
+    /// All census formats.
+    pub const ALL: [CensusFormat; 2] = [CensusFormat::Text, CensusFormat::Json];
+
+    /// Returns the format matching the extension of `path`, if any.
+    pub fn from_path(path: &str) -> Option<Self> {
+        let extension = Path::new(path).extension()?.to_str()?;
+        match extension.to_ascii_lowercase().as_str() {
+            "txt" => Some(CensusFormat::Text),
+            "json" => Some(CensusFormat::Json),
+            _ => None,
+        }
+    }
+
+    /// Returns the usual file extension of the format.
+    pub fn get_extension(&self) -> &'static str {
+        match self {
+            CensusFormat::Text => "txt",
+            CensusFormat::Json => "json",
+        }
+    }
+}
+
+impl fmt::Display for CensusFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            CensusFormat::Text => "text",
+            CensusFormat::Json => "json",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for CensusFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "text" | "txt" => Ok(CensusFormat::Text),
+            "json" => Ok(CensusFormat::Json),
+            other => Err(format!("Unknown census format '{}'", other)),
+        }
+    }
+}
+
+/// Number of objects of each type found in a universe, by apgcode.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Census {
+    rule: Rule,
+    counts: BTreeMap<String, u64>,
+}
+
+impl Census {
+    /// Creates an empty census of objects of `rule`.
+    pub fn new(rule: Rule) -> Self {
+        Self {
+            rule,
+            counts: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the rule the objects were classified with.
+    pub fn get_rule(&self) -> Rule {
+        self.rule
+    }
+
+    /// Returns the number of objects of type `apgcode`.
+    pub fn get_count(&self, apgcode: &str) -> u64 {
+        self.counts.get(apgcode).copied().unwrap_or(0)
+    }
+
+    /// Returns the number of objects of every type.
+    pub fn get_total(&self) -> u64 {
+        self.counts.values().sum()
+    }
+
+    /// Returns the apgcode and count of each object type, the most common first.
+    pub fn get_objects(&self) -> Vec<(&str, u64)> {
+        let mut objects: Vec<(&str, u64)> = self
+            .counts
+            .iter()
+            .map(|(apgcode, &count)| (apgcode.as_str(), count))
+            .collect();
+        objects.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        objects
+    }
+
+    /// Counts `count` more objects of type `apgcode`.
+    pub fn add(&mut self, apgcode: &str, count: u64) {
+        *self.counts.entry(apgcode.to_string()).or_insert(0) += count;
+    }
+
+    /// Adds the objects counted in `other`.
+    pub fn merge(&mut self, other: &Census) {
+        for (apgcode, &count) in &other.counts {
+            self.add(apgcode, count);
+        }
+    }
+
+    /// Writes the census in `format`.
+    pub fn write(&self, format: CensusFormat) -> String {
+        match format {
+            CensusFormat::Text => self.to_string(),
+            CensusFormat::Json => self.to_json(),
+        }
+    }
+
+    /// Writes the census as a JSON object, the most common objects first.
+    pub fn to_json(&self) -> String {
+        let objects: Vec<String> = self
+            .get_objects()
+            .iter()
+            .map(|(apgcode, count)| format!("    {}: {}", json_string(apgcode), count))
+            .collect();
+        let census = if objects.is_empty() {
+            "{}".to_string()
+        } else {
+            format!("{{\n{}\n  }}", objects.join(",\n"))
+        };
+        format!(
+            "{{\n  \"rule\": {},\n  \"objects\": {},\n  \"census\": {}\n}}\n",
+            json_string(&self.rule.to_string()),
+            self.get_total(),
+            census
+        )
+    }
+}
+
+impl fmt::Display for Census {
+    /// Writes the rule, the number of objects, then one line per object type,
+    /// the most common first.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "rule: {}", self.rule)?;
+        writeln!(f, "objects: {}", self.get_total())?;
+        for (apgcode, count) in self.get_objects() {
+            writeln!(f, "{} {}", apgcode, count)?;
+        }
+        Ok(())
+    }
+}
+
+/// Quotes `s` as a JSON string.
+fn json_string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Splits the live `cells` of a settled universe into objects and counts
+/// them by apgcode, as apgsearch does for the ash of a soup. The objects are
+/// the ones of `find_objects`.
+pub fn take_census(cells: impl IntoIterator<Item = (i64, i64)>, rule: Rule) -> Census {
+    let mut census = Census::new(rule);
+    for (_, code) in find_objects(cells, rule) {
+        census.add(&code, 1);
+    }
+    census
+}
+
+/// The live cells of an object, with its apgcode.
+pub type Object = (Vec<(i64, i64)>, String);
+
+/// Splits the live `cells` of a settled universe into objects, returned with
+/// their apgcode.
+///
+/// Objects are the groups of cells connected through their 8 neighbors in
+/// any of the next `CENSUS_PHASES` generations, and are classified alone on
+/// an unbounded plane by `classify`. Constellations whose parts never touch,
+/// such as the four blinkers of a traffic light, are counted part by part.
+///
+/// Pathological objects within `MERGE_DISTANCE` cells of each other are
+/// then classified together, and the ones left with the objects close to
+/// them, so that objects whose parts only live together are named whole.
+pub fn find_objects(cells: impl IntoIterator<Item = (i64, i64)>, rule: Rule) -> Vec<Object> {
+    let mut universe = SparseLife::new(rule);
+    for (x, y) in cells {
+        universe.set(x, y, true);
+    }
+    let cells = universe.live_cells();
+
+    let mut phases: HashSet<(i64, i64)> = cells.iter().copied().collect();
+    let mut future = universe.clone();
+    for _ in 1..CENSUS_PHASES {
+        future.next_generation();
+        phases.extend(future.live_cells());
+    }
+
+    let mut objects = Vec::new();
+    let mut visited: HashSet<(i64, i64)> = HashSet::with_capacity(phases.len());
+    for &cell in &cells {
+        if !visited.insert(cell) {
+            continue;
+        }
+        let mut object = Vec::new();
+        let mut stack = vec![cell];
+        while let Some((x, y)) = stack.pop() {
+            if universe.get(x, y) {
+                object.push((x, y));
+            }
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    let neighbor = (x + dx, y + dy);
+                    if phases.contains(&neighbor) && visited.insert(neighbor) {
+                        stack.push(neighbor);
+                    }
+                }
+            }
+        }
+        objects.push(object);
+    }
+
+    let mut codes: Vec<String> = objects
+        .iter()
+        .map(|object| classify(object, rule))
+        .collect();
+    merge_pathological(&mut objects, &mut codes, rule, true);
+    merge_pathological(&mut objects, &mut codes, rule, false);
+    objects
+        .into_iter()
+        .zip(codes)
+        .filter(|(object, _)| !object.is_empty())
+        .collect()
+}
+
+/// Classifies together the groups of objects within `MERGE_DISTANCE` cells
+/// of a pathological one, and only of pathological ones if `only_pathological`.
+/// The groups that settle replace their objects, whose cells are emptied.
+fn merge_pathological(
+    objects: &mut [Vec<(i64, i64)>],
+    codes: &mut [String],
+    rule: Rule,
+    only_pathological: bool,
+) {
+    let pathological = |code: &String| code == PATHOLOGICAL;
+    let boxes: Vec<_> = objects
+        .iter()
+        .map(|object| (!object.is_empty()).then(|| bounds(object)))
+        .collect();
+    // each object points to an object of its group, the first one pointing to itself
+    let mut groups: Vec<usize> = (0..objects.len()).collect();
+    fn root(groups: &mut [usize], mut index: usize) -> usize {
+        while groups[index] != index {
+            groups[index] = groups[groups[index]];
+            index = groups[index];
+        }
+        index
+    }
+    for i in (0..objects.len()).filter(|&i| pathological(&codes[i])) {
+        for j in 0..objects.len() {
+            if i == j || (only_pathological && !pathological(&codes[j])) {
+                continue;
+            }
+            let (Some(a), Some(b)) = (boxes[i], boxes[j]) else {
+                continue;
+            };
+            let apart = a.0 - b.2 > MERGE_DISTANCE
+                || b.0 - a.2 > MERGE_DISTANCE
+                || a.1 - b.3 > MERGE_DISTANCE
+                || b.1 - a.3 > MERGE_DISTANCE;
+            if !apart && are_close(&objects[i], &objects[j]) {
+                let (root_i, root_j) = (root(&mut groups, i), root(&mut groups, j));
+                groups[root_i.max(root_j)] = root_i.min(root_j);
+            }
+        }
+    }
+
+    let mut members: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for index in 0..objects.len() {
+        let group = root(&mut groups, index);
+        members.entry(group).or_default().push(index);
+    }
+    for (first, indexes) in members.into_iter().filter(|(_, indexes)| indexes.len() > 1) {
+        let cells: Vec<(i64, i64)> = indexes
+            .iter()
+            .flat_map(|&index| objects[index].iter().copied())
+            .collect();
+        let code = classify(&cells, rule);
+        if code != PATHOLOGICAL {
+            for &index in &indexes {
+                objects[index].clear();
+            }
+            objects[first] = cells;
+            codes[first] = code;
+        }
+    }
+}
+
+/// Returns true if a cell of `a` is within `MERGE_DISTANCE` cells of a cell of `b`.
+fn are_close(a: &[(i64, i64)], b: &[(i64, i64)]) -> bool {
+    let b: HashSet<(i64, i64)> = b.iter().copied().collect();
+    a.iter().any(|&(x, y)| {
+        (-MERGE_DISTANCE..=MERGE_DISTANCE)
+            .any(|dy| (-MERGE_DISTANCE..=MERGE_DISTANCE).any(|dx| b.contains(&(x + dx, y + dy))))
+    })
+}
+
+/// Returns the apgcode of the object made of `cells` under `rule`, followed
+/// alone on an unbounded plane: `xs<population>_` for still lifes,
+/// `xp<period>_` for oscillators and `xq<period>_` for spaceships, followed by
+/// the extended Wechsler code of the object, the shortest then smallest one
+/// of all its phases and orientations.
+///
+/// Objects that are not periodic from the start, die or grow beyond
+/// `MAX_OBJECT_POPULATION` are `PATHOLOGICAL`. Objects larger than
+/// `MAX_OBJECT_SIZE` are `ov_s<population>`, `ov_p<period>` or `ov_q<period>`.
+pub fn classify(cells: &[(i64, i64)], rule: Rule) -> String {
+    let mut universe = SparseLife::new(rule);
+    for &(x, y) in cells {
+        universe.set(x, y, true);
+    }
+    let mut detector = CycleDetector::new();
+    let mut phases = Vec::new();
+    for generation in 0..=MAX_OBJECT_GENERATIONS {
+        if universe.population() > MAX_OBJECT_POPULATION {
+            break;
+        }
+        let live_cells = universe.live_cells();
+        if let Some(cycle) = detector.record(
+            generation,
+            universe.bounding_box(),
+            live_cells.iter().copied(),
+        ) {
+            let prefix = match cycle.get_kind() {
+                _ if cycle.get_start() != 0 => break,
+                CycleKind::Empty => break,
+                CycleKind::StillLife => "s",
+                CycleKind::Oscillator => "p",
+                CycleKind::Spaceship => "q",
+            };
+            let size = if prefix == "s" {
+                cells.len() as u64
+            } else {
+                cycle.get_period()
+            };
+            let oversized = phases.iter().any(|phase: &Vec<(i64, i64)>| {
+                let (min_x, min_y, max_x, max_y) = bounds(phase);
+                max_x - min_x >= MAX_OBJECT_SIZE || max_y - min_y >= MAX_OBJECT_SIZE
+            });
+            if oversized {
+                return format!("ov_{}{}", prefix, size);
+            }
+            let code = phases
+                .iter()
+                .flat_map(|phase| orientations(phase))
+                .map(|orientation| wechsler(&orientation))
+                .min_by(|a, b| a.len().cmp(&b.len()).then(a.cmp(b)))
+                .unwrap_or_default();
+            return format!("x{}{}_{}", prefix, size, code);
+        }
+        phases.push(live_cells);
+        universe.next_generation();
+    }
+    PATHOLOGICAL.to_string()
+}
+
+/// Returns `(min_x, min_y, max_x, max_y)` of non-empty `cells`.
+fn bounds(cells: &[(i64, i64)]) -> (i64, i64, i64, i64) {
+    cells.iter().fold(
+        (i64::MAX, i64::MAX, i64::MIN, i64::MIN),
+        |(min_x, min_y, max_x, max_y), &(x, y)| {
+            (min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y))
+        },
+    )
+}
+
+/// Returns `cells` in each of the 8 orientations given by the rotations and
+/// reflections of the plane.
+fn orientations(cells: &[(i64, i64)]) -> Vec<Vec<(i64, i64)>> {
+    // each orientation swaps the axes or not, then mirrors each axis or not
+    (0..8)
+        .map(|orientation: u8| {
+            let (swap, flip_x, flip_y) = (orientation & 4 != 0, orientation & 1, orientation & 2);
+            cells
+                .iter()
+                .map(|&(x, y)| {
+                    let (x, y) = if swap { (y, x) } else { (x, y) };
+                    (
+                        if flip_x != 0 { -x } else { x },
+                        if flip_y != 0 { -y } else { y },
+                    )
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Returns the extended Wechsler code of `cells`, wherever they are.
+///
+/// The cells are cut into strips of 5 rows separated by `z`. Each column of
+/// a strip is a character from `0` to `v` whose bits are its cells, the top
+/// one first. Runs of empty columns are shortened to `w` (2), `x` (3) and `y`
+/// followed by a character for 4 to 39, and are left out at the end of a strip.
+pub fn wechsler(cells: &[(i64, i64)]) -> String {
+    const DIGITS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+    if cells.is_empty() {
+        return String::new();
+    }
+    let (min_x, min_y, max_x, max_y) = bounds(cells);
+    let (width, height) = ((max_x - min_x + 1) as usize, (max_y - min_y + 1) as usize);
+    let strip_count = height.div_ceil(5);
+    let mut columns = vec![0u8; width * strip_count];
+    for &(x, y) in cells {
+        let (x, y) = ((x - min_x) as usize, (y - min_y) as usize);
+        columns[(y / 5) * width + x] |= 1 << (y % 5);
+    }
+
+    let mut code = String::new();
+    for (strip, strip_columns) in columns.chunks(width).enumerate() {
+        if strip > 0 {
+            code.push('z');
+        }
+        let mut zeros = 0usize;
+        for &column in strip_columns {
+            if column == 0 {
+                zeros += 1;
+                continue;
+            }
+            while zeros > 39 {
+                code.push_str("yz");
+                zeros -= 39;
+            }
+            match zeros {
+                0 => {}
+                1 => code.push('0'),
+                2 => code.push('w'),
+                3 => code.push('x'),
+                _ => {
+                    code.push('y');
+                    code.push(DIGITS[zeros - 4] as char);
+                }
+            }
+            zeros = 0;
+            code.push(DIGITS[column as usize] as char);
+        }
+    }
+    code
+}
@@ -0,0 +1,426 @@
+use crate::age::{ColorMode, Gradient};
+use crate::engine::{center_viewport, Engine};
+use crate::game::Game;
+use crate::grid::Grid;
+use crate::hashlife::HashLife;
+use crate::headless::HeadlessOptions;
+use crate::pattern::{load_macrocell_file, load_pattern_file};
+use crate::rule::Rule;
+use crate::scheduler::parse_speed;
+use crate::search::SearchOptions;
+use crate::topology::Topology;
+use crate::utils::{fill_random, format_density};
+use std::str::FromStr;
+
+/// Help text printed by `rust_of_life help`.
+pub const USAGE: &str = "Usage: rust_of_life [run] [OPTIONS] [PATTERN]
+       rust_of_life headless [OPTIONS] [HEADLESS OPTIONS] [PATTERN]
+       rust_of_life search [OPTIONS] [SEARCH OPTIONS]
+       rust_of_life help
+       rust_of_life version
+
+Commands:
+  run                    Open the game window (default)
+  headless               Run without a window and write the final state
+  search                 Run many random soups in parallel and count what they settle into
+  help                   Print this help
+  version                Print the version
+
+Options:
+  -s, --size <CELLS>     Number of cells on each side of the grid [default: 100]
+      --speed <GEN/S>    Generations per second, from 0.1 to max [default: 10]
+  -r, --rule <RULE>      Birth/survival rule, such as B3/S23 or 23/3 [default: B3/S23]
+  -t, --topology <NAME>  dead, torus, klein, cross-surface or mirror [default: dead]
+  -e, --engine <NAME>    dense, hashlife or sparse [default: dense]
+      --step <N>         Advance the HashLife engine 2^N generations per step [default: 0]
+      --step-count <N>   Number of steps computed by the G key and rewound by B [default: 10]
+      --history <N>      Number of generations and edits that can be undone, 0 to disable
+                         [default: 1000]
+  -j, --threads <N>      Number of threads used by the dense engine [default: all cores]
+  -p, --pattern <FILE>   Pattern file loaded at startup (RLE, .cells, Life 1.05/1.06, .mc)
+      --font <FILE>      TTF font used for the HUD [default: ./assets/Roboto-Medium.ttf]
+      --width <PIXELS>   Width of the window [default: 1000]
+      --height <PIXELS>  Height of the window [default: 1000]
+      --seed <N>         Start from a random soup generated from this seed, also used by
+                         the random fill key [default: a new seed for each fill]
+      --density <PERCENT>
+                         Part of the cells alive in a random soup [default: 50]
+      --colors <MODE>    flat, age (cells colored by age) or trail (age and a fade trail
+                         behind the cells that die) [default: trail]
+      --gradient <COLORS>
+                         Colors of the cells by age, such as #ff5a00,#000000
+                         [default: #ff5a00,#e6aa00,#288c5a,#000000]
+      --trail <N>        Number of generations the cells that die stay visible [default: 6]
+      --stats-length <N> Number of generations whose statistics are kept for the X key
+                         [default: 100000]
+      --graph-length <N> Number of generations shown in the population graph, 0 hiding
+                         it [default: 500]
+  -h, --help             Print this help
+  -V, --version          Print the version
+
+Headless options:
+  -n, --generations <N>  Number of generations to compute, or the limit of --until-stable
+      --until-stable     Stop as soon as the universe settles into a cycle (a still life, an
+                         oscillator, or a spaceship on the unbounded engines)
+  -o, --output <FILE>    File the final state is written to [default: stdout]
+  -f, --format <NAME>    rle, plaintext, life105, life106 or macrocell [default: from the
+                         extension of the output file, or rle]
+      --stats <FILE>     File the statistics are written to [default: stdout, or stderr
+                         when the final state goes to stdout]
+      --census <FILE>    File the objects of the final state are counted in, by apgcode
+      --census-format <NAME>
+                         text or json [default: from the extension of the census file,
+                         or text]
+      --generation-stats <FILE>
+                         File the population, births, deaths, bounding box, density and
+                         step time of every generation are written to
+      --generation-stats-format <NAME>
+                         csv or json [default: from the extension of the file, or csv]
+
+Search options (-s is the size of the soups [default: 16], --seed the first seed
+[default: random], -j the number of threads):
+      --soups <N>        Number of soups, of seeds following each other [default: 1000]
+  -n, --generations <N>  Number of generations after which a soup is given up
+                         [default: 100000]
+      --top <N>          Number of longest-lived soups reported [default: 10]
+  -o, --output <FILE>    File the report is written to [default: stdout]
+      --census <FILE>    File the objects of every soup are counted in, by apgcode
+      --census-format <NAME>
+                         text or json [default: from the extension of the census file,
+                         or text]";
+
+/// Settings given on the command line. Settings that are not given keep the
+/// defaults of `Game::new`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Options {
+    size_grid: Option<u32>,
+    max_iteration_per_second: Option<f64>,
+    rule: Option<Rule>,
+    topology: Option<Topology>,
+    engine: Option<Engine>,
+    step_log2: Option<u8>,
+    step_count: Option<u64>,
+    history_length: Option<usize>,
+    thread_count: Option<usize>,
+    pattern_path: Option<String>,
+    font_path: Option<String>,
+    window_width: Option<u32>,
+    window_height: Option<u32>,
+    seed: Option<u64>,
+    density: Option<f64>,
+    color_mode: Option<ColorMode>,
+    gradient: Option<Gradient>,
+    trail_length: Option<u8>,
+    stats_length: Option<usize>,
+    graph_length: Option<usize>,
+}
+
+impl Options {
+    /// Returns the grid size given on the command line.
+    pub fn get_size_grid(&self) -> Option<u32> {
+        self.size_grid
+    }
+
+    /// Returns the rule given on the command line.
+    pub fn get_rule(&self) -> Option<Rule> {
+        self.rule
+    }
+
+    /// Returns the path of the pattern file to load at startup.
+    pub fn get_pattern_path(&self) -> Option<&str> {
+        self.pattern_path.as_deref()
+    }
+
+    /// Reads the pattern file straight into a HashLife universe if it is a
+    /// macrocell file and the engine is HashLife, keeping its generation, so
+    /// that patterns larger than the grid are not cropped, and centers the
+    /// viewport on it. Returns `None` otherwise, the pattern then going into
+    /// the grid through `load_start_grid`.
+    pub fn load_start_universe(&self, game_info: &mut Game) -> Result<Option<HashLife>, String> {
+        let Some(path) = self
+            .get_pattern_path()
+            .filter(|_| game_info.get_engine() == Engine::HashLife)
+        else {
+            return Ok(None);
+        };
+        let Some(mut universe) = load_macrocell_file(path, game_info.get_rule())? else {
+            return Ok(None);
+        };
+        if let Some(rule) = self.rule {
+            universe.set_rule(rule);
+        }
+        game_info.get_engine().check_rule(&universe.get_rule())?;
+        game_info.set_rule(universe.get_rule());
+        game_info.set_iteration(universe.get_generation());
+        let size = game_info.get_size_grid() as usize;
+        center_viewport(game_info, &universe, size, size);
+        Ok(Some(universe))
+    }
+
+    /// Fills `grid` with the pattern file centered on it, or with a random soup
+    /// if a seed was given. The rule of the pattern is used unless one was
+    /// given on the command line, and must be supported by the engine. A
+    /// warning tells how many cells were dropped if the pattern does not fit.
+    pub fn load_start_grid(&self, grid: &mut Grid, game_info: &mut Game) -> Result<(), String> {
+        if let Some(path) = self.get_pattern_path() {
+            let pattern = load_pattern_file(path)?;
+            let dropped = pattern.place_centered(grid);
+            if dropped > 0 {
+                eprintln!("{}", pattern.get_dropped_warning(grid, dropped));
+            }
+            if let (None, Some(rule)) = (self.rule, pattern.get_rule()) {
+                game_info.get_engine().check_rule(&rule)?;
+                game_info.set_rule(rule);
+            }
+        } else if let Some(seed) = game_info.get_seed() {
+            fill_random(grid, game_info.get_density(), seed);
+            game_info.set_last_seed(Some(seed));
+            eprintln!(
+                "Random soup seed: {} (density {})",
+                seed,
+                format_density(game_info.get_density())
+            );
+        }
+        Ok(())
+    }
+
+    /// Copies the settings that were given into `game_info`.
+    pub fn apply(&self, game_info: &mut Game) {
+        if let Some(size_grid) = self.size_grid {
+            game_info.set_size_grid(size_grid);
+        }
+        if let Some(max_iteration_per_second) = self.max_iteration_per_second {
+            game_info.set_max_iteration_per_second(max_iteration_per_second);
+        }
+        if let Some(rule) = self.rule {
+            game_info.set_rule(rule);
+        }
+        if let Some(topology) = self.topology {
+            game_info.set_topology(topology);
+        }
+        if let Some(engine) = self.engine {
+            game_info.set_engine(engine);
+        }
+        if let Some(step_log2) = self.step_log2 {
+            game_info.set_step_log2(step_log2);
+        }
+        if let Some(step_count) = self.step_count {
+            game_info.set_step_count(step_count);
+        }
+        if let Some(history_length) = self.history_length {
+            game_info.set_history_length(history_length);
+        }
+        if let Some(thread_count) = self.thread_count {
+            game_info.set_thread_count(thread_count);
+        }
+        if let Some(font_path) = &self.font_path {
+            game_info.set_font_path(font_path.clone());
+        }
+        if let Some(window_width) = self.window_width {
+            game_info.set_window_width(window_width);
+        }
+        if let Some(window_height) = self.window_height {
+            game_info.set_window_height(window_height);
+        }
+        if self.seed.is_some() {
+            game_info.set_seed(self.seed);
+        }
+        if let Some(density) = self.density {
+            game_info.set_density(density);
+        }
+        if let Some(color_mode) = self.color_mode {
+            game_info.set_color_mode(color_mode);
+        }
+        if let Some(gradient) = &self.gradient {
+            game_info.set_gradient(gradient.clone());
+        }
+        if let Some(trail_length) = self.trail_length {
+            game_info.set_trail_length(trail_length);
+        }
+        if let Some(stats_length) = self.stats_length {
+            game_info.set_stats_length(stats_length);
+        }
+        if let Some(graph_length) = self.graph_length {
+            game_info.set_graph_length(graph_length);
+        }
+    }
+}
+
+/// Action requested on the command line.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Open the game window with the given settings.
+    Run(Options),
+    /// Run the simulation without a window.
+    Headless(Options, HeadlessOptions),
+    /// Run a batch of random soups without a window.
+    Search(Options, SearchOptions),
+    /// Print the help.
+    Help,
+    /// Print the version.
+    Version,
+}
+
+fn parse_value<T: FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("Invalid value '{}' for {}", value, flag))
+}
+
+/// Parses a value that must be at least 1.
+fn parse_count<T: FromStr + Default + PartialOrd>(flag: &str, value: &str) -> Result<T, String> {
+    let count: T = parse_value(flag, value)?;
+    if count <= T::default() {
+        return Err(format!("{} must be at least 1", flag));
+    }
+    Ok(count)
+}
+
+/// Parses a percentage from 0 to 100 into a fraction from 0 to 1.
+fn parse_percent(flag: &str, value: &str) -> Result<f64, String> {
+    let percent: f64 = parse_value(flag, value.trim_end_matches('%'))?;
+    if !(0.0..=100.0).contains(&percent) {
+        return Err(format!("{} must be between 0 and 100", flag));
+    }
+    Ok(percent / 100.0)
+}
+
+/// Parses the arguments of the program, without the program name.
+///
+/// Flags take their value either as the next argument or after a `=`
+/// (`--rule B36/S23` or `--rule=B36/S23`). A lone argument that is not a flag
+/// is the pattern file to load.
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Command, String> {
+    let mut args = args.into_iter().peekable();
+    let mut headless = None;
+    let mut search = None;
+    match args.peek().map(String::as_str) {
+        Some("help") => return Ok(Command::Help),
+        Some("version") => return Ok(Command::Version),
+        Some("run") => {
+            args.next();
+        }
+        Some("headless") => {
+            args.next();
+            headless = Some(HeadlessOptions::default());
+        }
+        Some("search") => {
+            args.next();
+            search = Some(SearchOptions::default());
+        }
+        _ => {}
+    }
+
+    let mut options = Options::default();
+    while let Some(arg) = args.next() {
+        if !arg.starts_with('-') {
+            if options.pattern_path.is_some() {
+                return Err(format!("Unexpected argument '{}'", arg));
+            }
+            options.pattern_path = Some(arg);
+            continue;
+        }
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+            None => (arg, None),
+        };
+        match flag.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-V" | "--version" => return Ok(Command::Version),
+            "--until-stable" => {
+                headless
+                    .as_mut()
+                    .ok_or_else(|| format!("{} is only available in headless mode", flag))?
+                    .set_until_stable(true);
+                continue;
+            }
+            _ => {}
+        }
+        let value = match inline_value.or_else(|| args.next()) {
+            Some(value) => value,
+            None => return Err(format!("Missing value for {}", flag)),
+        };
+        let flag = flag.as_str();
+        match flag {
+            "-s" | "--size" => options.size_grid = Some(parse_count(flag, &value)?),
+            "--speed" => options.max_iteration_per_second = Some(parse_speed(&value)?),
+            "-r" | "--rule" => options.rule = Some(value.parse()?),
+            "-t" | "--topology" => options.topology = Some(value.parse()?),
+            "-e" | "--engine" => options.engine = Some(value.parse()?),
+            "--step" => options.step_log2 = Some(parse_value(flag, &value)?),
+            "--step-count" => options.step_count = Some(parse_count(flag, &value)?),
+            "--history" => options.history_length = Some(parse_value(flag, &value)?),
+            "-j" | "--threads" => options.thread_count = Some(parse_count(flag, &value)?),
+            "-p" | "--pattern" => options.pattern_path = Some(value),
+            "--font" => options.font_path = Some(value),
+            "--width" => options.window_width = Some(parse_count(flag, &value)?),
+            "--height" => options.window_height = Some(parse_count(flag, &value)?),
+            "--seed" => options.seed = Some(parse_value(flag, &value)?),
+            "--density" => options.density = Some(parse_percent(flag, &value)?),
+            "--colors" => options.color_mode = Some(value.parse()?),
+            "--gradient" => options.gradient = Some(value.parse()?),
+            "--trail" => options.trail_length = Some(parse_value(flag, &value)?),
+            "--stats-length" => options.stats_length = Some(parse_count(flag, &value)?),
+            "--graph-length" => options.graph_length = Some(parse_value(flag, &value)?),
+            "--soups" | "--top" => {
+                let search = search
+                    .as_mut()
+                    .ok_or_else(|| format!("{} is only available in search mode", flag))?;
+                match flag {
+                    "--soups" => search.set_soups(parse_count(flag, &value)?),
+                    _ => search.set_top(parse_value(flag, &value)?),
+                }
+            }
+            "-n"
+            | "--generations"
+            | "-o"
+            | "--output"
+            | "-f"
+            | "--format"
+            | "--stats"
+            | "--census"
+            | "--census-format"
+            | "--generation-stats"
+            | "--generation-stats-format" => match (headless.as_mut(), search.as_mut()) {
+                (Some(headless), _) => match flag {
+                    "-n" | "--generations" => {
+                        headless.set_generations(Some(parse_value(flag, &value)?))
+                    }
+                    "-o" | "--output" => headless.set_output_path(Some(value)),
+                    "-f" | "--format" => headless.set_format(Some(value.parse()?)),
+                    "--census" => headless.set_census_path(Some(value)),
+                    "--census-format" => headless.set_census_format(Some(value.parse()?)),
+                    "--generation-stats" => headless.set_generation_stats_path(Some(value)),
+                    "--generation-stats-format" => {
+                        headless.set_generation_stats_format(Some(value.parse()?))
+                    }
+                    _ => headless.set_stats_path(Some(value)),
+                },
+                (None, Some(search)) => match flag {
+                    "-n" | "--generations" => {
+                        search.set_generations(Some(parse_value(flag, &value)?))
+                    }
+                    "-o" | "--output" => search.set_output_path(Some(value)),
+                    "--census" => search.set_census_path(Some(value)),
+                    "--census-format" => search.set_census_format(Some(value.parse()?)),
+                    _ => return Err(format!("{} is only available in headless mode", flag)),
+                },
+                (None, None) => {
+                    return Err(format!(
+                        "{} is only available in headless or search mode",
+                        flag
+                    ))
+                }
+            },
+            _ => return Err(format!("Unknown option '{}'", flag)),
+        }
+    }
+    if let (Some(engine), Some(rule)) = (options.engine, options.rule) {
+        engine.check_rule(&rule)?;
+    }
+    Ok(match (headless, search) {
+        (Some(headless), _) => Command::Headless(options, headless),
+        (None, Some(search)) => Command::Search(options, search),
+        (None, None) => Command::Run(options),
+    })
+}
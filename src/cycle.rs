@@ -0,0 +1,252 @@
+use crate::engine::Universe;
+use crate::grid::Grid;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+/// Largest population followed by a cycle detector. Larger universes are not
+/// hashed, as hashing them every generation would cost more than stepping them.
+pub const MAX_CYCLE_POPULATION: u64 = 1 << 20;
+
+/// Largest number of generations remembered by a cycle detector, after which
+/// it starts over. Longer periods are not detected.
+pub const MAX_CYCLE_STATES: usize = 1 << 20;
+
+/// What a universe settled into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CycleKind {
+    /// Every cell died.
+    Empty,
+    /// The cells no longer change.
+    StillLife,
+    /// The cells come back in place every period.
+    Oscillator,
+    /// The cells come back moved by the displacement every period.
+    Spaceship,
+}
+
+impl fmt::Display for CycleKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            CycleKind::Empty => "empty",
+            CycleKind::StillLife => "still life",
+            CycleKind::Oscillator => "oscillator",
+            CycleKind::Spaceship => "spaceship",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Cycle a universe settled into: from generation `start` on, it comes back
+/// to the same cells moved by `displacement` every `period` generations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cycle {
+    start: u64,
+    period: u64,
+    displacement: (i64, i64),
+    empty: bool,
+}
+
+impl Cycle {
+    /// Returns the first generation of the cycle.
+    pub fn get_start(&self) -> u64 {
+        self.start
+    }
+
+    /// Returns the number of generations after which the cells come back.
+    pub fn get_period(&self) -> u64 {
+        self.period
+    }
+
+    /// Returns how far the cells move in a period, in cells.
+    pub fn get_displacement(&self) -> (i64, i64) {
+        self.displacement
+    }
+
+    /// Returns what the universe settled into.
+    pub fn get_kind(&self) -> CycleKind {
+        match (self.empty, self.displacement, self.period) {
+            (true, _, _) => CycleKind::Empty,
+            (false, (0, 0), 1) => CycleKind::StillLife,
+            (false, (0, 0), _) => CycleKind::Oscillator,
+            _ => CycleKind::Spaceship,
+        }
+    }
+}
+
+impl fmt::Display for Cycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.get_kind() {
+            kind @ (CycleKind::Empty | CycleKind::StillLife) => write!(f, "{}", kind)?,
+            kind @ CycleKind::Oscillator => write!(f, "{}, period {}", kind, self.period)?,
+            kind @ CycleKind::Spaceship => write!(
+                f,
+                "{}, period {}, moving ({}, {})",
+                kind, self.period, self.displacement.0, self.displacement.1
+            )?,
+        }
+        write!(f, " from generation {}", self.start)
+    }
+}
+
+/// Finds the cycle a universe settles into, by hashing the shape of its live
+/// cells every generation, wherever they are.
+///
+/// Each generation is remembered with the hash of its shape and the corner
+/// of its bounding box. A shape seen again gives the period and the
+/// displacement, and the generations before are walked back to find the
+/// first one of the cycle; a shape that does not come back as the cycle says
+/// means the universe has not settled (anymore, when a spaceship of a bounded
+/// grid hits the edge).
+#[derive(Debug, Clone, Default)]
+pub struct CycleDetector {
+    history: Vec<(u64, u64, i64, i64)>,
+    seen: HashMap<u64, usize>,
+    cycle: Option<Cycle>,
+}
+
+impl CycleDetector {
+    /// Creates a detector that has seen nothing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the cycle found, if the universe is in one.
+    pub fn get_cycle(&self) -> Option<Cycle> {
+        self.cycle
+    }
+
+    /// Forgets every generation seen.
+    pub fn reset(&mut self) {
+        self.history.clear();
+        self.seen.clear();
+        self.cycle = None;
+    }
+
+    /// Returns true if `generation` with the cells of hash `hash` is the last
+    /// one recorded, so that a universe shown again is not recorded twice.
+    fn is_last(&self, generation: u64, hash: u64) -> bool {
+        self.history
+            .last()
+            .is_some_and(|&(last, last_hash, _, _)| (last, last_hash) == (generation, hash))
+    }
+
+    /// Returns the hash and the corner of the generation recorded at `generation`, if any.
+    fn get_recorded(&self, generation: u64) -> Option<(u64, i64, i64)> {
+        let index = self
+            .history
+            .binary_search_by_key(&generation, |&(recorded, ..)| recorded)
+            .ok()?;
+        let (_, hash, x, y) = self.history[index];
+        Some((hash, x, y))
+    }
+
+    /// Returns true if the generation `generation` came back `period`
+    /// generations later, moved by `displacement`.
+    fn comes_back(&self, generation: u64, period: u64, displacement: (i64, i64)) -> bool {
+        match (
+            self.get_recorded(generation),
+            generation
+                .checked_add(period)
+                .and_then(|later| self.get_recorded(later)),
+        ) {
+            (Some((hash, x, y)), Some((later_hash, later_x, later_y))) => {
+                hash == later_hash && (later_x - x, later_y - y) == displacement
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the first generation recorded from which the universe comes
+    /// back every `period` generations moved by `displacement`, walking back
+    /// from the generation at `index` in the history, known to do so.
+    fn get_start(&self, mut index: usize, period: u64, displacement: (i64, i64)) -> u64 {
+        while index > 0 && self.comes_back(self.history[index - 1].0, period, displacement) {
+            index -= 1;
+        }
+        self.history[index].0
+    }
+
+    /// Records the live cells of `generation`, given in row then column order
+    /// with their bounding box, and returns the cycle found, if any.
+    ///
+    /// A generation that does not follow the last one recorded (after an edit
+    /// or an undo) starts the detection over.
+    pub fn record(
+        &mut self,
+        generation: u64,
+        bounding_box: Option<(i64, i64, i64, i64)>,
+        cells: impl IntoIterator<Item = (i64, i64)>,
+    ) -> Option<Cycle> {
+        let (min_x, min_y) = bounding_box.map_or((0, 0), |(x, y, _, _)| (x, y));
+        let mut hasher = DefaultHasher::new();
+        bounding_box.is_some().hash(&mut hasher);
+        for (x, y) in cells {
+            (x - min_x, y - min_y).hash(&mut hasher);
+        }
+        let hash = hasher.finish();
+        if self.is_last(generation, hash) {
+            return self.cycle;
+        }
+        if self
+            .history
+            .last()
+            .is_some_and(|&(last, ..)| generation <= last || self.history.len() >= MAX_CYCLE_STATES)
+        {
+            self.reset();
+        }
+
+        let previous = self.seen.insert(hash, self.history.len());
+        self.history.push((generation, hash, min_x, min_y));
+        self.cycle = match (self.cycle, previous) {
+            // the cycle goes on as long as every generation comes back as it says
+            (Some(cycle), _)
+                if generation.checked_sub(cycle.period).is_some_and(|earlier| {
+                    self.comes_back(earlier, cycle.period, cycle.displacement)
+                }) =>
+            {
+                Some(cycle)
+            }
+            (_, Some(index)) => {
+                let (previous, _, x, y) = self.history[index];
+                let period = generation - previous;
+                let displacement = (min_x - x, min_y - y);
+                Some(Cycle {
+                    start: self.get_start(index, period, displacement),
+                    period,
+                    displacement,
+                    empty: bounding_box.is_none(),
+                })
+            }
+            (_, None) => None,
+        };
+        self.cycle
+    }
+
+    /// Records the live cells of `grid` at `generation`.
+    pub fn record_grid(&mut self, generation: u64, grid: &Grid) -> Option<Cycle> {
+        if grid.population() as u64 > MAX_CYCLE_POPULATION {
+            self.reset();
+            return None;
+        }
+        let bounding_box = grid.bounding_box().map(|(min_x, min_y, max_x, max_y)| {
+            (min_x as i64, min_y as i64, max_x as i64, max_y as i64)
+        });
+        let cells = grid.live_cells().map(|(x, y)| (x as i64, y as i64));
+        self.record(generation, bounding_box, cells)
+    }
+
+    /// Records the live cells of `universe` at its generation.
+    pub fn record_universe(&mut self, universe: &dyn Universe) -> Option<Cycle> {
+        if universe.population() > MAX_CYCLE_POPULATION {
+            self.reset();
+            return None;
+        }
+        self.record(
+            universe.get_generation(),
+            universe.bounding_box(),
+            universe.live_cells(),
+        )
+    }
+}
@@ -0,0 +1,193 @@
+use crate::game::Game;
+use crate::grid::Grid;
+use crate::rule::Rule;
+use std::fmt;
+use std::str::FromStr;
+
+/// Simulation engine used to compute the next generations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Engine {
+    /// Bit-packed grid stepped one generation at a time, honoring the grid topology.
+    #[default]
+    Dense,
+    /// Memoized quadtree on an unbounded plane, stepped 2^n generations at a time.
+    HashLife,
+    /// Set of live cells on an unbounded plane, stepped one generation at a time.
+    Sparse,
+}
+
+impl Engine {
+    /// All engines, in the order they are cycled through.
+    pub const ALL: [Engine; 3] = [Engine::Dense, Engine::HashLife, Engine::Sparse];
+
+    /// Returns true if the engine runs on an unbounded plane rather than on the grid.
+    pub fn is_unbounded(&self) -> bool {
+        *self != Engine::Dense
+    }
+
+    /// Returns an error if the engine cannot run `rule`: the unbounded engines
+    /// keep empty space empty, so they do not support rules with `B0`.
+    pub fn check_rule(&self, rule: &Rule) -> Result<(), String> {
+        if self.is_unbounded() && rule.is_born(0) {
+            return Err(format!(
+                "The {} engine does not support rules with B0 such as {}",
+                self, rule
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns the engine that follows this one when cycling through them.
+    pub fn next(&self) -> Self {
+        let index = Self::ALL.iter().position(|e| e == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
+impl fmt::Display for Engine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Engine::Dense => "dense",
+            Engine::HashLife => "hashlife",
+            Engine::Sparse => "sparse",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Engine {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "dense" => Ok(Engine::Dense),
+            "hashlife" => Ok(Engine::HashLife),
+            "sparse" => Ok(Engine::Sparse),
+            other => Err(format!("Unknown engine '{}'", other)),
+        }
+    }
+}
+
+/// Universe on an unbounded plane with `i64` coordinates, shown through a grid-sized viewport.
+pub trait Universe {
+    /// Returns the rule used to advance the universe.
+    fn get_rule(&self) -> Rule;
+
+    /// Sets the rule used to advance the universe.
+    fn set_rule(&mut self, rule: Rule);
+
+    /// Returns the state of the cell at `(x, y)`.
+    fn get(&self, x: i64, y: i64) -> bool;
+
+    /// Sets the state of the cell at `(x, y)`.
+    fn set(&mut self, x: i64, y: i64, alive: bool);
+
+    /// Kills every cell and resets the generation counter.
+    fn clear(&mut self);
+
+    /// Returns the number of live cells.
+    fn population(&self) -> u64;
+
+    /// Returns the number of generations computed since the universe was cleared.
+    fn get_generation(&self) -> u64;
+
+    /// Sets the generation counter.
+    fn set_generation(&mut self, generation: u64);
+
+    /// Advances the universe by 2^`step_log2` generations.
+    fn step(&mut self, step_log2: u8);
+
+    /// Returns the coordinates of every live cell, sorted by row then column.
+    fn live_cells(&self) -> Vec<(i64, i64)>;
+
+    /// Returns `(min_x, min_y, max_x, max_y)` of the live cells, or `None` if the universe is empty.
+    fn bounding_box(&self) -> Option<(i64, i64, i64, i64)>;
+
+    /// Copies the part of the universe covered by `grid`, whose top-left cell
+    /// is at `(origin_x, origin_y)`, into `grid`.
+    fn write_to_grid(&self, grid: &mut Grid, origin_x: i64, origin_y: i64);
+
+    /// Replaces the content of the universe with the live cells of `grid`, the
+    /// top-left cell of the grid being placed at `(origin_x, origin_y)`.
+    /// The generation counter is kept.
+    fn load_grid(&mut self, grid: &Grid, origin_x: i64, origin_y: i64) {
+        let generation = self.get_generation();
+        self.clear();
+        self.set_generation(generation);
+        for (x, y) in grid.live_cells() {
+            self.set(origin_x + x as i64, origin_y + y as i64, true);
+        }
+    }
+}
+
+/// Moves the viewport of the game so that the live cells of `universe` are
+/// centered on a grid of `width` by `height` cells.
+pub fn center_viewport(game_info: &mut Game, universe: &dyn Universe, width: usize, height: usize) {
+    if let Some((min_x, min_y, max_x, max_y)) = universe.bounding_box() {
+        let center_x = min_x + (max_x - min_x) / 2;
+        let center_y = min_y + (max_y - min_y) / 2;
+        game_info.set_viewport_origin((
+            center_x.saturating_sub(width as i64 / 2),
+            center_y.saturating_sub(height as i64 / 2),
+        ));
+    }
+}
+
+/// Part of an unbounded universe as it was last copied into the grid.
+///
+/// The grid is the only thing the user edits and sees, so it is compared to
+/// this copy to find the edited cells before they are written into the universe.
+#[derive(Debug, Clone, Default)]
+pub struct UniverseView {
+    grid: Grid,
+    origin: (i64, i64),
+}
+
+impl UniverseView {
+    /// Copies the part of `universe` under the viewport into `grid`, so that
+    /// the next sync keeps the universe instead of rebuilding it from `grid`.
+    /// The iteration of the game must be the generation of the universe.
+    pub fn show(&mut self, universe: &dyn Universe, grid: &mut Grid, game_info: &Game) {
+        let (origin_x, origin_y) = game_info.get_viewport_origin();
+        universe.write_to_grid(grid, origin_x, origin_y);
+        self.grid = grid.clone();
+        self.origin = (origin_x, origin_y);
+    }
+
+    /// Writes the cells edited in `grid` into `universe`, advances the universe
+    /// by one step of the engine if `advance` is true, then copies the part of
+    /// the universe under the viewport back into `grid`.
+    ///
+    /// The universe is rebuilt from `grid` instead when `reload` is true, on the
+    /// first sync, or when its generation no longer matches the iteration of the
+    /// game (after a reset).
+    pub fn sync(
+        &mut self,
+        universe: &mut dyn Universe,
+        grid: &mut Grid,
+        game_info: &Game,
+        reload: bool,
+        advance: bool,
+    ) {
+        universe.set_rule(game_info.get_rule());
+        let (origin_x, origin_y) = game_info.get_viewport_origin();
+        let resized = self.grid.get_width() != grid.get_width()
+            || self.grid.get_height() != grid.get_height();
+        if reload || resized || universe.get_generation() != game_info.get_iteration() {
+            universe.clear();
+            universe.load_grid(grid, origin_x, origin_y);
+            universe.set_generation(game_info.get_iteration());
+        } else {
+            let (view_x, view_y) = self.origin;
+            for (x, y) in grid.changed_cells(&self.grid) {
+                universe.set(view_x + x as i64, view_y + y as i64, grid.get(x, y));
+            }
+        }
+        if advance {
+            universe.step(game_info.get_engine_step_log2());
+        }
+        universe.write_to_grid(grid, origin_x, origin_y);
+        self.grid = grid.clone();
+        self.origin = (origin_x, origin_y);
+    }
+}
@@ -0,0 +1,1173 @@
+use crate::age::{CellAges, TRAIL_COLOR};
+use crate::camera::{Camera, ZOOM_STEP};
+use crate::census::{take_census, CensusFormat};
+use crate::cycle::CycleDetector;
+use crate::engine::{center_viewport, Engine, Universe, UniverseView};
+use crate::game::{Game, GameStatus};
+use crate::grid::{BandPool, Grid};
+use crate::hashlife::HashLife;
+use crate::history::{Change, History};
+use crate::pattern::{load_macrocell_file, load_pattern_file, save_pattern_file, Pattern};
+use crate::scheduler::{faster_speed, format_speed, slower_speed, Scheduler, FRAME_BUDGET};
+use crate::selection::{Region, Selection};
+use crate::sparse::SparseLife;
+use crate::stats::{GenerationStats, StatsFormat, StatsRecorder, MAX_STATS_POPULATION};
+use crate::utils::{
+    fill_random_region, format_density, game_of_life_threaded, get_colored_rect_list,
+    get_grid_point_list, get_rect_list,
+};
+use chrono::Local;
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// Point in window coordinates, in pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Point {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Point {
+    /// Creates a point at `(x, y)`.
+    pub fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+}
+
+/// Rectangle in window coordinates, in pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Rect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Rect {
+    /// Creates a rectangle whose top-left corner is at `(x, y)`.
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+/// Color of something drawn, in RGB.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Color {
+    /// Creates the color of red, green and blue components `r`, `g` and `b`.
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+
+    /// Returns the color a fraction `t` of the way from this color to `other`.
+    pub fn lerp(self, other: Color, t: f32) -> Color {
+        let t = t.clamp(0.0, 1.0);
+        let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+        Color::new(
+            mix(self.r, other.r),
+            mix(self.g, other.g),
+            mix(self.b, other.b),
+        )
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+}
+
+impl FromStr for Color {
+    type Err = String;
+
+    /// Parses a color written `#rrggbb` or `rrggbb`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let hex = s.trim();
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+        let component = |index: usize| {
+            digits
+                .get(index..index + 2)
+                .and_then(|text| u8::from_str_radix(text, 16).ok())
+        };
+        let valid = digits.len() == 6 && digits.chars().all(|c| c.is_ascii_hexdigit());
+        match (valid, component(0), component(2), component(4)) {
+            (true, Some(r), Some(g), Some(b)) => Ok(Color::new(r, g, b)),
+            _ => Err(format!("Invalid color '{}', expected #rrggbb", hex)),
+        }
+    }
+}
+
+/// Color of the window behind the grid.
+pub const BACKGROUND_COLOR: Color = Color::new(255, 255, 255);
+
+/// Color of the live cells when they are not colored by age.
+pub const CELL_COLOR: Color = Color::new(0, 0, 0);
+
+/// Color of the population in the graph.
+pub const POPULATION_COLOR: Color = CELL_COLOR;
+
+/// Color of the births in the graph.
+pub const BIRTH_COLOR: Color = Color::new(0, 160, 60);
+
+/// Color of the deaths in the graph.
+pub const DEATH_COLOR: Color = Color::new(210, 40, 40);
+
+/// Space left around the population graph, in pixels.
+const GRAPH_MARGIN: f32 = 10.0;
+
+/// Height of the line of text above the population graph, in pixels.
+const GRAPH_LABEL_HEIGHT: f32 = 50.0;
+
+/// Smallest width and height of the curves of the population graph, in pixels.
+const GRAPH_MIN_SIZE: f32 = 50.0;
+
+/// Time between two refreshes of the statistics line of the HUD while the
+/// game runs, so that its text is not rendered again every frame.
+const STATS_TEXT_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Line of HUD text and the window position of its top-left corner.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Label {
+    pub text: String,
+    pub x: i32,
+    pub y: i32,
+}
+
+/// Polyline of one series of the population graph and its color.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Series {
+    pub points: Vec<Point>,
+    pub color: Color,
+}
+
+/// Everything drawn in one frame.
+#[derive(Debug, Clone, Copy)]
+pub struct Frame<'a> {
+    /// Polyline drawing the grid.
+    pub grid_lines: &'a [Point],
+    /// One square per live cell.
+    pub cells: &'a [Rect],
+    /// Color of each square of `cells`, in the same order.
+    pub cell_colors: &'a [Color],
+    /// One square per cell fading out after its death.
+    pub trail: &'a [Rect],
+    /// Color of each square of `trail`, in the same order.
+    pub trail_colors: &'a [Color],
+    /// HUD text.
+    pub labels: &'a [Label],
+    /// Outline of the selected region.
+    pub selection: Option<Rect>,
+    /// One square per live cell of the clipboard being pasted.
+    pub ghost: &'a [Rect],
+    /// Population, births and deaths of the last generations, in this order.
+    pub graph: &'a [Series],
+    /// Outline of the curves of the population graph, if it is shown.
+    pub graph_area: Option<Rect>,
+}
+
+/// Key of the keyboard, as far as the game is concerned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Key {
+    Escape,
+    Space,
+    Left,
+    Right,
+    Up,
+    Down,
+    /// Either shift key.
+    Shift,
+    /// Either control key, or the command key on macOS.
+    Ctrl,
+    /// The delete or backspace key.
+    Delete,
+    /// Key producing a printable character, lowercase (`'r'`, `'['`, ...).
+    Char(char),
+}
+
+/// Modifier keys held down, followed through the key events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+}
+
+impl Modifiers {
+    /// Updates the modifiers held down according to `event`.
+    pub fn update(&mut self, event: &InputEvent) {
+        match event {
+            InputEvent::KeyDown(Key::Shift) => self.shift = true,
+            InputEvent::KeyUp(Key::Shift) => self.shift = false,
+            InputEvent::KeyDown(Key::Ctrl) => self.ctrl = true,
+            InputEvent::KeyUp(Key::Ctrl) => self.ctrl = false,
+            _ => {}
+        }
+    }
+}
+
+/// Mouse button.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MouseButton {
+    Left,
+    Middle,
+    Right,
+}
+
+/// User input, independent of the windowing library.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputEvent {
+    /// The window was closed.
+    Quit,
+    /// A key was pressed.
+    KeyDown(Key),
+    /// A key was released.
+    KeyUp(Key),
+    /// A mouse button was pressed at `(x, y)`, in pixels.
+    MouseButtonDown { x: i32, y: i32, button: MouseButton },
+    /// The mouse moved by `(xrel, yrel)` to `(x, y)` with the given buttons held down.
+    MouseMotion {
+        x: i32,
+        y: i32,
+        xrel: i32,
+        yrel: i32,
+        left: bool,
+        middle: bool,
+        right: bool,
+    },
+    /// The mouse wheel was turned by `delta` steps with the mouse at `(x, y)`,
+    /// positive away from the user.
+    MouseWheel { x: i32, y: i32, delta: i32 },
+    /// A file was dropped on the window.
+    DropFile(String),
+}
+
+/// Source of user input, such as the SDL event pump.
+pub trait InputSource {
+    /// Returns the events that happened since the last call.
+    fn poll_events(&mut self) -> Vec<InputEvent>;
+}
+
+/// Backend drawing the frames, such as an SDL window.
+pub trait Renderer {
+    /// Returns the size of the drawing area, in pixels.
+    fn get_size(&self) -> (u32, u32);
+
+    /// Draws `frame` and shows it.
+    fn draw(&mut self, frame: &Frame) -> Result<(), String>;
+}
+
+fn calculate_cell_position(x: i32, y: i32, game_info: &Game) -> (i64, i64) {
+    game_info.get_camera().screen_to_cell(x as f32, y as f32)
+}
+
+fn check_cell_in_map(cell_x: i64, cell_y: i64, list_color: &Grid) -> bool {
+    list_color.contains(cell_x, cell_y)
+}
+
+/// Changes the camera of `game_info` with `update`.
+fn update_camera(game_info: &mut Game, update: impl FnOnce(&mut Camera)) {
+    let mut camera = game_info.get_camera();
+    update(&mut camera);
+    game_info.set_camera(camera);
+}
+
+/// Updates the game state and the grid according to one input event.
+pub fn handle_input(event: &InputEvent, list_color: &mut Grid, game_info: &mut Game) {
+    match event {
+        InputEvent::Quit | InputEvent::KeyDown(Key::Escape) => {
+            game_info.set_game_state(GameStatus::Exit);
+        }
+        InputEvent::KeyDown(Key::Space) => match game_info.get_game_state() {
+            GameStatus::Pause => {
+                game_info.set_game_state(GameStatus::Running);
+                game_info.set_start_time(Local::now());
+                game_info.set_start_time_iteration(game_info.get_iteration());
+            }
+            GameStatus::Running | GameStatus::Step(_) => {
+                game_info.set_game_state(GameStatus::Pause);
+            }
+            _ => {}
+        },
+        InputEvent::KeyDown(Key::Char('n')) => {
+            game_info.step(1);
+        }
+        InputEvent::KeyDown(Key::Char('g')) => {
+            game_info.step(game_info.get_step_count());
+        }
+        InputEvent::KeyDown(Key::Char('r')) => {
+            game_info.reset();
+            list_color.clear();
+        }
+        InputEvent::KeyDown(Key::Char('t')) => {
+            game_info.set_topology(game_info.get_topology().next());
+        }
+        InputEvent::KeyDown(Key::Char('e')) => {
+            // the engines that cannot run the rule are skipped, the dense one runs them all
+            let mut engine = game_info.get_engine().next();
+            while engine.check_rule(&game_info.get_rule()).is_err() {
+                engine = engine.next();
+            }
+            game_info.set_engine(engine);
+        }
+        InputEvent::KeyDown(Key::Char('c')) => {
+            game_info.set_color_mode(game_info.get_color_mode().next());
+        }
+        InputEvent::KeyDown(Key::Char(']')) => {
+            game_info.set_step_log2(game_info.get_step_log2().saturating_add(1));
+        }
+        InputEvent::KeyDown(Key::Char('[')) => {
+            game_info.set_step_log2(game_info.get_step_log2().saturating_sub(1));
+        }
+        InputEvent::KeyDown(Key::Char('+' | '=')) => {
+            game_info.set_max_iteration_per_second(faster_speed(
+                game_info.get_max_iteration_per_second(),
+            ));
+        }
+        InputEvent::KeyDown(Key::Char('-')) => {
+            game_info.set_max_iteration_per_second(slower_speed(
+                game_info.get_max_iteration_per_second(),
+            ));
+        }
+        InputEvent::KeyDown(key @ (Key::Left | Key::Right | Key::Up | Key::Down)) => {
+            let (dx, dy) = match key {
+                Key::Left => (-1, 0),
+                Key::Right => (1, 0),
+                Key::Up => (0, -1),
+                _ => (0, 1),
+            };
+            if game_info.get_engine().is_unbounded() {
+                // the unbounded engines move the part of the plane shown in the grid
+                let distance = (game_info.get_size_grid() as i64 / 10).max(1);
+                game_info.move_viewport(dx * distance, dy * distance);
+            } else {
+                let distance = game_info.get_window_min_length() as f32 / 10.0;
+                update_camera(game_info, |camera| {
+                    camera.pan(dx as f32 * distance, dy as f32 * distance)
+                });
+            }
+        }
+        InputEvent::KeyDown(Key::Char(key @ ('i' | 'o'))) => {
+            let factor = if *key == 'i' {
+                ZOOM_STEP
+            } else {
+                1.0 / ZOOM_STEP
+            };
+            let center = game_info.get_window_min_length() as f32 / 2.0;
+            update_camera(game_info, |camera| camera.zoom_at(factor, center, center));
+        }
+        InputEvent::KeyDown(Key::Char('f')) => match list_color.bounding_box() {
+            Some((min_x, min_y, max_x, max_y)) => {
+                let length = game_info.get_window_min_length();
+                update_camera(game_info, |camera| {
+                    camera.fit(
+                        (min_x as f32, min_y as f32, max_x as f32, max_y as f32),
+                        length,
+                        length,
+                    )
+                });
+            }
+            None => game_info.reset_camera(),
+        },
+        InputEvent::KeyDown(Key::Char('h')) => {
+            game_info.reset_camera();
+        }
+        InputEvent::MouseWheel { x, y, delta } => {
+            let factor = ZOOM_STEP.powi(*delta);
+            update_camera(game_info, |camera| {
+                camera.zoom_at(factor, *x as f32, *y as f32)
+            });
+        }
+        InputEvent::KeyDown(Key::Char('s')) => {
+            let path = format!("rust_of_life-{}.rle", Local::now().format("%Y%m%d-%H%M%S"));
+            let mut pattern = Pattern::from_grid(list_color);
+            pattern.set_rule(Some(game_info.get_rule()));
+            match save_pattern_file(&path, &pattern) {
+                Ok(()) => println!("Saved pattern to {}", path),
+                Err(e) => eprintln!("Error saving pattern: {}", e),
+            }
+        }
+        InputEvent::KeyDown(Key::Char('k')) => {
+            let format = CensusFormat::Text;
+            let path = format!(
+                "rust_of_life-{}-census.{}",
+                Local::now().format("%Y%m%d-%H%M%S"),
+                format.get_extension()
+            );
+            let cells = list_color.live_cells().map(|(x, y)| (x as i64, y as i64));
+            let census = take_census(cells, game_info.get_rule());
+            match std::fs::write(&path, census.write(format)) {
+                Ok(()) => println!("Saved census of {} objects to {}", census.get_total(), path),
+                Err(e) => eprintln!("Error saving census: {}", e),
+            }
+        }
+        InputEvent::DropFile(filename) => match load_pattern_file(filename) {
+            Ok(pattern) => {
+                game_info.reset();
+                let dropped = pattern.place_centered(list_color);
+                if dropped > 0 {
+                    eprintln!("{}", pattern.get_dropped_warning(list_color, dropped));
+                }
+                if let Some(rule) = pattern.get_rule() {
+                    if let Err(e) = game_info.get_engine().check_rule(&rule) {
+                        eprintln!("{}, switching to the dense engine", e);
+                        game_info.set_engine(Engine::Dense);
+                    }
+                    game_info.set_rule(rule);
+                }
+            }
+            Err(e) => eprintln!("Error loading pattern: {}", e),
+        },
+        InputEvent::MouseMotion {
+            xrel,
+            yrel,
+            middle: true,
+            ..
+        } => {
+            update_camera(game_info, |camera| camera.pan(-*xrel as f32, -*yrel as f32));
+        }
+        InputEvent::MouseMotion {
+            x, y, left, right, ..
+        } => {
+            if *left || *right {
+                let (cell_x, cell_y) = calculate_cell_position(*x, *y, game_info);
+                if check_cell_in_map(cell_x, cell_y, list_color) {
+                    list_color.set(cell_x as usize, cell_y as usize, *left);
+                }
+            }
+        }
+        InputEvent::MouseButtonDown { button, .. } if *button == MouseButton::Middle => {}
+        InputEvent::MouseButtonDown { x, y, .. } => {
+            let (cell_x, cell_y) = calculate_cell_position(*x, *y, game_info);
+            if check_cell_in_map(cell_x, cell_y, list_color) {
+                list_color.toggle(cell_x as usize, cell_y as usize);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Handles the undo, redo and rewind keys, returning false for other events.
+///
+/// Undoing or rewinding pauses the game and restores the iteration counter
+/// of the generation shown.
+pub fn handle_history_input(
+    event: &InputEvent,
+    history: &mut History,
+    list_color: &mut Grid,
+    game_info: &mut Game,
+) -> bool {
+    let iteration = match event {
+        InputEvent::KeyDown(Key::Char('z')) => history.undo(list_color),
+        InputEvent::KeyDown(Key::Char('y')) => history.redo(list_color),
+        InputEvent::KeyDown(Key::Char('b')) => {
+            history.rewind(list_color, game_info.get_step_count())
+        }
+        _ => return false,
+    };
+    if let Some(iteration) = iteration {
+        if game_info.get_game_state() != GameStatus::Pause {
+            game_info.set_game_state(GameStatus::Pause);
+        }
+        game_info.set_iteration(iteration);
+    }
+    true
+}
+
+/// Handles the key saving the statistics of the generations, `x`, returning
+/// false for other events.
+pub fn handle_stats_input(event: &InputEvent, stats: &StatsRecorder) -> bool {
+    if *event != InputEvent::KeyDown(Key::Char('x')) {
+        return false;
+    }
+    let format = StatsFormat::Csv;
+    let path = format!(
+        "rust_of_life-{}-stats.{}",
+        Local::now().format("%Y%m%d-%H%M%S"),
+        format.get_extension()
+    );
+    match std::fs::write(&path, stats.write(format)) {
+        Ok(()) => println!(
+            "Saved statistics of {} generations to {}",
+            stats.len(),
+            path
+        ),
+        Err(e) => eprintln!("Error saving statistics: {}", e),
+    }
+    true
+}
+
+/// Loads a macrocell file dropped on the window straight into the HashLife
+/// universe, keeping its generation, so that patterns larger than the grid
+/// are not cropped, and centers the viewport on it. Returns false for other
+/// events, files and engines, and for rules HashLife cannot run, which are
+/// loaded into the grid by `handle_input`.
+fn handle_universe_drop(
+    event: &InputEvent,
+    hashlife: &mut HashLife,
+    universe_view: &mut UniverseView,
+    list_color: &mut Grid,
+    game_info: &mut Game,
+) -> bool {
+    let InputEvent::DropFile(filename) = event else {
+        return false;
+    };
+    if game_info.get_engine() != Engine::HashLife {
+        return false;
+    }
+    let universe = match load_macrocell_file(filename, game_info.get_rule()) {
+        Ok(Some(universe)) if Engine::HashLife.check_rule(&universe.get_rule()).is_ok() => universe,
+        // errors are reported when handle_input loads the file again
+        _ => return false,
+    };
+    game_info.reset();
+    game_info.set_rule(universe.get_rule());
+    game_info.set_iteration(universe.get_generation());
+    center_viewport(
+        game_info,
+        &universe,
+        list_color.get_width(),
+        list_color.get_height(),
+    );
+    *hashlife = universe;
+    universe_view.show(hashlife, list_color, game_info);
+    true
+}
+
+/// Returns the HUD line of the last generation recorded in `stats`, if it is
+/// the generation `iteration` shown.
+fn get_stats_text(stats: &StatsRecorder, iteration: u64) -> String {
+    match stats
+        .get_last()
+        .filter(|last| last.get_generation() == iteration)
+    {
+        Some(last) => format!(
+            "births: {}, deaths: {}{}, density: {}, step: {:.1} ms",
+            last.get_births(),
+            last.get_deaths(),
+            match last.get_span() {
+                1 => String::new(),
+                span => format!(" over {} generations", span),
+            },
+            format_density(last.get_density()),
+            last.get_step_time().as_secs_f64() * 1000.0
+        ),
+        None => "births: -, deaths: -".to_string(),
+    }
+}
+
+/// Handles the selection tool, returning false for events it does not use.
+///
+/// Shift with the left button selects a region; control with `c`, `x` and
+/// `v` copies, cuts and pastes it, `a` selects the whole grid, `f` fills the
+/// selection and `r` fills it with a random soup (the whole grid if nothing
+/// is selected), and delete clears it. Shift with `r`, `h` and `v` rotates
+/// and flips the clipboard while pasting, otherwise the selection. While
+/// pasting, the clipboard follows the mouse until the left button places it,
+/// and the right button or escape cancels it.
+pub fn handle_selection_input(
+    event: &InputEvent,
+    modifiers: &Modifiers,
+    selection: &mut Selection,
+    list_color: &mut Grid,
+    game_info: &mut Game,
+) -> bool {
+    match event {
+        InputEvent::KeyDown(Key::Escape) if selection.is_pasting() => selection.cancel_paste(),
+        InputEvent::KeyDown(Key::Escape) if selection.get_region().is_some() => {
+            selection.set_region(None, list_color);
+        }
+        InputEvent::KeyDown(Key::Delete) => {
+            selection.clear(list_color);
+        }
+        InputEvent::KeyDown(Key::Char(key)) if modifiers.ctrl => match key {
+            'a' => selection.set_region(
+                Some(Region::new(
+                    0,
+                    0,
+                    list_color.get_width(),
+                    list_color.get_height(),
+                )),
+                list_color,
+            ),
+            'c' => {
+                selection.copy(list_color);
+            }
+            'x' => {
+                selection.cut(list_color);
+            }
+            'v' => {
+                selection.start_paste();
+            }
+            'f' => {
+                selection.fill(list_color);
+            }
+            'r' => {
+                let region = selection.get_region().unwrap_or(Region::new(
+                    0,
+                    0,
+                    list_color.get_width(),
+                    list_color.get_height(),
+                ));
+                let seed = game_info.next_seed();
+                fill_random_region(list_color, region, game_info.get_density(), seed);
+                game_info.set_last_seed(Some(seed));
+                println!(
+                    "Random fill of {}x{} cells at ({}, {}) with seed {} (density {})",
+                    region.width,
+                    region.height,
+                    region.x,
+                    region.y,
+                    seed,
+                    format_density(game_info.get_density())
+                );
+            }
+            _ => return false,
+        },
+        InputEvent::KeyDown(Key::Char(key)) if modifiers.shift => {
+            let transform = match key {
+                'r' => Grid::rotate_clockwise,
+                'h' => Grid::flip_horizontal,
+                'v' => Grid::flip_vertical,
+                _ => return false,
+            };
+            selection.transform(list_color, transform);
+        }
+        InputEvent::MouseMotion { x, y, left, .. } => {
+            let (cell_x, cell_y) = calculate_cell_position(*x, *y, game_info);
+            selection.set_cursor((cell_x, cell_y));
+            if selection.is_pasting() {
+                return true;
+            }
+            if !(*left && modifiers.shift) {
+                return false;
+            }
+            selection.drag_to(cell_x, cell_y, list_color);
+        }
+        InputEvent::MouseButtonDown { x, y, button } => {
+            let (cell_x, cell_y) = calculate_cell_position(*x, *y, game_info);
+            selection.set_cursor((cell_x, cell_y));
+            match button {
+                MouseButton::Left if selection.is_pasting() => {
+                    selection.paste(list_color);
+                }
+                MouseButton::Right if selection.is_pasting() => selection.cancel_paste(),
+                MouseButton::Left if modifiers.shift => {
+                    selection.start_drag(cell_x, cell_y, list_color);
+                }
+                _ => return false,
+            }
+        }
+        _ => return false,
+    }
+    true
+}
+
+/// Returns the window rectangle covering the cells `(x, y)` to
+/// `(x + width, y + height)` excluded.
+fn get_cell_rect(camera: &Camera, x: f32, y: f32, width: f32, height: f32) -> Rect {
+    let corner = camera.cell_to_screen(x, y);
+    let zoom = camera.get_zoom();
+    Rect::new(corner.x, corner.y, width * zoom, height * zoom)
+}
+
+/// Returns the outline of the selected region and the squares of the ghost
+/// of the clipboard being pasted, in window coordinates.
+fn get_selection_rects(selection: &Selection, camera: &Camera) -> (Option<Rect>, Vec<Rect>) {
+    let outline = selection.get_region().map(|region| {
+        get_cell_rect(
+            camera,
+            region.x as f32,
+            region.y as f32,
+            region.width as f32,
+            region.height as f32,
+        )
+    });
+    let ghost = selection
+        .get_ghost_cells()
+        .into_iter()
+        .map(|(x, y)| get_cell_rect(camera, x as f32, y as f32, 1.0, 1.0))
+        .collect();
+    (outline, ghost)
+}
+
+/// Returns the squares of the live cells of `list_color` and of the cells
+/// fading out, with their colors, according to the color mode of `game_info`.
+fn get_cell_rects(
+    list_color: &Grid,
+    ages: &CellAges,
+    game_info: &Game,
+) -> (Vec<Rect>, Vec<Color>, Vec<Rect>, Vec<Color>) {
+    let camera = game_info.get_camera();
+    let (width, height) = (game_info.get_window_width(), game_info.get_window_height());
+    let color_mode = game_info.get_color_mode();
+    if !color_mode.shows_age() {
+        let cells = get_rect_list(list_color, &camera, width, height);
+        let cell_colors = vec![CELL_COLOR; cells.len()];
+        return (cells, cell_colors, Vec::new(), Vec::new());
+    }
+    let gradient = game_info.get_gradient();
+    let (cells, cell_colors) = get_colored_rect_list(
+        ages.live_cells()
+            .map(|(x, y, age)| (x, y, gradient.get_color(age))),
+        &camera,
+        width,
+        height,
+    );
+    if !color_mode.shows_trail() {
+        return (cells, cell_colors, Vec::new(), Vec::new());
+    }
+    // the trail fades from its color to the background
+    let length = game_info.get_trail_length().max(1) as f32;
+    let (trail, trail_colors) = get_colored_rect_list(
+        ages.fading_cells().map(|(x, y, fade)| {
+            let color = BACKGROUND_COLOR.lerp(TRAIL_COLOR, fade as f32 / length);
+            (x, y, color)
+        }),
+        &camera,
+        width,
+        height,
+    );
+    (cells, cell_colors, trail, trail_colors)
+}
+
+/// Returns the universe simulated by `engine`, or `None` for the dense grid.
+fn get_universe<'a>(
+    engine: Engine,
+    hashlife: &'a mut HashLife,
+    sparse: &'a mut SparseLife,
+) -> Option<&'a mut dyn Universe> {
+    match engine {
+        Engine::Dense => None,
+        Engine::HashLife => Some(hashlife),
+        Engine::Sparse => Some(sparse),
+    }
+}
+
+/// Returns the HUD text describing the rule and how it is applied.
+fn get_rule_text(game_info: &Game) -> String {
+    match game_info.get_engine() {
+        Engine::Dense => format!(
+            "rule: {} ({})",
+            game_info.get_rule(),
+            game_info.get_topology()
+        ),
+        Engine::HashLife => format!(
+            "rule: {} (hashlife, step 2^{}, at {}, {})",
+            game_info.get_rule(),
+            game_info.get_step_log2(),
+            game_info.get_viewport_origin().0,
+            game_info.get_viewport_origin().1
+        ),
+        Engine::Sparse => format!(
+            "rule: {} (sparse, at {}, {})",
+            game_info.get_rule(),
+            game_info.get_viewport_origin().0,
+            game_info.get_viewport_origin().1
+        ),
+    }
+}
+
+/// Returns the top-left corner of the HUD: below the grid if there is room
+/// for it, otherwise to its right, otherwise over the grid.
+fn get_start_text_point(game_info: &Game) -> [u32; 2] {
+    let mut start_text_point = [0, 0];
+    let grid_length = game_info.get_size_grid() as f32 * game_info.get_unit_grid();
+    if game_info.get_window_height() as f32 - grid_length > 100.0 {
+        start_text_point[1] = grid_length as u32;
+    } else if game_info.get_window_width() as f32 - grid_length > 100.0 {
+        start_text_point[0] = grid_length as u32;
+    }
+    start_text_point
+}
+
+/// Returns the area of the population graph, its line of text included: the
+/// room left under `line_count` labels when the HUD is right of the grid, the
+/// right half of the HUD when it is below, or None if there is no room for it.
+fn get_graph_area(game_info: &Game, start_text_point: [u32; 2], line_count: usize) -> Option<Rect> {
+    let [x, y] = start_text_point.map(|v| v as f32);
+    let (width, height) = (
+        game_info.get_window_width() as f32,
+        game_info.get_window_height() as f32,
+    );
+    let (left, top) = if x > 0.0 {
+        (x, y + 100.0 * line_count as f32)
+    } else if y > 0.0 {
+        (width / 2.0, y)
+    } else {
+        return None;
+    };
+    let area = Rect::new(
+        left + GRAPH_MARGIN,
+        top + GRAPH_MARGIN,
+        width - left - 2.0 * GRAPH_MARGIN,
+        height - top - 2.0 * GRAPH_MARGIN,
+    );
+    (area.width >= GRAPH_MIN_SIZE && area.height >= GRAPH_LABEL_HEIGHT + GRAPH_MIN_SIZE)
+        .then_some(area)
+}
+
+/// Returns the population, births and deaths of the last `length` generations
+/// of `stats` drawn in `area`, the newest on its right, and the value shown at
+/// the top, which follows the highest one so that the curves fill the area.
+pub fn get_graph(stats: &StatsRecorder, length: usize, area: Rect) -> (Vec<Series>, u64) {
+    let records: Vec<&GenerationStats> = stats.get_records().rev().take(length).collect();
+    let max = records
+        .iter()
+        .map(|s| s.get_population().max(s.get_births()).max(s.get_deaths()))
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let step = area.width / length.saturating_sub(1).max(1) as f32;
+    let series = [
+        (
+            POPULATION_COLOR,
+            GenerationStats::get_population as fn(&GenerationStats) -> u64,
+        ),
+        (BIRTH_COLOR, GenerationStats::get_births),
+        (DEATH_COLOR, GenerationStats::get_deaths),
+    ]
+    .into_iter()
+    .map(|(color, value)| Series {
+        points: records
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(index, &stats)| {
+                let age = (records.len() - 1 - index) as f32;
+                Point::new(
+                    area.x + area.width - age * step,
+                    area.y + area.height * (1.0 - value(stats) as f32 / max as f32),
+                )
+            })
+            .collect(),
+        color,
+    })
+    .collect();
+    (series, max)
+}
+
+/// Runs the main game loop on `list_color`, reading input from `input` and
+/// drawing every frame with `renderer`, until the game exits.
+pub fn run_frontend(
+    game_info: &mut Game,
+    list_color: Grid,
+    renderer: &mut dyn Renderer,
+    input: &mut dyn InputSource,
+) -> Result<(), String> {
+    run_frontend_with_universe(game_info, list_color, None, renderer, input)
+}
+
+/// Runs the main game loop like `run_frontend`, starting the HashLife engine
+/// from `universe` instead of the grid if one is given, such as a macrocell
+/// file too large for the grid. The iteration of the game must be the
+/// generation of the universe.
+pub fn run_frontend_with_universe(
+    game_info: &mut Game,
+    list_color: Grid,
+    universe: Option<HashLife>,
+    renderer: &mut dyn Renderer,
+    input: &mut dyn InputSource,
+) -> Result<(), String> {
+    let mut list_color = list_color;
+    let mut list_color_next = list_color.clone();
+    let mut pool = BandPool::new(game_info.get_thread_count());
+    // The unbounded engines show the part of the plane under the viewport in the grid.
+    let mut hashlife = HashLife::new(game_info.get_rule());
+    let mut sparse = SparseLife::new(game_info.get_rule());
+    let mut universe_view = UniverseView::default();
+    if let Some(universe) = universe {
+        hashlife = universe;
+        universe_view.show(&hashlife, &mut list_color, game_info);
+    }
+    let mut last_engine = game_info.get_engine();
+
+    let mut grid_lines = Vec::new();
+    let mut grid_lines_view = None;
+    let mut start_text_point = get_start_text_point(game_info);
+    let mut iteration_per_second = 0.0;
+    let mut scheduler = Scheduler::new();
+    let mut history = History::new(game_info.get_history_length());
+    history.reset(&list_color, game_info.get_iteration());
+    let mut ages = CellAges::new(&list_color);
+    let mut showed_age = game_info.get_color_mode().shows_age();
+    let mut detector = CycleDetector::new();
+    let mut modifiers = Modifiers::default();
+    let mut selection = Selection::new();
+    let mut stats = StatsRecorder::new(game_info.get_stats_length());
+    let mut stats_text = get_stats_text(&stats, game_info.get_iteration());
+    let mut stats_text_time = Instant::now();
+    let mut last_frame = Instant::now();
+
+    while game_info.get_game_state() != GameStatus::Exit {
+        for event in input.poll_events() {
+            modifiers.update(&event);
+            if !handle_selection_input(
+                &event,
+                &modifiers,
+                &mut selection,
+                &mut list_color,
+                game_info,
+            ) && !handle_history_input(&event, &mut history, &mut list_color, game_info)
+                && !handle_stats_input(&event, &stats)
+                && !handle_universe_drop(
+                    &event,
+                    &mut hashlife,
+                    &mut universe_view,
+                    &mut list_color,
+                    game_info,
+                )
+            {
+                handle_input(&event, &mut list_color, game_info);
+            }
+            if !game_info.get_engine().is_unbounded() {
+                // strokes drawn with the mouse are undone at once
+                let stroke = matches!(
+                    event,
+                    InputEvent::MouseMotion { left, right, .. } if left || right
+                );
+                history.record(&list_color, game_info.get_iteration(), Change::Edit, stroke);
+            }
+        }
+
+        // if the window is resized, update the grid
+        let (window_width, window_height) = renderer.get_size();
+        if window_width != game_info.get_window_width()
+            || window_height != game_info.get_window_height()
+        {
+            game_info.set_window_width(window_width);
+            game_info.set_window_height(window_height);
+            start_text_point = get_start_text_point(game_info);
+        }
+        // the grid lines follow the camera
+        let camera = game_info.get_camera();
+        if grid_lines_view != Some((camera, window_width, window_height)) {
+            grid_lines = get_grid_point_list(
+                &camera,
+                list_color.get_width(),
+                list_color.get_height(),
+                window_width,
+                window_height,
+            );
+            grid_lines_view = Some((camera, window_width, window_height));
+        }
+
+        let engine = game_info.get_engine();
+        if let Some(universe) = get_universe(engine, &mut hashlife, &mut sparse) {
+            // sync every frame so that edits and viewport moves show while paused
+            universe_view.sync(
+                universe,
+                &mut list_color,
+                game_info,
+                engine != last_engine,
+                false,
+            );
+        }
+        // the history only follows the dense grid, the unbounded engines
+        // keeping most of their universe out of it
+        if engine.is_unbounded() || engine != last_engine {
+            history.reset(&list_color, game_info.get_iteration());
+        }
+        last_engine = engine;
+        // the ages are only followed while they are shown, and the cells
+        // changed since the last frame are newborn
+        let color_mode = game_info.get_color_mode();
+        let trail_length = if color_mode.shows_trail() {
+            game_info.get_trail_length()
+        } else {
+            0
+        };
+        if color_mode.shows_age() && !showed_age {
+            ages.reset(&list_color);
+        } else if color_mode.shows_age() {
+            ages.sync(&list_color);
+        }
+        showed_age = color_mode.shows_age();
+        // the detector starts over if the cells changed since the last generation
+        match get_universe(engine, &mut hashlife, &mut sparse) {
+            Some(universe) => detector.record_universe(universe),
+            None => detector.record_grid(game_info.get_iteration(), &list_color),
+        };
+
+        let now = Instant::now();
+        let elapsed = now - last_frame;
+        last_frame = now;
+        let state = game_info.get_game_state();
+        let due = match state {
+            GameStatus::Running => {
+                scheduler.advance(elapsed, game_info.get_max_iteration_per_second())
+            }
+            GameStatus::Step(remaining) => {
+                scheduler.reset();
+                remaining
+            }
+            _ => {
+                scheduler.reset();
+                0
+            }
+        };
+
+        // update the grid, as many generations as are due or fit in the frame budget;
+        // the generations of a long step that do not fit are computed in the next frames
+        let mut generations = 0;
+        while generations < due && (generations == 0 || now.elapsed() < FRAME_BUDGET) {
+            let step_start = Instant::now();
+            match get_universe(engine, &mut hashlife, &mut sparse) {
+                Some(universe) => {
+                    // births and deaths are counted over the whole plane, unless
+                    // listing its cells every step costs too much
+                    let previous = (universe.population() <= MAX_STATS_POPULATION)
+                        .then(|| universe.live_cells());
+                    let step_log2 = game_info.get_engine_step_log2();
+                    universe.step(step_log2);
+                    let step_time = step_start.elapsed();
+                    if let Some(previous) = previous {
+                        stats.record(GenerationStats::of_universe(
+                            universe,
+                            &previous,
+                            1 << step_log2.min(63),
+                            step_time,
+                        ));
+                    }
+                    detector.record_universe(universe);
+                }
+                None => {
+                    game_of_life_threaded(
+                        &list_color,
+                        &mut list_color_next,
+                        &game_info.get_rule(),
+                        game_info.get_topology(),
+                        &mut pool,
+                    );
+                    std::mem::swap(&mut list_color, &mut list_color_next);
+                    let step_time = step_start.elapsed();
+                    if color_mode.shows_age() {
+                        ages.advance(&list_color, trail_length);
+                    }
+                    let iteration = game_info.get_iteration().saturating_add(generations + 1);
+                    history.record(&list_color, iteration, Change::Generation, false);
+                    detector.record_grid(iteration, &list_color);
+                    stats.record(GenerationStats::of_grid(
+                        iteration,
+                        &list_color_next,
+                        &list_color,
+                        step_time,
+                    ));
+                }
+            }
+            generations += 1;
+        }
+        if generations > 0 {
+            let iteration = match get_universe(engine, &mut hashlife, &mut sparse) {
+                Some(universe) => universe.get_generation(),
+                None => game_info.get_iteration().saturating_add(generations),
+            };
+            game_info.set_iteration(iteration);
+            if let Some(universe) = get_universe(engine, &mut hashlife, &mut sparse) {
+                universe_view.sync(universe, &mut list_color, game_info, false, false);
+                // the unbounded engines age the cells by one step at a time
+                if color_mode.shows_age() {
+                    ages.advance(&list_color, trail_length);
+                }
+            }
+            match state {
+                GameStatus::Running => iteration_per_second = game_info.get_iteration_per_second(),
+                GameStatus::Step(remaining) if remaining > generations => {
+                    game_info.set_game_state(GameStatus::Step(remaining - generations));
+                }
+                GameStatus::Step(_) => game_info.set_game_state(GameStatus::Pause),
+                _ => {}
+            }
+        }
+
+        // display the grid
+        if game_info.get_game_state() != GameStatus::Exit {
+            let (cells, cell_colors, trail, trail_colors) =
+                get_cell_rects(&list_color, &ages, game_info);
+            let (selection_outline, ghost) =
+                get_selection_rects(&selection, &game_info.get_camera());
+            if game_info.get_game_state() != GameStatus::Running
+                || stats_text_time.elapsed() >= STATS_TEXT_INTERVAL
+            {
+                stats_text = get_stats_text(&stats, game_info.get_iteration());
+                stats_text_time = Instant::now();
+            }
+            let [x, y] = start_text_point.map(|v| v as i32);
+            let mut labels = [
+                format!("iteration: {}", game_info.get_iteration()),
+                format!("population: {}", list_color.population()),
+                format!(
+                    "iteration / s: {} (max {})",
+                    iteration_per_second,
+                    format_speed(game_info.get_max_iteration_per_second())
+                ),
+                get_rule_text(game_info),
+                match detector.get_cycle() {
+                    Some(cycle) => format!("cycle: {}", cycle),
+                    None => "cycle: none".to_string(),
+                },
+                stats_text.clone(),
+            ]
+            .into_iter()
+            .chain(game_info.get_last_seed().map(|seed| {
+                format!(
+                    "seed: {} (density {})",
+                    seed,
+                    format_density(game_info.get_density())
+                )
+            }))
+            .enumerate()
+            .map(|(line, text)| Label {
+                text,
+                x,
+                y: y + 100 * line as i32,
+            })
+            .collect::<Vec<_>>();
+            // the graph scrolls under its line of text, rescaled to the highest value shown
+            let graph_length = game_info.get_graph_length();
+            let graph_area = get_graph_area(game_info, start_text_point, labels.len())
+                .filter(|_| graph_length > 0);
+            let (graph, graph_area) = match graph_area {
+                Some(area) => {
+                    let curves = Rect::new(
+                        area.x,
+                        area.y + GRAPH_LABEL_HEIGHT,
+                        area.width,
+                        area.height - GRAPH_LABEL_HEIGHT,
+                    );
+                    let (graph, max) = get_graph(&stats, graph_length, curves);
+                    labels.push(Label {
+                        text: format!("graph max: {}", max),
+                        x: area.x as i32,
+                        y: area.y as i32,
+                    });
+                    (graph, Some(curves))
+                }
+                None => (Vec::new(), None),
+            };
+            renderer.draw(&Frame {
+                grid_lines: &grid_lines,
+                cells: &cells,
+                cell_colors: &cell_colors,
+                trail: &trail,
+                trail_colors: &trail_colors,
+                labels: &labels,
+                selection: selection_outline,
+                ghost: &ghost,
+                graph: &graph,
+                graph_area,
+            })?;
+        }
+    }
+
+    Ok(())
+}
@@ -1,3 +1,12 @@
+use crate::age::{ColorMode, Gradient, DEFAULT_TRAIL_LENGTH};
+use crate::camera::Camera;
+use crate::engine::Engine;
+use crate::hashlife::MAX_STEP_LOG2;
+use crate::history::DEFAULT_HISTORY_LENGTH;
+use crate::rule::Rule;
+use crate::scheduler::MIN_ITERATION_PER_SECOND;
+use crate::stats::{DEFAULT_GRAPH_LENGTH, DEFAULT_STATS_LENGTH};
+use crate::topology::Topology;
 use std::cmp::min;
 
 /// Represents the current status of the game.
@@ -10,8 +19,19 @@ pub enum GameStatus {
     Pause,
     /// The game is running.
     Running,
+    /// The game computes this many more generations, then pauses.
+    Step(u64),
 }
 
+/// Number of generations computed by the step-by-N action when no other one is given.
+pub const DEFAULT_STEP_COUNT: u64 = 10;
+
+/// Part of the cells brought to life by a random fill when no other one is given.
+pub const DEFAULT_DENSITY: f64 = 0.5;
+
+/// Font used for the HUD when no other one is given.
+pub const DEFAULT_FONT_PATH: &str = "./assets/Roboto-Medium.ttf";
+
 /// Main game state and configuration.
 #[derive(Debug, Default, Clone)]
 pub struct Game {
@@ -22,10 +42,28 @@ pub struct Game {
     window_width: u32,
     window_min_length: u32,
     unit_grid: f32,
-    iteration: u32,
+    camera: Camera,
+    iteration: u64,
     start_time: chrono::DateTime<chrono::Local>,
-    start_time_iteration: u32,
-    max_iteration_per_second: u32,
+    start_time_iteration: u64,
+    max_iteration_per_second: f64,
+    rule: Rule,
+    topology: Topology,
+    thread_count: usize,
+    engine: Engine,
+    step_log2: u8,
+    step_count: u64,
+    history_length: usize,
+    viewport_origin: (i64, i64),
+    font_path: String,
+    seed: Option<u64>,
+    density: f64,
+    last_seed: Option<u64>,
+    color_mode: ColorMode,
+    gradient: Gradient,
+    trail_length: u8,
+    stats_length: usize,
+    graph_length: usize,
 }
 
 impl Game {
@@ -39,23 +77,53 @@ impl Game {
             window_width: 1000,
             window_min_length: 1000,
             unit_grid: 0.0,
+            camera: Camera::default(),
             iteration: 0,
             start_time: chrono::Local::now(),
             start_time_iteration: 0,
-            max_iteration_per_second: 10,
+            max_iteration_per_second: 10.0,
+            rule: Rule::conway(),
+            topology: Topology::Dead,
+            thread_count: std::thread::available_parallelism().map_or(1, |n| n.get()),
+            engine: Engine::Dense,
+            step_log2: 0,
+            step_count: DEFAULT_STEP_COUNT,
+            history_length: DEFAULT_HISTORY_LENGTH,
+            viewport_origin: (0, 0),
+            font_path: DEFAULT_FONT_PATH.to_string(),
+            seed: None,
+            density: DEFAULT_DENSITY,
+            last_seed: None,
+            color_mode: ColorMode::default(),
+            gradient: Gradient::default(),
+            trail_length: DEFAULT_TRAIL_LENGTH,
+            stats_length: DEFAULT_STATS_LENGTH,
+            graph_length: DEFAULT_GRAPH_LENGTH,
         };
         new_instance.calculate_unit_grid();
         new_instance
     }
 
     fn calculate_unit_grid(&mut self) {
+        // a camera that was not moved keeps showing the whole grid
+        let home = self.camera == Camera::new(self.unit_grid);
         self.unit_grid = self.window_min_length as f32 / self.size_grid as f32;
+        if home {
+            self.camera = Camera::new(self.unit_grid);
+        }
     }
 
     /// Returns the number of iterations per second since the game started.
     pub fn get_iteration_per_second(&self) -> f64 {
-        let n1 = (self.get_iteration() - self.get_start_time_iteration()) as f64
-            / (chrono::Local::now() - self.get_start_time()).num_seconds() as f64;
+        let milliseconds = (chrono::Local::now() - self.get_start_time()).num_milliseconds();
+        if milliseconds <= 0 {
+            return 0.0;
+        }
+        let n1 = self
+            .get_iteration()
+            .saturating_sub(self.get_start_time_iteration()) as f64
+            * 1000.0
+            / milliseconds as f64;
         (n1 * 10.0).trunc() / 10.0
     }
 
@@ -89,8 +157,13 @@ impl Game {
         self.unit_grid
     }
 
+    /// Returns the camera the grid is seen through.
+    pub fn get_camera(&self) -> Camera {
+        self.camera
+    }
+
     /// Returns the current iteration count.
-    pub fn get_iteration(&self) -> u32 {
+    pub fn get_iteration(&self) -> u64 {
         self.iteration
     }
 
@@ -100,12 +173,13 @@ impl Game {
     }
 
     /// Returns the iteration count at the start time.
-    pub fn get_start_time_iteration(&self) -> u32 {
+    pub fn get_start_time_iteration(&self) -> u64 {
         self.start_time_iteration
     }
 
-    /// Returns the maximum allowed iterations per second.
-    pub fn get_max_iteration_per_second(&self) -> u32 {
+    /// Returns the maximum allowed iterations per second, `f64::INFINITY`
+    /// meaning as fast as possible.
+    pub fn get_max_iteration_per_second(&self) -> f64 {
         self.max_iteration_per_second
     }
 
@@ -114,6 +188,107 @@ impl Game {
         self.window_min_length
     }
 
+    /// Returns the birth/survival rule used to compute the next generation.
+    pub fn get_rule(&self) -> Rule {
+        self.rule
+    }
+
+    /// Returns how the edges of the grid are connected.
+    pub fn get_topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// Returns the number of threads used to compute a generation.
+    pub fn get_thread_count(&self) -> usize {
+        self.thread_count
+    }
+
+    /// Returns the simulation engine.
+    pub fn get_engine(&self) -> Engine {
+        self.engine
+    }
+
+    /// Returns the number of generations computed by the step-by-N action.
+    pub fn get_step_count(&self) -> u64 {
+        self.step_count
+    }
+
+    /// Returns the number of generations and edits kept to be undone.
+    pub fn get_history_length(&self) -> usize {
+        self.history_length
+    }
+
+    /// Returns the step exponent: the HashLife engine advances 2^step_log2 generations per step.
+    pub fn get_step_log2(&self) -> u8 {
+        self.step_log2
+    }
+
+    /// Returns the step exponent the engine applies: the setting on the HashLife
+    /// engine, 0 on the others, which compute one generation at a time.
+    pub fn get_engine_step_log2(&self) -> u8 {
+        match self.engine {
+            Engine::HashLife => self.step_log2,
+            _ => 0,
+        }
+    }
+
+    /// Returns the plane coordinates of the top-left cell of the grid,
+    /// used by the engines that run on an unbounded plane.
+    pub fn get_viewport_origin(&self) -> (i64, i64) {
+        self.viewport_origin
+    }
+
+    /// Returns the path of the TTF font used for the HUD.
+    pub fn get_font_path(&self) -> String {
+        self.font_path.clone()
+    }
+
+    /// Returns the seed of the random soup the grid starts with, if any.
+    pub fn get_seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// Returns the part of the cells brought to life by a random fill, from 0 to 1.
+    pub fn get_density(&self) -> f64 {
+        self.density
+    }
+
+    /// Returns the seed of the last random fill, if any.
+    pub fn get_last_seed(&self) -> Option<u64> {
+        self.last_seed
+    }
+
+    /// Returns how the live cells are colored.
+    pub fn get_color_mode(&self) -> ColorMode {
+        self.color_mode
+    }
+
+    /// Returns the colors of the cells by age.
+    pub fn get_gradient(&self) -> Gradient {
+        self.gradient.clone()
+    }
+
+    /// Returns the number of generations a dead cell stays in the fade trail.
+    pub fn get_trail_length(&self) -> u8 {
+        self.trail_length
+    }
+
+    /// Returns the number of generations whose statistics are kept.
+    pub fn get_stats_length(&self) -> usize {
+        self.stats_length
+    }
+
+    /// Returns the number of generations shown in the population graph, 0 hiding it.
+    pub fn get_graph_length(&self) -> usize {
+        self.graph_length
+    }
+
+    /// Returns the seed of the next random fill: the seed given on the command
+    /// line, so that the soup can be reproduced, otherwise a new random one.
+    pub fn next_seed(&self) -> u64 {
+        self.seed.unwrap_or_else(rand::random)
+    }
+
     /// Sets the current game state.
     pub fn set_game_state(&mut self, game_state: GameStatus) {
         self.game_state = game_state;
@@ -122,6 +297,7 @@ impl Game {
     /// Sets the grid size and updates the unit grid.
     pub fn set_size_grid(&mut self, size_grid: u32) {
         self.size_grid = size_grid;
+        self.calculate_unit_grid();
     }
 
     /// Sets the window height.
@@ -143,18 +319,128 @@ impl Game {
         self.unit_grid = unit_grid;
     }
 
+    /// Sets the camera the grid is seen through.
+    pub fn set_camera(&mut self, camera: Camera) {
+        self.camera = camera;
+    }
+
+    /// Moves the camera back to the whole grid, `unit_grid` pixels per cell.
+    pub fn reset_camera(&mut self) {
+        self.camera = Camera::new(self.unit_grid);
+    }
+
     /// Sets the current iteration count.
-    pub fn set_iteration(&mut self, iteration: u32) {
+    pub fn set_iteration(&mut self, iteration: u64) {
         self.iteration = iteration;
     }
 
+    /// Sets the maximum allowed iterations per second, `f64::INFINITY`
+    /// meaning as fast as possible. Slower speeds are raised to `MIN_ITERATION_PER_SECOND`.
+    pub fn set_max_iteration_per_second(&mut self, max_iteration_per_second: f64) {
+        self.max_iteration_per_second = max_iteration_per_second.max(MIN_ITERATION_PER_SECOND);
+        // measure the speed from now on
+        self.set_start_time(chrono::Local::now());
+        self.set_start_time_iteration(self.get_iteration());
+    }
+
+    /// Sets the birth/survival rule used to compute the next generation.
+    pub fn set_rule(&mut self, rule: Rule) {
+        self.rule = rule;
+    }
+
+    /// Sets how the edges of the grid are connected.
+    pub fn set_topology(&mut self, topology: Topology) {
+        self.topology = topology;
+    }
+
+    /// Sets the number of threads used to compute a generation (at least one).
+    pub fn set_thread_count(&mut self, thread_count: usize) {
+        self.thread_count = thread_count.max(1);
+    }
+
+    /// Sets the simulation engine.
+    pub fn set_engine(&mut self, engine: Engine) {
+        self.engine = engine;
+    }
+
+    /// Sets the step exponent, capped at `MAX_STEP_LOG2`.
+    pub fn set_step_log2(&mut self, step_log2: u8) {
+        self.step_log2 = step_log2.min(MAX_STEP_LOG2);
+    }
+
+    /// Sets the number of generations and edits kept to be undone, 0 disabling the history.
+    pub fn set_history_length(&mut self, history_length: usize) {
+        self.history_length = history_length;
+    }
+
+    /// Sets the number of generations computed by the step-by-N action, at least 1.
+    pub fn set_step_count(&mut self, step_count: u64) {
+        self.step_count = step_count.max(1);
+    }
+
+    /// Sets the plane coordinates of the top-left cell of the grid.
+    pub fn set_viewport_origin(&mut self, viewport_origin: (i64, i64)) {
+        self.viewport_origin = viewport_origin;
+    }
+
+    /// Moves the viewport by `(dx, dy)` cells.
+    pub fn move_viewport(&mut self, dx: i64, dy: i64) {
+        let (x, y) = self.viewport_origin;
+        self.viewport_origin = (x.saturating_add(dx), y.saturating_add(dy));
+    }
+
+    /// Sets the path of the TTF font used for the HUD.
+    pub fn set_font_path(&mut self, font_path: String) {
+        self.font_path = font_path;
+    }
+
+    /// Sets the seed of the random soup the grid starts with.
+    pub fn set_seed(&mut self, seed: Option<u64>) {
+        self.seed = seed;
+    }
+
+    /// Sets the part of the cells brought to life by a random fill, clamped to 0 to 1.
+    pub fn set_density(&mut self, density: f64) {
+        self.density = density.clamp(0.0, 1.0);
+    }
+
+    /// Sets the seed of the last random fill.
+    pub fn set_last_seed(&mut self, last_seed: Option<u64>) {
+        self.last_seed = last_seed;
+    }
+
+    /// Sets how the live cells are colored.
+    pub fn set_color_mode(&mut self, color_mode: ColorMode) {
+        self.color_mode = color_mode;
+    }
+
+    /// Sets the colors of the cells by age.
+    pub fn set_gradient(&mut self, gradient: Gradient) {
+        self.gradient = gradient;
+    }
+
+    /// Sets the number of generations a dead cell stays in the fade trail.
+    pub fn set_trail_length(&mut self, trail_length: u8) {
+        self.trail_length = trail_length;
+    }
+
+    /// Sets the number of generations whose statistics are kept.
+    pub fn set_stats_length(&mut self, stats_length: usize) {
+        self.stats_length = stats_length;
+    }
+
+    /// Sets the number of generations shown in the population graph, 0 hiding it.
+    pub fn set_graph_length(&mut self, graph_length: usize) {
+        self.graph_length = graph_length;
+    }
+
     /// Sets the start time of the game.
     pub fn set_start_time(&mut self, start_time: chrono::DateTime<chrono::Local>) {
         self.start_time = start_time;
     }
 
     /// Sets the iteration count at the start time.
-    pub fn set_start_time_iteration(&mut self, start_time_iteration: u32) {
+    pub fn set_start_time_iteration(&mut self, start_time_iteration: u64) {
         self.start_time_iteration = start_time_iteration;
     }
 
@@ -165,6 +451,18 @@ impl Game {
         self.set_start_time_iteration(self.get_iteration());
     }
 
+    /// Pauses the game after `generations` more generations, adding them to
+    /// the ones still to compute if the game is already stepping.
+    pub fn step(&mut self, generations: u64) {
+        let remaining = match self.get_game_state() {
+            GameStatus::Step(remaining) => remaining,
+            _ => 0,
+        };
+        if generations > 0 {
+            self.set_game_state(GameStatus::Step(remaining.saturating_add(generations)));
+        }
+    }
+
     /// Resets the game to its initial state.
     pub fn reset(&mut self) {
         self.set_iteration(0);
@@ -0,0 +1,552 @@
+use crate::rule::Rule;
+use crate::topology::Topology;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+/// Number of cells stored in each word of a row.
+pub const WORD_BITS: usize = 64;
+
+/// Bit-packed grid of cells, storing 64 cells per `u64` in one contiguous buffer.
+///
+/// Rows are laid out one after another, each padded to a whole number of words.
+/// Bit `x % 64` of word `x / 64` of a row holds the cell in column `x`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Grid {
+    width: usize,
+    height: usize,
+    words_per_row: usize,
+    cells: Vec<u64>,
+}
+
+impl Grid {
+    /// Creates an empty grid of `width` columns and `height` rows.
+    pub fn new(width: usize, height: usize) -> Self {
+        let words_per_row = width.div_ceil(WORD_BITS);
+        Self {
+            width,
+            height,
+            words_per_row,
+            cells: vec![0; words_per_row * height],
+        }
+    }
+
+    /// Creates a grid from rows of booleans, `rows[y][x]` being the cell in column `x` of row `y`.
+    pub fn from_rows(rows: &[Vec<bool>]) -> Self {
+        let width = rows.first().map_or(0, |row| row.len());
+        let mut grid = Self::new(width, rows.len());
+        for (y, row) in rows.iter().enumerate() {
+            for (x, &cell) in row.iter().enumerate().take(width) {
+                grid.set(x, y, cell);
+            }
+        }
+        grid
+    }
+
+    /// Returns the grid as rows of booleans.
+    pub fn to_rows(&self) -> Vec<Vec<bool>> {
+        (0..self.height)
+            .map(|y| (0..self.width).map(|x| self.get(x, y)).collect())
+            .collect()
+    }
+
+    /// Returns the number of columns.
+    pub fn get_width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the number of rows.
+    pub fn get_height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the number of `u64` words used by each row.
+    pub fn get_words_per_row(&self) -> usize {
+        self.words_per_row
+    }
+
+    /// Returns the words of row `y`.
+    pub fn get_row(&self, y: usize) -> &[u64] {
+        &self.cells[y * self.words_per_row..(y + 1) * self.words_per_row]
+    }
+
+    /// Returns true if `(x, y)` is inside the grid.
+    pub fn contains(&self, x: i64, y: i64) -> bool {
+        x >= 0 && (x as usize) < self.width && y >= 0 && (y as usize) < self.height
+    }
+
+    /// Returns the state of the cell in column `x` of row `y`.
+    /// Cells outside the grid are dead.
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        if x >= self.width || y >= self.height {
+            return false;
+        }
+        let word = self.cells[y * self.words_per_row + x / WORD_BITS];
+        (word >> (x % WORD_BITS)) & 1 == 1
+    }
+
+    /// Sets the state of the cell in column `x` of row `y`.
+    /// Cells outside the grid are ignored.
+    pub fn set(&mut self, x: usize, y: usize, alive: bool) {
+        if x >= self.width || y >= self.height {
+            return;
+        }
+        let word = &mut self.cells[y * self.words_per_row + x / WORD_BITS];
+        let mask = 1u64 << (x % WORD_BITS);
+        if alive {
+            *word |= mask;
+        } else {
+            *word &= !mask;
+        }
+    }
+
+    /// Flips the state of the cell in column `x` of row `y`.
+    pub fn toggle(&mut self, x: usize, y: usize) {
+        let alive = self.get(x, y);
+        self.set(x, y, !alive);
+    }
+
+    /// Kills every cell.
+    pub fn clear(&mut self) {
+        self.cells.iter_mut().for_each(|word| *word = 0);
+    }
+
+    /// Returns the number of live cells.
+    pub fn population(&self) -> usize {
+        self.cells
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Iterates over the `(x, y)` coordinates of the live cells, row by row.
+    pub fn live_cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.cells
+            .chunks(self.words_per_row.max(1))
+            .enumerate()
+            .flat_map(|(y, row)| {
+                row.iter().enumerate().flat_map(move |(k, &word)| {
+                    let mut bits = word;
+                    std::iter::from_fn(move || {
+                        if bits == 0 {
+                            return None;
+                        }
+                        let bit = bits.trailing_zeros() as usize;
+                        bits &= bits - 1;
+                        Some((k * WORD_BITS + bit, y))
+                    })
+                })
+            })
+    }
+
+    /// Returns `(min_x, min_y, max_x, max_y)` of the live cells, or `None` if every cell is dead.
+    pub fn bounding_box(&self) -> Option<(usize, usize, usize, usize)> {
+        self.live_cells().fold(None, |bounds, (x, y)| {
+            Some(match bounds {
+                None => (x, y, x, y),
+                Some((min_x, min_y, max_x, max_y)) => {
+                    (min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y))
+                }
+            })
+        })
+    }
+
+    /// Returns a copy of the `width` by `height` cells whose top-left corner is
+    /// `(x, y)`. Cells outside the grid are dead.
+    pub fn crop(&self, x: usize, y: usize, width: usize, height: usize) -> Grid {
+        let mut cropped = Grid::new(width, height);
+        for row in 0..height {
+            for column in 0..width {
+                cropped.set(column, row, self.get(x + column, y + row));
+            }
+        }
+        cropped
+    }
+
+    /// Copies the cells of `other` with its top-left corner at `(x, y)`,
+    /// replacing the cells under it. Cells falling outside the grid are ignored.
+    pub fn paste(&mut self, other: &Grid, x: i64, y: i64) {
+        for row in 0..other.height {
+            for column in 0..other.width {
+                let (target_x, target_y) = (x + column as i64, y + row as i64);
+                if self.contains(target_x, target_y) {
+                    self.set(target_x as usize, target_y as usize, other.get(column, row));
+                }
+            }
+        }
+    }
+
+    /// Sets the state of the `width` by `height` cells whose top-left corner is `(x, y)`.
+    pub fn fill_region(&mut self, x: usize, y: usize, width: usize, height: usize, alive: bool) {
+        for row in y..(y + height).min(self.height) {
+            for column in x..(x + width).min(self.width) {
+                self.set(column, row, alive);
+            }
+        }
+    }
+
+    /// Returns the grid turned a quarter turn clockwise.
+    pub fn rotate_clockwise(&self) -> Grid {
+        let mut rotated = Grid::new(self.height, self.width);
+        for (x, y) in self.live_cells() {
+            rotated.set(self.height - 1 - y, x, true);
+        }
+        rotated
+    }
+
+    /// Returns the grid mirrored left to right.
+    pub fn flip_horizontal(&self) -> Grid {
+        let mut flipped = Grid::new(self.width, self.height);
+        for (x, y) in self.live_cells() {
+            flipped.set(self.width - 1 - x, y, true);
+        }
+        flipped
+    }
+
+    /// Returns the grid mirrored top to bottom.
+    pub fn flip_vertical(&self) -> Grid {
+        let mut flipped = Grid::new(self.width, self.height);
+        for (x, y) in self.live_cells() {
+            flipped.set(x, self.height - 1 - y, true);
+        }
+        flipped
+    }
+
+    /// Returns the `(x, y)` coordinates of the cells whose state differs from
+    /// `other`, row by row. Cells outside `other` are compared to dead cells.
+    pub fn changed_cells(&self, other: &Grid) -> Vec<(usize, usize)> {
+        if other.width != self.width || other.height != self.height {
+            return (0..self.height)
+                .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+                .filter(|&(x, y)| self.get(x, y) != other.get(x, y))
+                .collect();
+        }
+        let mut changed = Vec::new();
+        for (index, (&word, &other_word)) in self.cells.iter().zip(&other.cells).enumerate() {
+            let mut bits = word ^ other_word;
+            while bits != 0 {
+                let bit = bits.trailing_zeros() as usize;
+                bits &= bits - 1;
+                let y = index / self.words_per_row;
+                let x = (index % self.words_per_row) * WORD_BITS + bit;
+                changed.push((x, y));
+            }
+        }
+        changed
+    }
+
+    /// Returns the words that differ from `other`, as `(index, self ^ other)`
+    /// pairs sorted by index. Both grids must have the same size.
+    pub fn xor_delta(&self, other: &Grid) -> Vec<(usize, u64)> {
+        self.cells
+            .iter()
+            .zip(&other.cells)
+            .enumerate()
+            .filter(|(_, (word, other_word))| word != other_word)
+            .map(|(index, (word, other_word))| (index, word ^ other_word))
+            .collect()
+    }
+
+    /// Flips the cells set in a delta returned by `xor_delta`, so that applying
+    /// `a.xor_delta(&b)` to `b` gives `a`, and applying it to `a` gives `b`.
+    pub fn apply_xor_delta(&mut self, delta: &[(usize, u64)]) {
+        for &(index, bits) in delta {
+            if let Some(word) = self.cells.get_mut(index) {
+                *word ^= bits;
+            }
+        }
+    }
+
+    /// Computes the next generation into `next`, which is resized to match this grid.
+    ///
+    /// Neighbor counts are computed 64 cells at a time with bit-sliced adders, and the
+    /// rows are split into one band per thread of `pool`, computed in parallel. The
+    /// result does not depend on the number of threads.
+    pub fn next_generation_into(
+        &self,
+        next: &mut Grid,
+        rule: &Rule,
+        topology: Topology,
+        pool: &mut BandPool,
+    ) {
+        if next.width != self.width || next.height != self.height {
+            *next = Grid::new(self.width, self.height);
+        }
+        if self.width == 0 || self.height == 0 {
+            return;
+        }
+
+        let padded = Arc::new(PaddedGrid::new(self, topology));
+        let rule = Arc::new(BandRule {
+            born: (0..=8).filter(|&n| rule.is_born(n)).collect(),
+            survive: (0..=8).filter(|&n| rule.survives(n)).collect(),
+            words_per_row: self.words_per_row,
+            last_word_mask: match self.width % WORD_BITS {
+                0 => u64::MAX,
+                bits => (1u64 << bits) - 1,
+            },
+        });
+        let band_rows = self.height.div_ceil(pool.get_thread_count());
+        let band_words = band_rows * self.words_per_row;
+
+        // the workers take the bands after the first one
+        let mut sent = Vec::new();
+        for (index, worker) in pool.workers.iter_mut().enumerate() {
+            let first_row = (index + 1) * band_rows;
+            if first_row >= self.height {
+                break;
+            }
+            let mut band = std::mem::take(&mut worker.buffer);
+            band.clear();
+            band.resize(
+                band_rows.min(self.height - first_row) * self.words_per_row,
+                0,
+            );
+            let job = BandJob {
+                padded: Arc::clone(&padded),
+                rule: Arc::clone(&rule),
+                first_row,
+                band,
+            };
+            match worker.jobs.send(job) {
+                Ok(()) => sent.push(index),
+                // the worker is gone: compute its band here
+                Err(error) => {
+                    let mut job = error.0;
+                    job.compute();
+                    next.cells[first_row * self.words_per_row..][..job.band.len()]
+                        .copy_from_slice(&job.band);
+                    worker.buffer = job.band;
+                }
+            }
+        }
+
+        let first_band = band_words.min(next.cells.len());
+        rule.compute(&padded, &mut next.cells[..first_band], 0);
+
+        for index in sent {
+            let worker = &mut pool.workers[index];
+            let first_row = (index + 1) * band_rows;
+            let offset = first_row * self.words_per_row;
+            let end = (offset + band_words).min(next.cells.len());
+            let band = &mut next.cells[offset..end];
+            match worker.bands.recv() {
+                Ok(job) => {
+                    band.copy_from_slice(&job.band);
+                    worker.buffer = job.band;
+                }
+                Err(_) => rule.compute(&padded, band, first_row),
+            }
+        }
+    }
+}
+
+/// Worker threads computing the bands of rows of the next generation, kept
+/// alive from one generation to the next.
+///
+/// The calling thread computes the first band, and each worker the band after
+/// it. A worker computes its band into a buffer of its own, sent back and
+/// copied into the next grid, then reused for the next generation.
+pub struct BandPool {
+    workers: Vec<BandWorker>,
+}
+
+impl BandPool {
+    /// Creates a pool computing each generation on `thread_count` threads (at
+    /// least one), the calling thread included.
+    pub fn new(thread_count: usize) -> Self {
+        let workers = (1..thread_count.max(1))
+            .map_while(|_| BandWorker::spawn())
+            .collect();
+        Self { workers }
+    }
+
+    /// Returns the number of threads computing a generation, the calling
+    /// thread included.
+    pub fn get_thread_count(&self) -> usize {
+        self.workers.len() + 1
+    }
+}
+
+impl Drop for BandPool {
+    fn drop(&mut self) {
+        for worker in self.workers.drain(..) {
+            // closing the channel ends the loop of the worker
+            drop(worker.jobs);
+            let _ = worker.thread.join();
+        }
+    }
+}
+
+/// Thread of a `BandPool`, with the channels of its jobs.
+struct BandWorker {
+    jobs: Sender<BandJob>,
+    bands: Receiver<BandJob>,
+    buffer: Vec<u64>,
+    thread: JoinHandle<()>,
+}
+
+impl BandWorker {
+    /// Starts a worker, or returns `None` if no thread could be spawned.
+    fn spawn() -> Option<Self> {
+        let (jobs, job_receiver) = mpsc::channel::<BandJob>();
+        let (band_sender, bands) = mpsc::channel();
+        let thread = std::thread::Builder::new()
+            .name("generation band".to_string())
+            .spawn(move || {
+                while let Ok(mut job) = job_receiver.recv() {
+                    job.compute();
+                    if band_sender.send(job).is_err() {
+                        break;
+                    }
+                }
+            })
+            .ok()?;
+        Some(Self {
+            jobs,
+            bands,
+            buffer: Vec::new(),
+            thread,
+        })
+    }
+}
+
+/// Rule and row layout shared by the bands of one generation.
+struct BandRule {
+    born: Vec<u8>,
+    survive: Vec<u8>,
+    words_per_row: usize,
+    last_word_mask: u64,
+}
+
+impl BandRule {
+    /// Computes the rows of `band` of the next generation, from grid row `first_row`.
+    fn compute(&self, padded: &PaddedGrid, band: &mut [u64], first_row: usize) {
+        for (offset, row) in band.chunks_mut(self.words_per_row).enumerate() {
+            padded.compute_row(first_row + offset, row, &self.born, &self.survive);
+            if let Some(last) = row.last_mut() {
+                *last &= self.last_word_mask;
+            }
+        }
+    }
+}
+
+/// Band of rows sent to a worker, returned with its rows computed.
+struct BandJob {
+    padded: Arc<PaddedGrid>,
+    rule: Arc<BandRule>,
+    first_row: usize,
+    band: Vec<u64>,
+}
+
+impl BandJob {
+    fn compute(&mut self) {
+        self.rule
+            .compute(&self.padded, &mut self.band, self.first_row);
+    }
+}
+
+/// Copy of a grid surrounded by a one-cell border whose cells are resolved through a topology.
+///
+/// Padded row `y + 1` holds grid row `y`, and padded bit `x + 1` holds grid column `x`.
+/// Each row has one extra zero word so that shifted reads never go out of bounds.
+struct PaddedGrid {
+    words_per_row: usize,
+    cells: Vec<u64>,
+}
+
+impl PaddedGrid {
+    fn new(grid: &Grid, topology: Topology) -> Self {
+        let width = grid.width as i64;
+        let height = grid.height as i64;
+        let words_per_row = (grid.width + 2).div_ceil(WORD_BITS) + 1;
+        let mut cells = vec![0u64; words_per_row * (grid.height + 2)];
+
+        let set = |cells: &mut [u64], padded_x: usize, padded_y: usize| {
+            cells[padded_y * words_per_row + padded_x / WORD_BITS] |= 1 << (padded_x % WORD_BITS);
+        };
+        let resolve = |x: i64, y: i64| {
+            topology
+                .resolve(x, y, width, height)
+                .is_some_and(|(x, y)| grid.get(x as usize, y as usize))
+        };
+
+        for y in 0..grid.height {
+            // interior cells, shifted by one bit
+            let row = grid.get_row(y);
+            let target = &mut cells[(y + 1) * words_per_row..(y + 2) * words_per_row];
+            for (k, &word) in row.iter().enumerate() {
+                target[k] |= word << 1;
+                target[k + 1] |= word >> (WORD_BITS - 1);
+            }
+            if resolve(-1, y as i64) {
+                set(&mut cells, 0, y + 1);
+            }
+            if resolve(width, y as i64) {
+                set(&mut cells, grid.width + 1, y + 1);
+            }
+        }
+        for (padded_y, y) in [(0, -1), (grid.height + 1, height)] {
+            for x in -1..=width {
+                if resolve(x, y) {
+                    set(&mut cells, (x + 1) as usize, padded_y);
+                }
+            }
+        }
+
+        Self {
+            words_per_row,
+            cells,
+        }
+    }
+
+    fn row(&self, padded_y: usize) -> &[u64] {
+        &self.cells[padded_y * self.words_per_row..(padded_y + 1) * self.words_per_row]
+    }
+
+    /// Computes grid row `y` of the next generation into `out`.
+    fn compute_row(&self, y: usize, out: &mut [u64], born: &[u8], survive: &[u8]) {
+        let above = self.row(y);
+        let middle = self.row(y + 1);
+        let below = self.row(y + 2);
+
+        for (k, out_word) in out.iter_mut().enumerate() {
+            let left = |row: &[u64]| row[k];
+            let center = |row: &[u64]| (row[k] >> 1) | (row[k + 1] << (WORD_BITS - 1));
+            let right = |row: &[u64]| (row[k] >> 2) | (row[k + 1] << (WORD_BITS - 2));
+
+            let neighbors = [
+                left(above),
+                center(above),
+                right(above),
+                left(middle),
+                right(middle),
+                left(below),
+                center(below),
+                right(below),
+            ];
+
+            // bit-sliced ripple counter: (s3 s2 s1 s0) is the neighbor count of each lane
+            let (mut s0, mut s1, mut s2, mut s3) = (0u64, 0u64, 0u64, 0u64);
+            for neighbor in neighbors {
+                let carry0 = s0 & neighbor;
+                s0 ^= neighbor;
+                let carry1 = s1 & carry0;
+                s1 ^= carry0;
+                let carry2 = s2 & carry1;
+                s2 ^= carry1;
+                s3 |= carry2;
+            }
+
+            let count_is = |count: u8| {
+                let bit = |index: u8, sum: u64| if count >> index & 1 == 1 { sum } else { !sum };
+                bit(0, s0) & bit(1, s1) & bit(2, s2) & bit(3, s3)
+            };
+            let born_mask = born.iter().fold(0, |acc, &count| acc | count_is(count));
+            let survive_mask = survive.iter().fold(0, |acc, &count| acc | count_is(count));
+
+            let alive = center(middle);
+            *out_word = (alive & survive_mask) | (!alive & born_mask);
+        }
+    }
+}
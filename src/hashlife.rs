@@ -0,0 +1,595 @@
+use crate::engine::Universe;
+use crate::grid::Grid;
+use crate::rule::Rule;
+use std::collections::HashMap;
+
+/// Index of a node in the `HashLife` node arena.
+pub type NodeId = u32;
+
+const DEAD_LEAF: NodeId = 0;
+const ALIVE_LEAF: NodeId = 1;
+
+/// Largest supported step exponent: one step advances at most 2^48 generations.
+pub const MAX_STEP_LOG2: u8 = 48;
+
+/// Largest level of the root node. The universe spans at most 2^62 cells on
+/// each axis, from -2^61 to 2^61 - 1, so that node sizes and coordinates fit
+/// in an `i64`; cells beyond are dropped.
+pub const MAX_LEVEL: u8 = 62;
+
+/// Number of nodes above which unreachable nodes and cached results are dropped.
+const GARBAGE_COLLECTION_THRESHOLD: usize = 1 << 22;
+
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    level: u8,
+    population: u64,
+    children: [NodeId; 4],
+}
+
+/// Unbounded universe stored as a hash-consed quadtree and advanced with the
+/// Hashlife algorithm, memoizing the future of every node it has seen.
+///
+/// The root node is always centered on the origin. Children are ordered
+/// north-west, north-east, south-west, south-east, with `y` growing downwards.
+/// Rules with `B0` are not supported: empty space always stays empty, and
+/// [`Engine::check_rule`](crate::engine::Engine::check_rule) rejects them.
+/// The root grows up to `MAX_LEVEL`: cells set beyond it are ignored, and
+/// cells that move past it are dropped.
+#[derive(Debug, Clone)]
+pub struct HashLife {
+    rule: Rule,
+    nodes: Vec<Node>,
+    index: HashMap<[NodeId; 4], NodeId>,
+    results: HashMap<(NodeId, u8), NodeId>,
+    empty: Vec<NodeId>,
+    root: NodeId,
+    generation: u64,
+}
+
+impl HashLife {
+    /// Creates an empty universe using `rule`.
+    pub fn new(rule: Rule) -> Self {
+        let leaf = |population| Node {
+            level: 0,
+            population,
+            children: [DEAD_LEAF; 4],
+        };
+        let mut universe = Self {
+            rule,
+            nodes: vec![leaf(0), leaf(1)],
+            index: HashMap::new(),
+            results: HashMap::new(),
+            empty: vec![DEAD_LEAF],
+            root: DEAD_LEAF,
+            generation: 0,
+        };
+        universe.root = universe.empty_node(3);
+        universe
+    }
+
+    /// Returns the rule used to advance the universe.
+    pub fn get_rule(&self) -> Rule {
+        self.rule
+    }
+
+    /// Sets the rule used to advance the universe, forgetting memoized results.
+    pub fn set_rule(&mut self, rule: Rule) {
+        if rule != self.rule {
+            self.rule = rule;
+            self.results.clear();
+        }
+    }
+
+    /// Returns the number of generations computed since the universe was created or cleared.
+    pub fn get_generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Sets the generation counter.
+    pub fn set_generation(&mut self, generation: u64) {
+        self.generation = generation;
+    }
+
+    /// Returns the number of live cells.
+    pub fn population(&self) -> u64 {
+        self.nodes[self.root as usize].population
+    }
+
+    /// Returns the level of the root node: the universe currently spans 2^level cells.
+    pub fn get_root_level(&self) -> u8 {
+        self.nodes[self.root as usize].level
+    }
+
+    /// Returns the number of nodes currently stored in the arena.
+    pub fn get_node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Kills every cell and resets the generation counter.
+    pub fn clear(&mut self) {
+        self.root = self.empty_node(3);
+        self.generation = 0;
+    }
+
+    fn half_size(level: u8) -> i64 {
+        1i64 << (level - 1)
+    }
+
+    fn node(&self, id: NodeId) -> &Node {
+        &self.nodes[id as usize]
+    }
+
+    /// Returns the level-0 node for a dead or live cell.
+    pub(crate) fn leaf(alive: bool) -> NodeId {
+        if alive {
+            ALIVE_LEAF
+        } else {
+            DEAD_LEAF
+        }
+    }
+
+    /// Returns the root node.
+    pub(crate) fn get_root(&self) -> NodeId {
+        self.root
+    }
+
+    /// Replaces the root node, which is placed centered on the origin.
+    /// Roots smaller than 8x8 cells are grown to that size.
+    pub(crate) fn set_root(&mut self, id: NodeId) {
+        self.root = if self.node(id).level == 0 {
+            self.join(id, DEAD_LEAF, DEAD_LEAF, DEAD_LEAF)
+        } else {
+            id
+        };
+        while self.get_root_level() < 3 {
+            self.expand();
+        }
+    }
+
+    /// Returns the level of a node: it spans 2^level cells.
+    pub(crate) fn node_level(&self, id: NodeId) -> u8 {
+        self.node(id).level
+    }
+
+    /// Returns the number of live cells of a node.
+    pub(crate) fn node_population(&self, id: NodeId) -> u64 {
+        self.node(id).population
+    }
+
+    /// Returns the north-west, north-east, south-west and south-east children of a node.
+    pub(crate) fn node_children(&self, id: NodeId) -> [NodeId; 4] {
+        self.node(id).children
+    }
+
+    /// Returns the node made of the four given children, which must share the same level.
+    pub(crate) fn join(&mut self, nw: NodeId, ne: NodeId, sw: NodeId, se: NodeId) -> NodeId {
+        let children = [nw, ne, sw, se];
+        if let Some(&id) = self.index.get(&children) {
+            return id;
+        }
+        let level = self.node(nw).level + 1;
+        let population = children.iter().map(|&c| self.node(c).population).sum();
+        let id = self.nodes.len() as NodeId;
+        self.nodes.push(Node {
+            level,
+            population,
+            children,
+        });
+        self.index.insert(children, id);
+        id
+    }
+
+    /// Returns the empty node of the given level.
+    pub(crate) fn empty_node(&mut self, level: u8) -> NodeId {
+        while self.empty.len() <= level as usize {
+            let child = *self.empty.last().unwrap_or(&DEAD_LEAF);
+            let id = self.join(child, child, child, child);
+            self.empty.push(id);
+        }
+        self.empty[level as usize]
+    }
+
+    /// Wraps the root in a node twice as large, keeping it centered.
+    /// The root must be below `MAX_LEVEL`.
+    fn expand(&mut self) {
+        let root = *self.node(self.root);
+        let [nw, ne, sw, se] = root.children;
+        let border = self.empty_node(root.level - 1);
+        let nw = self.join(border, border, border, nw);
+        let ne = self.join(border, border, ne, border);
+        let sw = self.join(border, sw, border, border);
+        let se = self.join(se, border, border, border);
+        self.root = self.join(nw, ne, sw, se);
+    }
+
+    fn contains(&self, x: i64, y: i64) -> bool {
+        let half = Self::half_size(self.get_root_level());
+        (-half..half).contains(&x) && (-half..half).contains(&y)
+    }
+
+    /// Returns the state of the cell at `(x, y)`.
+    pub fn get(&self, x: i64, y: i64) -> bool {
+        if !self.contains(x, y) {
+            return false;
+        }
+        let half = Self::half_size(self.get_root_level());
+        let (mut x, mut y) = (x + half, y + half);
+        let mut id = self.root;
+        loop {
+            let node = self.node(id);
+            if node.level == 0 {
+                return id == ALIVE_LEAF;
+            }
+            if node.population == 0 {
+                return false;
+            }
+            let half = 1i64 << (node.level - 1);
+            let quadrant = (x >= half) as usize + 2 * (y >= half) as usize;
+            x %= half;
+            y %= half;
+            id = node.children[quadrant];
+        }
+    }
+
+    /// Sets the state of the cell at `(x, y)`, growing the universe if needed.
+    /// Cells beyond the reach of a root of `MAX_LEVEL` are ignored.
+    pub fn set(&mut self, x: i64, y: i64, alive: bool) {
+        if !alive && !self.contains(x, y) {
+            return;
+        }
+        while !self.contains(x, y) {
+            if self.get_root_level() >= MAX_LEVEL {
+                return;
+            }
+            self.expand();
+        }
+        let half = Self::half_size(self.get_root_level());
+        self.root = self.set_in(self.root, x + half, y + half, alive);
+    }
+
+    fn set_in(&mut self, id: NodeId, x: i64, y: i64, alive: bool) -> NodeId {
+        let node = *self.node(id);
+        if node.level == 0 {
+            return if alive { ALIVE_LEAF } else { DEAD_LEAF };
+        }
+        let half = 1i64 << (node.level - 1);
+        let quadrant = (x >= half) as usize + 2 * (y >= half) as usize;
+        let mut children = node.children;
+        children[quadrant] = self.set_in(children[quadrant], x % half, y % half, alive);
+        self.join(children[0], children[1], children[2], children[3])
+    }
+
+    /// Replaces the content of the universe with the live cells of `grid`,
+    /// the top-left cell of the grid being placed at `(origin_x, origin_y)`.
+    /// Cells beyond the reach of a root of `MAX_LEVEL` are dropped.
+    pub fn load_grid(&mut self, grid: &Grid, origin_x: i64, origin_y: i64) {
+        let extent = [
+            origin_x,
+            origin_y,
+            origin_x.saturating_add(grid.get_width() as i64),
+            origin_y.saturating_add(grid.get_height() as i64),
+        ]
+        .iter()
+        .map(|v| v.unsigned_abs())
+        .max()
+        .unwrap_or(0);
+        let mut level = 3;
+        while level < MAX_LEVEL && (Self::half_size(level) as u64) < extent {
+            level += 1;
+        }
+        let half = Self::half_size(level);
+        self.root = self.build(level, -half, -half, grid, origin_x, origin_y);
+    }
+
+    fn build(
+        &mut self,
+        level: u8,
+        x: i64,
+        y: i64,
+        grid: &Grid,
+        origin_x: i64,
+        origin_y: i64,
+    ) -> NodeId {
+        let size = 1i64 << level;
+        // the origin may be far outside the root
+        let (left, top) = (x.saturating_sub(origin_x), y.saturating_sub(origin_y));
+        if left >= grid.get_width() as i64
+            || top >= grid.get_height() as i64
+            || left.saturating_add(size) <= 0
+            || top.saturating_add(size) <= 0
+        {
+            return self.empty_node(level);
+        }
+        if level == 0 {
+            return if grid.get(left as usize, top as usize) {
+                ALIVE_LEAF
+            } else {
+                DEAD_LEAF
+            };
+        }
+        let half = size / 2;
+        let nw = self.build(level - 1, x, y, grid, origin_x, origin_y);
+        let ne = self.build(level - 1, x + half, y, grid, origin_x, origin_y);
+        let sw = self.build(level - 1, x, y + half, grid, origin_x, origin_y);
+        let se = self.build(level - 1, x + half, y + half, grid, origin_x, origin_y);
+        self.join(nw, ne, sw, se)
+    }
+
+    /// Copies the square of the universe covered by `grid`, whose top-left cell
+    /// is at `(origin_x, origin_y)`, into `grid`.
+    pub fn write_to_grid(&self, grid: &mut Grid, origin_x: i64, origin_y: i64) {
+        grid.clear();
+        let width = grid.get_width() as i64;
+        let height = grid.get_height() as i64;
+        self.visit_live_cells(
+            origin_x,
+            origin_y,
+            origin_x.saturating_add(width),
+            origin_y.saturating_add(height),
+            &mut |x, y| {
+                grid.set((x - origin_x) as usize, (y - origin_y) as usize, true);
+            },
+        );
+    }
+
+    /// Returns the coordinates of every live cell, sorted by row then column.
+    pub fn live_cells(&self) -> Vec<(i64, i64)> {
+        let mut cells = Vec::with_capacity(self.population() as usize);
+        self.visit_live_cells(i64::MIN, i64::MIN, i64::MAX, i64::MAX, &mut |x, y| {
+            cells.push((x, y));
+        });
+        cells.sort_by_key(|&(x, y)| (y, x));
+        cells
+    }
+
+    /// Calls `visit` for every live cell with `left <= x < right` and `top <= y < bottom`.
+    pub fn visit_live_cells(
+        &self,
+        left: i64,
+        top: i64,
+        right: i64,
+        bottom: i64,
+        visit: &mut impl FnMut(i64, i64),
+    ) {
+        let half = Self::half_size(self.get_root_level());
+        self.visit_in(self.root, -half, -half, [left, top, right, bottom], visit);
+    }
+
+    fn visit_in(
+        &self,
+        id: NodeId,
+        x: i64,
+        y: i64,
+        window: [i64; 4],
+        visit: &mut impl FnMut(i64, i64),
+    ) {
+        let node = self.node(id);
+        let size = 1i64 << node.level;
+        let [left, top, right, bottom] = window;
+        if node.population == 0 || x >= right || y >= bottom || x + size <= left || y + size <= top
+        {
+            return;
+        }
+        if node.level == 0 {
+            visit(x, y);
+            return;
+        }
+        let half = size / 2;
+        let [nw, ne, sw, se] = node.children;
+        self.visit_in(nw, x, y, window, visit);
+        self.visit_in(ne, x + half, y, window, visit);
+        self.visit_in(sw, x, y + half, window, visit);
+        self.visit_in(se, x + half, y + half, window, visit);
+    }
+
+    /// Returns `(min_x, min_y, max_x, max_y)` of the live cells, or `None` if the universe is empty.
+    pub fn bounding_box(&self) -> Option<(i64, i64, i64, i64)> {
+        let mut bounds: Option<(i64, i64, i64, i64)> = None;
+        self.visit_live_cells(i64::MIN, i64::MIN, i64::MAX, i64::MAX, &mut |x, y| {
+            bounds = Some(match bounds {
+                None => (x, y, x, y),
+                Some((min_x, min_y, max_x, max_y)) => {
+                    (min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y))
+                }
+            });
+        });
+        bounds
+    }
+
+    /// Returns true if every live cell is inside the central quarter of the root.
+    fn is_centered(&self) -> bool {
+        let root = *self.node(self.root);
+        let [nw, ne, sw, se] = root.children.map(|c| *self.node(c));
+        let inner = self.node(nw.children[3]).population
+            + self.node(ne.children[2]).population
+            + self.node(sw.children[1]).population
+            + self.node(se.children[0]).population;
+        inner == root.population
+    }
+
+    /// Advances the universe by 2^`step_log2` generations.
+    pub fn step(&mut self, step_log2: u8) {
+        let step_log2 = step_log2.min(MAX_STEP_LOG2);
+        while self.get_root_level() < MAX_LEVEL
+            && (self.get_root_level() < step_log2 + 3 || !self.is_centered())
+        {
+            self.expand();
+        }
+        if self.get_root_level() >= MAX_LEVEL {
+            // no room left around the root: the cells outside its center are dropped
+            self.root = self.center(self.root);
+        }
+        self.expand();
+        self.root = self.successor(self.root, step_log2);
+        self.generation = self.generation.saturating_add(1 << step_log2);
+        if self.nodes.len() > GARBAGE_COLLECTION_THRESHOLD {
+            self.collect_garbage();
+        }
+    }
+
+    /// Returns the level-1 node one generation ahead of the center of a level-2 node.
+    fn base_successor(&mut self, id: NodeId) -> NodeId {
+        let mut cells = [[false; 4]; 4];
+        for (y, row) in cells.iter_mut().enumerate() {
+            for (x, cell) in row.iter_mut().enumerate() {
+                let quadrant = self.node(id).children[(x >= 2) as usize + 2 * (y >= 2) as usize];
+                let leaf = self.node(quadrant).children[(x % 2) + 2 * (y % 2)];
+                *cell = leaf == ALIVE_LEAF;
+            }
+        }
+        let mut next = [DEAD_LEAF; 4];
+        for (quadrant, (x, y)) in [(1, 1), (2, 1), (1, 2), (2, 2)].into_iter().enumerate() {
+            let mut count = 0;
+            for (ny, row) in cells.iter().enumerate().take(y + 2).skip(y - 1) {
+                for (nx, &cell) in row.iter().enumerate().take(x + 2).skip(x - 1) {
+                    if cell && (nx, ny) != (x, y) {
+                        count += 1;
+                    }
+                }
+            }
+            if self.rule.next_state(cells[y][x], count) {
+                next[quadrant] = ALIVE_LEAF;
+            }
+        }
+        self.join(next[0], next[1], next[2], next[3])
+    }
+
+    /// Returns the center of a node, one level down.
+    fn center(&mut self, id: NodeId) -> NodeId {
+        let [nw, ne, sw, se] = self.node(id).children.map(|c| self.node(c).children);
+        self.join(nw[3], ne[2], sw[1], se[0])
+    }
+
+    /// Returns the center of node `id` (level `L`), one level down and
+    /// 2^`step_log2` generations ahead, with `step_log2 <= L - 2`.
+    fn successor(&mut self, id: NodeId, step_log2: u8) -> NodeId {
+        let node = *self.node(id);
+        if node.population == 0 {
+            return self.empty_node(node.level - 1);
+        }
+        if let Some(&result) = self.results.get(&(id, step_log2)) {
+            return result;
+        }
+        let result = if node.level == 2 {
+            self.base_successor(id)
+        } else {
+            let [nw, ne, sw, se] = node.children.map(|c| self.node(c).children);
+            let n00 = node.children[0];
+            let n01 = self.join(nw[1], ne[0], nw[3], ne[2]);
+            let n02 = node.children[1];
+            let n10 = self.join(nw[2], nw[3], sw[0], sw[1]);
+            let n11 = self.join(nw[3], ne[2], sw[1], se[0]);
+            let n12 = self.join(ne[2], ne[3], se[0], se[1]);
+            let n20 = node.children[2];
+            let n21 = self.join(sw[1], se[0], sw[3], se[2]);
+            let n22 = node.children[3];
+
+            let full_speed = step_log2 == node.level - 2;
+            let mut parts = [n00, n01, n02, n10, n11, n12, n20, n21, n22];
+            for part in parts.iter_mut() {
+                *part = if full_speed {
+                    self.successor(*part, step_log2 - 1)
+                } else {
+                    self.center(*part)
+                };
+            }
+            let [r00, r01, r02, r10, r11, r12, r20, r21, r22] = parts;
+            let inner_step = if full_speed { step_log2 - 1 } else { step_log2 };
+            let quadrants = [
+                self.join(r00, r01, r10, r11),
+                self.join(r01, r02, r11, r12),
+                self.join(r10, r11, r20, r21),
+                self.join(r11, r12, r21, r22),
+            ];
+            let [a, b, c, d] = quadrants.map(|q| self.successor(q, inner_step));
+            self.join(a, b, c, d)
+        };
+        self.results.insert((id, step_log2), result);
+        result
+    }
+
+    /// Drops every node that is not reachable from the root, along with all memoized results.
+    pub fn collect_garbage(&mut self) {
+        let old_nodes = std::mem::take(&mut self.nodes);
+        self.nodes = old_nodes[..2].to_vec();
+        self.index.clear();
+        self.results.clear();
+        self.empty = vec![DEAD_LEAF];
+        let mut remap: HashMap<NodeId, NodeId> = HashMap::new();
+        remap.insert(DEAD_LEAF, DEAD_LEAF);
+        remap.insert(ALIVE_LEAF, ALIVE_LEAF);
+        self.root = self.copy_node(self.root, &old_nodes, &mut remap);
+    }
+
+    fn copy_node(
+        &mut self,
+        id: NodeId,
+        old_nodes: &[Node],
+        remap: &mut HashMap<NodeId, NodeId>,
+    ) -> NodeId {
+        if let Some(&new_id) = remap.get(&id) {
+            return new_id;
+        }
+        let [nw, ne, sw, se] = old_nodes[id as usize]
+            .children
+            .map(|child| self.copy_node(child, old_nodes, remap));
+        let new_id = self.join(nw, ne, sw, se);
+        remap.insert(id, new_id);
+        new_id
+    }
+}
+
+impl Universe for HashLife {
+    fn get_rule(&self) -> Rule {
+        HashLife::get_rule(self)
+    }
+
+    fn set_rule(&mut self, rule: Rule) {
+        HashLife::set_rule(self, rule);
+    }
+
+    fn get(&self, x: i64, y: i64) -> bool {
+        HashLife::get(self, x, y)
+    }
+
+    fn set(&mut self, x: i64, y: i64, alive: bool) {
+        HashLife::set(self, x, y, alive);
+    }
+
+    fn clear(&mut self) {
+        HashLife::clear(self);
+    }
+
+    fn population(&self) -> u64 {
+        HashLife::population(self)
+    }
+
+    fn get_generation(&self) -> u64 {
+        HashLife::get_generation(self)
+    }
+
+    fn set_generation(&mut self, generation: u64) {
+        HashLife::set_generation(self, generation);
+    }
+
+    fn step(&mut self, step_log2: u8) {
+        HashLife::step(self, step_log2);
+    }
+
+    fn live_cells(&self) -> Vec<(i64, i64)> {
+        HashLife::live_cells(self)
+    }
+
+    fn bounding_box(&self) -> Option<(i64, i64, i64, i64)> {
+        HashLife::bounding_box(self)
+    }
+
+    fn write_to_grid(&self, grid: &mut Grid, origin_x: i64, origin_y: i64) {
+        HashLife::write_to_grid(self, grid, origin_x, origin_y);
+    }
+
+    fn load_grid(&mut self, grid: &Grid, origin_x: i64, origin_y: i64) {
+        HashLife::load_grid(self, grid, origin_x, origin_y);
+    }
+}
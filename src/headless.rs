@@ -0,0 +1,568 @@
+use crate::census::{take_census, Census, CensusFormat};
+use crate::cli::Options;
+use crate::cycle::{Cycle, CycleDetector};
+use crate::engine::{Engine, Universe};
+use crate::game::Game;
+use crate::grid::{BandPool, Grid};
+use crate::hashlife::HashLife;
+use crate::pattern::{write_macrocell, write_pattern, Pattern, PatternFormat};
+use crate::sparse::SparseLife;
+use crate::stats::{GenerationStats, StatsFormat, StatsRecorder};
+use std::fmt;
+use std::fs;
+use std::time::{Duration, Instant};
+
+/// Largest number of generations of a run until stabilization when no limit is given.
+pub const DEFAULT_MAX_GENERATIONS: u64 = 100_000;
+
+/// Largest number of cells of a final state written in a cell-by-cell format.
+const MAX_GRID_CELLS: u64 = 1 << 28;
+
+/// Settings of a run without a window.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct HeadlessOptions {
+    generations: Option<u64>,
+    until_stable: bool,
+    output_path: Option<String>,
+    stats_path: Option<String>,
+    format: Option<PatternFormat>,
+    census_path: Option<String>,
+    census_format: Option<CensusFormat>,
+    generation_stats_path: Option<String>,
+    generation_stats_format: Option<StatsFormat>,
+}
+
+impl HeadlessOptions {
+    /// Returns the number of generations to compute, or the limit of a run until stabilization.
+    pub fn get_generations(&self) -> Option<u64> {
+        self.generations
+    }
+
+    /// Returns true if the run stops as soon as the universe settles into a cycle.
+    pub fn get_until_stable(&self) -> bool {
+        self.until_stable
+    }
+
+    /// Returns the file the final state is written to, stdout if `None`.
+    pub fn get_output_path(&self) -> Option<&str> {
+        self.output_path.as_deref()
+    }
+
+    /// Returns the file the statistics are written to.
+    pub fn get_stats_path(&self) -> Option<&str> {
+        self.stats_path.as_deref()
+    }
+
+    /// Returns the format of the final state: the one given, the one matching
+    /// the extension of the output file, or RLE.
+    pub fn get_format(&self) -> PatternFormat {
+        self.format
+            .or_else(|| {
+                self.output_path
+                    .as_deref()
+                    .and_then(PatternFormat::from_path)
+            })
+            .unwrap_or_default()
+    }
+
+    /// Returns the file the census of the objects of the final state is written to, if any.
+    pub fn get_census_path(&self) -> Option<&str> {
+        self.census_path.as_deref()
+    }
+
+    /// Returns the format of the census: the one given, the one matching the
+    /// extension of the census file, or text.
+    pub fn get_census_format(&self) -> CensusFormat {
+        self.census_format
+            .or_else(|| {
+                self.census_path
+                    .as_deref()
+                    .and_then(CensusFormat::from_path)
+            })
+            .unwrap_or_default()
+    }
+
+    /// Returns the file the statistics of every generation are written to, if any.
+    pub fn get_generation_stats_path(&self) -> Option<&str> {
+        self.generation_stats_path.as_deref()
+    }
+
+    /// Returns the format of the statistics of every generation: the one
+    /// given, the one matching the extension of their file, or CSV.
+    pub fn get_generation_stats_format(&self) -> StatsFormat {
+        self.generation_stats_format
+            .or_else(|| {
+                self.generation_stats_path
+                    .as_deref()
+                    .and_then(StatsFormat::from_path)
+            })
+            .unwrap_or_default()
+    }
+
+    /// Sets the number of generations to compute.
+    pub fn set_generations(&mut self, generations: Option<u64>) {
+        self.generations = generations;
+    }
+
+    /// Sets whether the run stops as soon as the universe settles into a cycle.
+    pub fn set_until_stable(&mut self, until_stable: bool) {
+        self.until_stable = until_stable;
+    }
+
+    /// Sets the file the final state is written to.
+    pub fn set_output_path(&mut self, output_path: Option<String>) {
+        self.output_path = output_path;
+    }
+
+    /// Sets the file the statistics are written to.
+    pub fn set_stats_path(&mut self, stats_path: Option<String>) {
+        self.stats_path = stats_path;
+    }
+
+    /// Sets the format of the final state.
+    pub fn set_format(&mut self, format: Option<PatternFormat>) {
+        self.format = format;
+    }
+
+    /// Sets the file the census of the objects of the final state is written to.
+    pub fn set_census_path(&mut self, census_path: Option<String>) {
+        self.census_path = census_path;
+    }
+
+    /// Sets the format of the census.
+    pub fn set_census_format(&mut self, census_format: Option<CensusFormat>) {
+        self.census_format = census_format;
+    }
+
+    /// Sets the file the statistics of every generation are written to.
+    pub fn set_generation_stats_path(&mut self, generation_stats_path: Option<String>) {
+        self.generation_stats_path = generation_stats_path;
+    }
+
+    /// Sets the format of the statistics of every generation.
+    pub fn set_generation_stats_format(&mut self, generation_stats_format: Option<StatsFormat>) {
+        self.generation_stats_format = generation_stats_format;
+    }
+}
+
+/// Statistics of a finished headless run.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct HeadlessReport {
+    generation: u64,
+    computed: u64,
+    population: u64,
+    bounding_box: Option<(i64, i64, i64, i64)>,
+    stable: Option<(u64, u64)>,
+    cycle: Option<Cycle>,
+    census: Option<Census>,
+    generation_stats: Option<StatsRecorder>,
+    elapsed: Duration,
+}
+
+impl HeadlessReport {
+    /// Returns the generation the run stopped at.
+    pub fn get_generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Returns the number of generations computed, less than the generation
+    /// the run stopped at for universes that started at a later generation.
+    pub fn get_computed(&self) -> u64 {
+        self.computed
+    }
+
+    /// Returns the number of live cells at the end of the run.
+    pub fn get_population(&self) -> u64 {
+        self.population
+    }
+
+    /// Returns `(min_x, min_y, max_x, max_y)` of the live cells at the end of the run.
+    pub fn get_bounding_box(&self) -> Option<(i64, i64, i64, i64)> {
+        self.bounding_box
+    }
+
+    /// Returns `(first_generation, period)` if the universe repeated a state:
+    /// the state of `first_generation` came back every `period` generations.
+    pub fn get_stable(&self) -> Option<(u64, u64)> {
+        self.stable
+    }
+
+    /// Returns the cycle the universe was in at the end of the run, moving or
+    /// not, if one was found.
+    pub fn get_cycle(&self) -> Option<Cycle> {
+        self.cycle
+    }
+
+    /// Returns the objects of the final state counted by apgcode, if a census
+    /// was asked for.
+    pub fn get_census(&self) -> Option<&Census> {
+        self.census.as_ref()
+    }
+
+    /// Returns the statistics of every generation computed, if they were asked for.
+    pub fn get_generation_stats(&self) -> Option<&StatsRecorder> {
+        self.generation_stats.as_ref()
+    }
+
+    /// Returns the time spent computing generations.
+    pub fn get_elapsed(&self) -> Duration {
+        self.elapsed
+    }
+}
+
+impl fmt::Display for HeadlessReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "generation: {}", self.generation)?;
+        writeln!(f, "population: {}", self.population)?;
+        match self.bounding_box {
+            Some((min_x, min_y, max_x, max_y)) => writeln!(
+                f,
+                "bounding box: {}x{} at {}, {}",
+                max_x - min_x + 1,
+                max_y - min_y + 1,
+                min_x,
+                min_y
+            )?,
+            None => writeln!(f, "bounding box: empty")?,
+        }
+        match self.stable {
+            Some((start, period)) => {
+                writeln!(f, "stable: period {} from generation {}", period, start)?
+            }
+            None => writeln!(f, "stable: no")?,
+        }
+        match self.cycle {
+            Some(cycle) => writeln!(f, "cycle: {}", cycle)?,
+            None => writeln!(f, "cycle: none")?,
+        }
+        let seconds = self.elapsed.as_secs_f64();
+        writeln!(f, "elapsed: {:.3} s", seconds)?;
+        if seconds > 0.0 {
+            writeln!(f, "iteration / s: {:.1}", self.computed as f64 / seconds)?;
+        }
+        Ok(())
+    }
+}
+
+/// Universe advanced by a headless run, with the engine chosen in the game.
+enum World {
+    Dense {
+        grid: Grid,
+        next: Grid,
+        pool: BandPool,
+    },
+    HashLife(HashLife),
+    Sparse(SparseLife),
+}
+
+impl World {
+    fn new(grid: &Grid, game_info: &Game) -> Self {
+        let (origin_x, origin_y) = game_info.get_viewport_origin();
+        match game_info.get_engine() {
+            Engine::Dense => World::Dense {
+                grid: grid.clone(),
+                next: grid.clone(),
+                pool: BandPool::new(game_info.get_thread_count()),
+            },
+            Engine::HashLife => {
+                let mut hashlife = HashLife::new(game_info.get_rule());
+                hashlife.load_grid(grid, origin_x, origin_y);
+                World::HashLife(hashlife)
+            }
+            Engine::Sparse => {
+                let mut sparse = SparseLife::new(game_info.get_rule());
+                sparse.load_grid(grid, origin_x, origin_y);
+                World::Sparse(sparse)
+            }
+        }
+    }
+
+    /// Returns the unbounded universe, or `None` for the dense grid.
+    fn universe(&self) -> Option<&dyn Universe> {
+        match self {
+            World::Dense { .. } => None,
+            World::HashLife(hashlife) => Some(hashlife),
+            World::Sparse(sparse) => Some(sparse),
+        }
+    }
+
+    /// Advances the universe by 2^step_log2 generations.
+    fn step(&mut self, game_info: &Game, step_log2: u8) {
+        match self {
+            World::Dense { grid, next, pool } => {
+                for _ in 0..1u64 << step_log2 {
+                    grid.next_generation_into(
+                        next,
+                        &game_info.get_rule(),
+                        game_info.get_topology(),
+                        pool,
+                    );
+                    std::mem::swap(grid, next);
+                }
+            }
+            World::HashLife(hashlife) => hashlife.step(step_log2),
+            World::Sparse(sparse) => Universe::step(sparse, step_log2),
+        }
+    }
+
+    fn population(&self) -> u64 {
+        match (self, self.universe()) {
+            (World::Dense { grid, .. }, _) => grid.population() as u64,
+            (_, universe) => universe.map_or(0, |universe| universe.population()),
+        }
+    }
+
+    fn bounding_box(&self) -> Option<(i64, i64, i64, i64)> {
+        match (self, self.universe()) {
+            (World::Dense { grid, .. }, _) => {
+                let (min_x, min_y, max_x, max_y) = grid.bounding_box()?;
+                Some((min_x as i64, min_y as i64, max_x as i64, max_y as i64))
+            }
+            (_, universe) => universe?.bounding_box(),
+        }
+    }
+
+    /// Returns the coordinates of every live cell.
+    fn live_cells(&self) -> Vec<(i64, i64)> {
+        match (self, self.universe()) {
+            (World::Dense { grid, .. }, _) => grid
+                .live_cells()
+                .map(|(x, y)| (x as i64, y as i64))
+                .collect(),
+            (_, universe) => universe.map_or_else(Vec::new, |universe| universe.live_cells()),
+        }
+    }
+
+    /// Returns the statistics of `generation`, computed in `step_time` from
+    /// the live cells `previous` of the unbounded engines, `span` generations
+    /// before. The dense grid still holds its previous generation.
+    fn generation_stats(
+        &self,
+        generation: u64,
+        previous: &[(i64, i64)],
+        span: u64,
+        step_time: Duration,
+    ) -> GenerationStats {
+        match (self, self.universe()) {
+            (World::Dense { grid, next, .. }, _) => {
+                GenerationStats::of_grid(generation, next, grid, step_time)
+            }
+            (_, universe) => universe.map_or_else(GenerationStats::default, |universe| {
+                GenerationStats::of_universe(universe, previous, span, step_time)
+            }),
+        }
+    }
+
+    /// Records the live cells of `generation` in `detector` and returns the cycle found.
+    fn record(&self, detector: &mut CycleDetector, generation: u64) -> Option<Cycle> {
+        match (self, self.universe()) {
+            (World::Dense { grid, .. }, _) => detector.record_grid(generation, grid),
+            (_, universe) => universe.and_then(|universe| detector.record_universe(universe)),
+        }
+    }
+
+    /// Writes the live cells in `format`, cropped to their bounding box.
+    /// HashLife universes are written straight from their quadtree in
+    /// macrocell format, so that huge universes never go through a grid.
+    fn write(&self, game_info: &Game, format: PatternFormat) -> Result<String, String> {
+        let universe = match self {
+            World::Dense { grid, .. } => {
+                let cropped = Pattern::from_grid(grid);
+                return Ok(write_pattern(
+                    &rule_pattern(cropped.get_grid().clone(), game_info),
+                    format,
+                ));
+            }
+            World::HashLife(hashlife) if format == PatternFormat::Macrocell => {
+                return Ok(write_macrocell(hashlife));
+            }
+            World::HashLife(hashlife) => hashlife as &dyn Universe,
+            World::Sparse(sparse) => sparse as &dyn Universe,
+        };
+        let Some((min_x, min_y, max_x, max_y)) = universe.bounding_box() else {
+            return Ok(write_pattern(
+                &rule_pattern(Grid::new(0, 0), game_info),
+                format,
+            ));
+        };
+        let (width, height) = ((max_x - min_x + 1) as u64, (max_y - min_y + 1) as u64);
+        if width.saturating_mul(height) > MAX_GRID_CELLS {
+            return Err(format!(
+                "Final state of {}x{} cells is too large for {}, use the hashlife engine and the macrocell format",
+                width, height, format
+            ));
+        }
+        let mut grid = Grid::new(width as usize, height as usize);
+        universe.write_to_grid(&mut grid, min_x, min_y);
+        Ok(write_pattern(&rule_pattern(grid, game_info), format))
+    }
+}
+
+/// Returns a pattern of the cells of `grid`, made for the rule of the game.
+fn rule_pattern(grid: Grid, game_info: &Game) -> Pattern {
+    let mut pattern = Pattern::new(grid);
+    pattern.set_rule(Some(game_info.get_rule()));
+    pattern
+}
+
+/// Advances `grid` with the rule, topology and engine of `game_info`, without
+/// opening a window, and returns the final state in the format of `options`
+/// along with the statistics of the run, the census of the objects of the
+/// final state when `options` has a census file, and the statistics of every
+/// generation when it has a file for them.
+///
+/// The run computes exactly `options.get_generations()` generations, or, with
+/// `until_stable`, stops as soon as the universe comes back to a state it was
+/// already in, at the latest after that many generations
+/// (`DEFAULT_MAX_GENERATIONS` by default). On the unbounded engines, a
+/// spaceship coming back moved also stops the run, since it flies on forever;
+/// on the grid it may still hit the edge. The HashLife engine advances
+/// 2^step_log2 generations at a time, so it is only checked for cycles at
+/// those steps.
+pub fn run_headless(
+    grid: &Grid,
+    game_info: &Game,
+    options: &HeadlessOptions,
+) -> Result<(String, HeadlessReport), String> {
+    run_world(World::new(grid, game_info), game_info, options)
+}
+
+/// Advances a HashLife `universe` like `run_headless`, from its own
+/// generation, so that universes too large for a grid, such as the ones
+/// read from macrocell files, can be run. The rule of the game is used.
+pub fn run_headless_universe(
+    universe: HashLife,
+    game_info: &Game,
+    options: &HeadlessOptions,
+) -> Result<(String, HeadlessReport), String> {
+    let mut universe = universe;
+    universe.set_rule(game_info.get_rule());
+    run_world(World::HashLife(universe), game_info, options)
+}
+
+/// Runs `world` as described by `run_headless`.
+fn run_world(
+    world: World,
+    game_info: &Game,
+    options: &HeadlessOptions,
+) -> Result<(String, HeadlessReport), String> {
+    let mut world = world;
+    let limit = match (options.generations, options.until_stable) {
+        (Some(generations), _) => generations,
+        (None, true) => DEFAULT_MAX_GENERATIONS,
+        (None, false) => {
+            return Err("A headless run needs --generations or --until-stable".to_string())
+        }
+    };
+    let step_log2 = game_info.get_engine_step_log2();
+
+    let mut detector = CycleDetector::new();
+    let first_generation = world
+        .universe()
+        .map_or(0, |universe| universe.get_generation());
+    let mut generation = first_generation;
+    let mut computed = 0u64;
+    let mut stable = None;
+    let mut generation_stats = options
+        .generation_stats_path
+        .is_some()
+        .then(|| StatsRecorder::new(usize::MAX));
+    let start = Instant::now();
+    loop {
+        let cycle = world.record(&mut detector, generation);
+        if let Some(cycle) = cycle.filter(|_| options.until_stable) {
+            if cycle.get_displacement() == (0, 0) || world.universe().is_some() {
+                stable = Some((cycle.get_start(), cycle.get_period()));
+                break;
+            }
+        }
+        let remaining = limit - computed;
+        if remaining == 0 {
+            break;
+        }
+        // the last steps get smaller so that the run stops exactly at the limit
+        let step = step_log2.min(remaining.ilog2() as u8);
+        // the unbounded engines only list their cells when births and deaths are counted
+        let previous = match (&generation_stats, world.universe()) {
+            (Some(_), Some(universe)) => universe.live_cells(),
+            _ => Vec::new(),
+        };
+        let step_start = Instant::now();
+        world.step(game_info, step);
+        let step_time = step_start.elapsed();
+        computed += 1 << step;
+        generation = first_generation.saturating_add(computed);
+        if let Some(generation_stats) = generation_stats.as_mut() {
+            generation_stats.record(world.generation_stats(
+                generation,
+                &previous,
+                1 << step,
+                step_time,
+            ));
+        }
+    }
+    let elapsed = start.elapsed();
+
+    let report = HeadlessReport {
+        generation,
+        computed,
+        population: world.population(),
+        bounding_box: world.bounding_box(),
+        stable,
+        cycle: detector.get_cycle(),
+        census: options
+            .census_path
+            .is_some()
+            .then(|| take_census(world.live_cells(), game_info.get_rule())),
+        generation_stats,
+        elapsed,
+    };
+    Ok((world.write(game_info, options.get_format())?, report))
+}
+
+/// Runs the simulation described on the command line without a window, then
+/// writes the final state to the output file (stdout by default), the
+/// statistics to the statistics file, and the census and the statistics of
+/// every generation to their files, if any. Without a statistics file, the
+/// statistics go to stdout, or to stderr when the final state already goes
+/// to stdout.
+pub fn run_headless_with(options: &Options, headless: &HeadlessOptions) -> Result<(), String> {
+    let mut game_info = Game::new();
+    options.apply(&mut game_info);
+    let (text, report) = match options.load_start_universe(&mut game_info)? {
+        Some(universe) => run_headless_universe(universe, &game_info, headless)?,
+        None => {
+            let size = game_info.get_size_grid() as usize;
+            let mut grid = Grid::new(size, size);
+            options.load_start_grid(&mut grid, &mut game_info)?;
+            run_headless(&grid, &game_info, headless)?
+        }
+    };
+    match headless.get_output_path() {
+        Some(path) => {
+            fs::write(path, text).map_err(|e| format!("Failed to write {}: {}", path, e))?
+        }
+        None => print!("{}", text),
+    }
+    match (headless.get_stats_path(), headless.get_output_path()) {
+        (Some(path), _) => fs::write(path, report.to_string())
+            .map_err(|e| format!("Failed to write {}: {}", path, e))?,
+        (None, Some(_)) => print!("{}", report),
+        (None, None) => eprint!("{}", report),
+    }
+    if let (Some(path), Some(census)) = (headless.get_census_path(), report.get_census()) {
+        fs::write(path, census.write(headless.get_census_format()))
+            .map_err(|e| format!("Failed to write {}: {}", path, e))?;
+    }
+    if let (Some(path), Some(generation_stats)) = (
+        headless.get_generation_stats_path(),
+        report.get_generation_stats(),
+    ) {
+        fs::write(
+            path,
+            generation_stats.write(headless.get_generation_stats_format()),
+        )
+        .map_err(|e| format!("Failed to write {}: {}", path, e))?;
+    }
+    Ok(())
+}
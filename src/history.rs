@@ -0,0 +1,202 @@
+use crate::grid::Grid;
+use std::collections::VecDeque;
+
+/// Number of entries kept when no other one is given.
+pub const DEFAULT_HISTORY_LENGTH: usize = 1000;
+
+/// Memory the deltas of a history may use, in bytes. The oldest entries are
+/// dropped beyond it, so that chaotic soups on large grids stay bounded.
+pub const MAX_HISTORY_BYTES: usize = 64 << 20;
+
+/// What changed the grid between two entries of a history.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    /// The cells were edited by hand (or cleared, or a pattern was loaded).
+    Edit,
+    /// One generation was computed.
+    Generation,
+}
+
+/// Change of the grid, stored as the words that differ before and after it.
+#[derive(Debug, Clone)]
+struct Entry {
+    change: Change,
+    delta: Vec<(usize, u64)>,
+    iteration_before: u64,
+    iteration_after: u64,
+}
+
+impl Entry {
+    fn size_in_bytes(&self) -> usize {
+        std::mem::size_of::<Self>() + self.delta.len() * std::mem::size_of::<(usize, u64)>()
+    }
+}
+
+/// Merges two deltas sorted by index into the delta of both changes.
+fn merge_deltas(first: &[(usize, u64)], second: &[(usize, u64)]) -> Vec<(usize, u64)> {
+    let mut merged = Vec::with_capacity(first.len() + second.len());
+    let (mut i, mut j) = (0, 0);
+    while i < first.len() || j < second.len() {
+        let entry = match (first.get(i), second.get(j)) {
+            (Some(&(a, x)), Some(&(b, y))) if a == b => {
+                i += 1;
+                j += 1;
+                (a, x ^ y)
+            }
+            (Some(&a), Some(&b)) if a.0 < b.0 => {
+                i += 1;
+                a
+            }
+            (Some(&a), None) => {
+                i += 1;
+                a
+            }
+            (_, Some(&b)) => {
+                j += 1;
+                b
+            }
+            (None, None) => unreachable!(),
+        };
+        if entry.1 != 0 {
+            merged.push(entry);
+        }
+    }
+    merged
+}
+
+/// Bounded history of the grid, keeping past generations and edits as deltas
+/// so that they can be undone, redone and rewound.
+///
+/// The history keeps a copy of the grid as of the last recorded change. Each
+/// record compares the grid with this copy, so changes made to the grid
+/// between two records are stored as one entry.
+#[derive(Debug, Clone, Default)]
+pub struct History {
+    past: VecDeque<Entry>,
+    future: Vec<Entry>,
+    current: Grid,
+    iteration: u64,
+    length: usize,
+    bytes: usize,
+}
+
+impl History {
+    /// Creates an empty history keeping at most `length` entries.
+    /// A length of 0 keeps nothing.
+    pub fn new(length: usize) -> Self {
+        Self {
+            length,
+            ..Self::default()
+        }
+    }
+
+    /// Returns the number of changes that can be undone.
+    pub fn get_undo_count(&self) -> usize {
+        self.past.len()
+    }
+
+    /// Returns the number of changes that can be redone.
+    pub fn get_redo_count(&self) -> usize {
+        self.future.len()
+    }
+
+    /// Returns the maximum number of entries.
+    pub fn get_length(&self) -> usize {
+        self.length
+    }
+
+    /// Forgets every entry and starts again from `grid` at `iteration`.
+    pub fn reset(&mut self, grid: &Grid, iteration: u64) {
+        self.past.clear();
+        self.future.clear();
+        self.bytes = 0;
+        self.current.clone_from(grid);
+        self.iteration = iteration;
+    }
+
+    /// Records the changes made to `grid` since the last record.
+    ///
+    /// If `merge` is true and the last entry is an edit, the changes are added
+    /// to it, so that a stroke drawn with the mouse is undone at once.
+    pub fn record(&mut self, grid: &Grid, iteration: u64, change: Change, merge: bool) {
+        if grid.get_width() != self.current.get_width()
+            || grid.get_height() != self.current.get_height()
+        {
+            self.reset(grid, iteration);
+            return;
+        }
+        let delta = grid.xor_delta(&self.current);
+        if delta.is_empty() && iteration == self.iteration {
+            return;
+        }
+        self.current.apply_xor_delta(&delta);
+        let entry = Entry {
+            change,
+            delta,
+            iteration_before: self.iteration,
+            iteration_after: iteration,
+        };
+        self.iteration = iteration;
+        self.future.clear();
+
+        match self.past.back_mut() {
+            Some(last) if merge && change == Change::Edit && last.change == Change::Edit => {
+                self.bytes -= last.size_in_bytes();
+                last.delta = merge_deltas(&last.delta, &entry.delta);
+                last.iteration_after = entry.iteration_after;
+                self.bytes += last.size_in_bytes();
+            }
+            _ => {
+                self.bytes += entry.size_in_bytes();
+                self.past.push_back(entry);
+            }
+        }
+        while self.past.len() > self.length || self.bytes > MAX_HISTORY_BYTES {
+            match self.past.pop_front() {
+                Some(entry) => self.bytes -= entry.size_in_bytes(),
+                None => break,
+            }
+        }
+    }
+
+    /// Reverts the last change of `grid` and returns the iteration before it,
+    /// or `None` if there is nothing to undo.
+    pub fn undo(&mut self, grid: &mut Grid) -> Option<u64> {
+        let entry = self.past.pop_back()?;
+        self.bytes -= entry.size_in_bytes();
+        grid.apply_xor_delta(&entry.delta);
+        self.current.apply_xor_delta(&entry.delta);
+        self.iteration = entry.iteration_before;
+        self.future.push(entry);
+        Some(self.iteration)
+    }
+
+    /// Applies again the last undone change to `grid` and returns the
+    /// iteration after it, or `None` if there is nothing to redo.
+    pub fn redo(&mut self, grid: &mut Grid) -> Option<u64> {
+        let entry = self.future.pop()?;
+        grid.apply_xor_delta(&entry.delta);
+        self.current.apply_xor_delta(&entry.delta);
+        self.iteration = entry.iteration_after;
+        self.bytes += entry.size_in_bytes();
+        self.past.push_back(entry);
+        Some(self.iteration)
+    }
+
+    /// Reverts changes of `grid` until `generations` generations are undone,
+    /// along with the edits made in between. Returns the iteration reached, or
+    /// `None` if there was nothing to rewind.
+    pub fn rewind(&mut self, grid: &mut Grid, generations: u64) -> Option<u64> {
+        let mut iteration = None;
+        let mut remaining = generations;
+        while remaining > 0 {
+            match self.past.back() {
+                Some(entry) if entry.change == Change::Generation => remaining -= 1,
+                Some(_) => {}
+                None => break,
+            }
+            iteration = self.undo(grid);
+        }
+        iteration
+    }
+}
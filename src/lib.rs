@@ -1,3 +1,23 @@
+pub mod age;
+pub mod camera;
+pub mod census;
+pub mod cli;
+pub mod cycle;
+pub mod engine;
+pub mod frontend;
 pub mod game;
+pub mod grid;
+pub mod hashlife;
+pub mod headless;
+pub mod history;
+pub mod pattern;
+pub mod rule;
+pub mod scheduler;
+#[cfg(feature = "sdl")]
 pub mod sdl_lib;
+pub mod search;
+pub mod selection;
+pub mod sparse;
+pub mod stats;
+pub mod topology;
 pub mod utils;
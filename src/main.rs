@@ -1,5 +1,31 @@
-use rust_of_life::utils::run_game;
+use rust_of_life::cli::{parse_args, Command, USAGE};
+use rust_of_life::headless::run_headless_with;
+use rust_of_life::search::run_search_with;
+#[cfg(feature = "sdl")]
+use rust_of_life::utils::run_game_with;
 
 fn main() -> Result<(), String> {
-    run_game()
+    let command = parse_args(std::env::args().skip(1)).unwrap_or_else(|e| {
+        eprintln!("{}\n\n{}", e, USAGE);
+        std::process::exit(2);
+    });
+    match command {
+        #[cfg(feature = "sdl")]
+        Command::Run(options) => run_game_with(&options),
+        #[cfg(not(feature = "sdl"))]
+        Command::Run(_) => Err(
+            "This build has no window; rebuild with the sdl feature or use headless mode"
+                .to_string(),
+        ),
+        Command::Headless(options, headless) => run_headless_with(&options, &headless),
+        Command::Search(options, search) => run_search_with(&options, &search),
+        Command::Help => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        Command::Version => {
+            println!("rust_of_life {}", env!("CARGO_PKG_VERSION"));
+            Ok(())
+        }
+    }
 }
@@ -0,0 +1,292 @@
+pub mod life;
+pub mod macrocell;
+pub mod plaintext;
+pub mod rle;
+
+pub use life::{parse_life105, parse_life106, write_life105, write_life106};
+pub use macrocell::{
+    parse_macrocell, parse_macrocell_pattern, write_macrocell, write_macrocell_pattern,
+};
+pub use plaintext::{parse_plaintext, write_plaintext};
+pub use rle::{parse_rle, write_rle};
+
+use crate::grid::Grid;
+use crate::hashlife::HashLife;
+use crate::rule::Rule;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Largest number of cells a pattern may span to be read into a grid.
+pub const MAX_GRID_CELLS: u64 = 1 << 28;
+
+/// File format of a pattern.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum PatternFormat {
+    /// Run Length Encoded (`.rle`).
+    #[default]
+    Rle,
+    /// LifeWiki plaintext (`.cells`).
+    Plaintext,
+    /// Life 1.05 (`.lif`), blocks of `.` and `*` cells.
+    Life105,
+    /// Life 1.06 (`.lif`), one line of coordinates per live cell.
+    Life106,
+    /// Golly macrocell (`.mc`), a quadtree with shared nodes.
+    Macrocell,
+}
+
+impl PatternFormat {
+    /// All pattern formats.
+    pub const ALL: [PatternFormat; 5] = [
+        PatternFormat::Rle,
+        PatternFormat::Plaintext,
+        PatternFormat::Life105,
+        PatternFormat::Life106,
+        PatternFormat::Macrocell,
+    ];
+
+    /// Guesses the format of a pattern file from its contents.
+    ///
+    /// Life and macrocell files are recognized by their header and plaintext files
+    /// by their `!` comments or by lines made only of `.`, `O` and `*`.
+    /// Everything else is read as RLE.
+    pub fn detect(text: &str) -> Self {
+        let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+        let Some(first) = lines.next() else {
+            return PatternFormat::Rle;
+        };
+        if first.starts_with(life::LIFE_105_HEADER) {
+            PatternFormat::Life105
+        } else if first.starts_with(life::LIFE_106_HEADER) {
+            PatternFormat::Life106
+        } else if first.starts_with(macrocell::MACROCELL_HEADER) {
+            PatternFormat::Macrocell
+        } else if first.starts_with('!') || first.chars().all(|c| matches!(c, '.' | 'O' | '*')) {
+            PatternFormat::Plaintext
+        } else {
+            PatternFormat::Rle
+        }
+    }
+
+    /// Returns the format matching the extension of `path`, if any.
+    /// `.lif` and `.life` files are written as Life 1.06.
+    pub fn from_path(path: &str) -> Option<Self> {
+        let extension = Path::new(path).extension()?.to_str()?;
+        match extension.to_ascii_lowercase().as_str() {
+            "rle" => Some(PatternFormat::Rle),
+            "cells" => Some(PatternFormat::Plaintext),
+            "lif" | "life" => Some(PatternFormat::Life106),
+            "mc" => Some(PatternFormat::Macrocell),
+            _ => None,
+        }
+    }
+
+    /// Returns the usual file extension of the format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            PatternFormat::Rle => "rle",
+            PatternFormat::Plaintext => "cells",
+            PatternFormat::Life105 | PatternFormat::Life106 => "lif",
+            PatternFormat::Macrocell => "mc",
+        }
+    }
+}
+
+impl fmt::Display for PatternFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            PatternFormat::Rle => "rle",
+            PatternFormat::Plaintext => "plaintext",
+            PatternFormat::Life105 => "life105",
+            PatternFormat::Life106 => "life106",
+            PatternFormat::Macrocell => "macrocell",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for PatternFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "rle" => Ok(PatternFormat::Rle),
+            "plaintext" | "cells" => Ok(PatternFormat::Plaintext),
+            "life105" | "life1.05" => Ok(PatternFormat::Life105),
+            "life106" | "life1.06" | "lif" => Ok(PatternFormat::Life106),
+            "macrocell" | "mc" => Ok(PatternFormat::Macrocell),
+            other => Err(format!("Unknown pattern format '{}'", other)),
+        }
+    }
+}
+
+/// Pattern read from or written to a pattern file, with its metadata.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Pattern {
+    grid: Grid,
+    rule: Option<Rule>,
+    name: Option<String>,
+    author: Option<String>,
+    comments: Vec<String>,
+}
+
+impl Pattern {
+    /// Creates a pattern without metadata from the cells of `grid`.
+    pub fn new(grid: Grid) -> Self {
+        Self {
+            grid,
+            ..Self::default()
+        }
+    }
+
+    /// Creates a pattern from the live cells of `grid`, cropped to their bounding box.
+    pub fn from_grid(grid: &Grid) -> Self {
+        let Some((min_x, min_y, max_x, max_y)) = grid.bounding_box() else {
+            return Self::new(Grid::new(0, 0));
+        };
+        let mut cropped = Grid::new(max_x - min_x + 1, max_y - min_y + 1);
+        for (x, y) in grid.live_cells() {
+            cropped.set(x - min_x, y - min_y, true);
+        }
+        Self::new(cropped)
+    }
+
+    /// Returns the cells of the pattern.
+    pub fn get_grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    /// Returns the rule the pattern was made for, if it is known.
+    pub fn get_rule(&self) -> Option<Rule> {
+        self.rule
+    }
+
+    /// Returns the name of the pattern.
+    pub fn get_name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Returns the author of the pattern.
+    pub fn get_author(&self) -> Option<&str> {
+        self.author.as_deref()
+    }
+
+    /// Returns the free-form comment lines of the pattern.
+    pub fn get_comments(&self) -> &[String] {
+        &self.comments
+    }
+
+    /// Sets the cells of the pattern.
+    pub fn set_grid(&mut self, grid: Grid) {
+        self.grid = grid;
+    }
+
+    /// Sets the rule the pattern was made for.
+    pub fn set_rule(&mut self, rule: Option<Rule>) {
+        self.rule = rule;
+    }
+
+    /// Sets the name of the pattern.
+    pub fn set_name(&mut self, name: Option<String>) {
+        self.name = name;
+    }
+
+    /// Sets the author of the pattern.
+    pub fn set_author(&mut self, author: Option<String>) {
+        self.author = author;
+    }
+
+    /// Appends a comment line.
+    pub fn add_comment(&mut self, comment: String) {
+        self.comments.push(comment);
+    }
+
+    /// Clears `grid` and copies the pattern into its center.
+    /// Cells that do not fit in the grid are dropped, and their number is returned.
+    pub fn place_centered(&self, grid: &mut Grid) -> usize {
+        grid.clear();
+        let offset_x = (grid.get_width() as i64 - self.grid.get_width() as i64) / 2;
+        let offset_y = (grid.get_height() as i64 - self.grid.get_height() as i64) / 2;
+        let mut dropped = 0;
+        for (x, y) in self.grid.live_cells() {
+            let (target_x, target_y) = (x as i64 + offset_x, y as i64 + offset_y);
+            if grid.contains(target_x, target_y) {
+                grid.set(target_x as usize, target_y as usize, true);
+            } else {
+                dropped += 1;
+            }
+        }
+        dropped
+    }
+
+    /// Returns the warning given when `dropped` live cells of the pattern did
+    /// not fit in `grid`.
+    pub fn get_dropped_warning(&self, grid: &Grid, dropped: usize) -> String {
+        format!(
+            "Warning: {} live cells of the {}x{} pattern do not fit in the {}x{} grid and were dropped",
+            dropped,
+            self.grid.get_width(),
+            self.grid.get_height(),
+            grid.get_width(),
+            grid.get_height()
+        )
+    }
+}
+
+/// Parses a pattern in the given format.
+pub fn parse_pattern_as(text: &str, format: PatternFormat) -> Result<Pattern, String> {
+    match format {
+        PatternFormat::Rle => parse_rle(text),
+        PatternFormat::Plaintext => parse_plaintext(text),
+        PatternFormat::Life105 => parse_life105(text),
+        PatternFormat::Life106 => parse_life106(text),
+        PatternFormat::Macrocell => parse_macrocell_pattern(text),
+    }
+}
+
+/// Parses a pattern, detecting its format from the contents of `text`.
+pub fn parse_pattern(text: &str) -> Result<Pattern, String> {
+    parse_pattern_as(text, PatternFormat::detect(text))
+}
+
+/// Writes a pattern in the given format.
+pub fn write_pattern(pattern: &Pattern, format: PatternFormat) -> String {
+    match format {
+        PatternFormat::Rle => write_rle(pattern),
+        PatternFormat::Plaintext => write_plaintext(pattern),
+        PatternFormat::Life105 => write_life105(pattern),
+        PatternFormat::Life106 => write_life106(pattern),
+        PatternFormat::Macrocell => write_macrocell_pattern(pattern),
+    }
+}
+
+/// Reads a pattern file, detecting its format from its contents.
+pub fn load_pattern_file(path: &str) -> Result<Pattern, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    parse_pattern(&text)
+}
+
+/// Reads a macrocell file straight into a universe, keeping its `#G`
+/// generation, so that patterns too large for a grid can be loaded. The
+/// universe runs the rule of the file, or `rule` if it has none. Returns
+/// `None` for files in other formats.
+pub fn load_macrocell_file(path: &str, rule: Rule) -> Result<Option<HashLife>, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    if PatternFormat::detect(&text) != PatternFormat::Macrocell {
+        return Ok(None);
+    }
+    let mut universe = parse_macrocell(&text)?;
+    if !text.lines().any(|line| line.trim().starts_with("#R")) {
+        universe.set_rule(rule);
+    }
+    Ok(Some(universe))
+}
+
+/// Writes a pattern file in the format matching its extension, RLE by default.
+pub fn save_pattern_file(path: &str, pattern: &Pattern) -> Result<(), String> {
+    let format = PatternFormat::from_path(path).unwrap_or_default();
+    fs::write(path, write_pattern(pattern, format))
+        .map_err(|e| format!("Failed to write {}: {}", path, e))
+}
@@ -0,0 +1,164 @@
+use super::Pattern;
+use crate::grid::Grid;
+use crate::rule::Rule;
+
+/// Header line of a Life 1.05 file.
+pub const LIFE_105_HEADER: &str = "#Life 1.05";
+/// Header line of a Life 1.06 file.
+pub const LIFE_106_HEADER: &str = "#Life 1.06";
+
+/// Formats a rule the way Life 1.05 `#R` lines do, survival digits first (`23/3`).
+fn format_rule(rule: &Rule) -> String {
+    let digits = |counts: Vec<u8>| counts.iter().map(|n| n.to_string()).collect::<String>();
+    format!(
+        "{}/{}",
+        digits(rule.get_survival()),
+        digits(rule.get_birth())
+    )
+}
+
+/// Reads a `#D`, `#N` or `#R` metadata line. `#D Name:` and `#D Author:`
+/// descriptions fill in the name and the author of the pattern.
+fn parse_metadata(pattern: &mut Pattern, line: &str) -> Result<(), String> {
+    let (tag, text) = (line.get(..2), line.get(2..).unwrap_or(""));
+    let text = text.strip_prefix(' ').unwrap_or(text);
+    match tag {
+        Some("#D") => {
+            if let Some(name) = text.strip_prefix("Name:") {
+                pattern.set_name(Some(name.trim().to_string()));
+            } else if let Some(author) = text.strip_prefix("Author:") {
+                pattern.set_author(Some(author.trim().to_string()));
+            } else {
+                pattern.add_comment(text.to_string());
+            }
+        }
+        Some("#N") => pattern.set_rule(Some(Rule::conway())),
+        Some("#R") => pattern.set_rule(Some(text.trim().parse()?)),
+        _ => {}
+    }
+    Ok(())
+}
+
+fn write_metadata(pattern: &Pattern, text: &mut String) {
+    if let Some(name) = pattern.get_name() {
+        *text += &format!("#D Name: {}\n", name);
+    }
+    if let Some(author) = pattern.get_author() {
+        *text += &format!("#D Author: {}\n", author);
+    }
+    for comment in pattern.get_comments() {
+        *text += &format!("#D {}\n", comment);
+    }
+    if let Some(rule) = pattern.get_rule() {
+        *text += &format!("#R {}\n", format_rule(&rule));
+    }
+}
+
+/// Sets the grid of `pattern` to the given cells, moved so that their bounding box starts at `(0, 0)`.
+fn set_cells(pattern: &mut Pattern, cells: &[(i64, i64)]) {
+    let min_x = cells.iter().map(|&(x, _)| x).min().unwrap_or(0);
+    let min_y = cells.iter().map(|&(_, y)| y).min().unwrap_or(0);
+    let width = cells.iter().map(|&(x, _)| x - min_x + 1).max().unwrap_or(0);
+    let height = cells.iter().map(|&(_, y)| y - min_y + 1).max().unwrap_or(0);
+    let mut grid = Grid::new(width as usize, height as usize);
+    for &(x, y) in cells {
+        grid.set((x - min_x) as usize, (y - min_y) as usize, true);
+    }
+    pattern.set_grid(grid);
+}
+
+fn parse_point(line: &str) -> Result<(i64, i64), String> {
+    let mut coordinates = line.split_whitespace().map(|value| {
+        value
+            .parse::<i64>()
+            .map_err(|_| format!("Invalid coordinate '{}' in '{}'", value, line))
+    });
+    match (coordinates.next(), coordinates.next(), coordinates.next()) {
+        (Some(x), Some(y), None) => Ok((x?, y?)),
+        _ => Err(format!("Expected two coordinates in '{}'", line)),
+    }
+}
+
+/// Parses a pattern in Life 1.05 format: `#D` description lines, an optional
+/// `#N` (normal rules) or `#R` (survival/birth) rule line, and `#P x y` blocks
+/// of `.` and `*` cells. The pattern is cropped to its live cells.
+pub fn parse_life105(text: &str) -> Result<Pattern, String> {
+    let mut pattern = Pattern::default();
+    let mut cells = Vec::new();
+    let (mut block_x, mut block_y) = (0i64, 0i64);
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with("#Life") {
+            continue;
+        }
+        if let Some(position) = line.strip_prefix("#P") {
+            (block_x, block_y) = parse_point(position)?;
+        } else if line.starts_with('#') {
+            parse_metadata(&mut pattern, line)?;
+        } else {
+            for (x, c) in line.chars().enumerate() {
+                match c {
+                    '*' => cells.push((block_x + x as i64, block_y)),
+                    '.' => {}
+                    _ => return Err(format!("Invalid character '{}' in Life 1.05 data", c)),
+                }
+            }
+            block_y += 1;
+        }
+    }
+    set_cells(&mut pattern, &cells);
+    Ok(pattern)
+}
+
+/// Parses a pattern in Life 1.06 format: one `x y` line per live cell. The
+/// `#D` and `#R` lines of Life 1.05 are also read. The pattern is cropped to
+/// its live cells.
+pub fn parse_life106(text: &str) -> Result<Pattern, String> {
+    let mut pattern = Pattern::default();
+    let mut cells = Vec::new();
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with("#Life") {
+            continue;
+        }
+        if line.starts_with('#') {
+            parse_metadata(&mut pattern, line)?;
+        } else {
+            cells.push(parse_point(line)?);
+        }
+    }
+    set_cells(&mut pattern, &cells);
+    Ok(pattern)
+}
+
+/// Writes a pattern in Life 1.05 format as a single `#P` block centered on the
+/// origin, with its metadata as `#D` lines and its rule as a `#R` line.
+pub fn write_life105(pattern: &Pattern) -> String {
+    let grid = pattern.get_grid();
+    let mut text = format!("{}\n", LIFE_105_HEADER);
+    write_metadata(pattern, &mut text);
+    text += &format!(
+        "#P {} {}\n",
+        -(grid.get_width() as i64 / 2),
+        -(grid.get_height() as i64 / 2)
+    );
+    for y in 0..grid.get_height() {
+        let row: String = (0..grid.get_width())
+            .map(|x| if grid.get(x, y) { '*' } else { '.' })
+            .collect();
+        // trailing dead cells of a row are implied, but an empty row needs a '.'
+        let row = row.trim_end_matches('.');
+        text += if row.is_empty() { "." } else { row };
+        text.push('\n');
+    }
+    text
+}
+
+/// Writes a pattern in Life 1.06 format, one `x y` line per live cell, with
+/// its metadata as `#D` lines and its rule as a `#R` line.
+pub fn write_life106(pattern: &Pattern) -> String {
+    let mut text = format!("{}\n", LIFE_106_HEADER);
+    write_metadata(pattern, &mut text);
+    for (x, y) in pattern.get_grid().live_cells() {
+        text += &format!("{} {}\n", x, y);
+    }
+    text
+}
@@ -0,0 +1,274 @@
+use super::{Pattern, MAX_GRID_CELLS};
+use crate::grid::Grid;
+use crate::hashlife::{HashLife, NodeId, MAX_LEVEL};
+use crate::rule::Rule;
+use std::collections::HashMap;
+
+/// First line of a macrocell file.
+pub const MACROCELL_HEADER: &str = "[M2]";
+
+/// Level of the 8x8 leaves written as `.`/`*` pictures in two-state files.
+const LEAF_LEVEL: u8 = 3;
+
+/// Builds the node of the given level whose top-left cell is `(x, y)` in an 8x8 picture.
+fn build_leaf(
+    universe: &mut HashLife,
+    cells: &[[bool; 8]; 8],
+    level: u8,
+    x: usize,
+    y: usize,
+) -> NodeId {
+    if level == 0 {
+        return HashLife::leaf(cells[y][x]);
+    }
+    let half = 1 << (level - 1);
+    let nw = build_leaf(universe, cells, level - 1, x, y);
+    let ne = build_leaf(universe, cells, level - 1, x + half, y);
+    let sw = build_leaf(universe, cells, level - 1, x, y + half);
+    let se = build_leaf(universe, cells, level - 1, x + half, y + half);
+    universe.join(nw, ne, sw, se)
+}
+
+fn parse_leaf(universe: &mut HashLife, line: &str) -> Result<NodeId, String> {
+    let mut cells = [[false; 8]; 8];
+    let (mut x, mut y) = (0, 0);
+    for c in line.chars() {
+        match c {
+            '$' => {
+                x = 0;
+                y += 1;
+                continue;
+            }
+            '.' | '*' if x < 8 && y < 8 => cells[y][x] = c == '*',
+            '.' | '*' => return Err(format!("Macrocell leaf '{}' is larger than 8x8", line)),
+            _ => return Err(format!("Invalid character '{}' in macrocell leaf", c)),
+        }
+        x += 1;
+    }
+    Ok(build_leaf(universe, &cells, LEAF_LEVEL, 0, 0))
+}
+
+/// Parses a `level nw ne sw se` node line. Children are line numbers of
+/// earlier nodes, or 0 for an empty node; level-1 children are cell states.
+fn parse_node(universe: &mut HashLife, line: &str, nodes: &[NodeId]) -> Result<NodeId, String> {
+    let numbers = line
+        .split_whitespace()
+        .map(|value| value.parse::<usize>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| format!("Invalid macrocell node '{}'", line))?;
+    let [level, nw, ne, sw, se] = numbers[..] else {
+        return Err(format!(
+            "Macrocell node '{}' needs a level and 4 children",
+            line
+        ));
+    };
+    if !(1..=MAX_LEVEL as usize).contains(&level) {
+        return Err(format!("Invalid level in macrocell node '{}'", line));
+    }
+    let level = level as u8;
+    let mut ids = [0; 4];
+    for (id, child) in ids.iter_mut().zip([nw, ne, sw, se]) {
+        *id = if level == 1 {
+            // multi-state files: every state other than 0 is read as alive
+            HashLife::leaf(child != 0)
+        } else if child == 0 {
+            universe.empty_node(level - 1)
+        } else {
+            let node = *nodes
+                .get(child - 1)
+                .ok_or_else(|| format!("Macrocell node '{}' refers to a later node", line))?;
+            if universe.node_level(node) != level - 1 {
+                return Err(format!(
+                    "Macrocell node '{}' has children of the wrong level",
+                    line
+                ));
+            }
+            node
+        };
+    }
+    Ok(universe.join(ids[0], ids[1], ids[2], ids[3]))
+}
+
+/// Parses a file in Golly's macrocell format into a universe, reading its
+/// `#R` rule (Conway's rule by default) and its `#G` generation.
+///
+/// Both two-state files, whose leaves are 8x8 pictures of `.` and `*` cells,
+/// and multi-state files, whose leaves are `1 a b c d` lines of cell states,
+/// are supported. Every state other than 0 is read as alive. The root node,
+/// the last one of the file, is centered on the origin.
+pub fn parse_macrocell(text: &str) -> Result<HashLife, String> {
+    let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+    if !lines
+        .next()
+        .is_some_and(|line| line.starts_with(MACROCELL_HEADER))
+    {
+        return Err(format!("Missing macrocell header '{}'", MACROCELL_HEADER));
+    }
+    let mut universe = HashLife::new(Rule::conway());
+    let mut nodes: Vec<NodeId> = Vec::new();
+    let mut generation = 0;
+    for line in lines {
+        if let Some(rule) = line.strip_prefix("#R") {
+            universe.set_rule(rule.trim().parse()?);
+        } else if let Some(value) = line.strip_prefix("#G") {
+            generation = value
+                .trim()
+                .parse()
+                .map_err(|_| format!("Invalid macrocell generation '{}'", value.trim()))?;
+        } else if line.starts_with('#') {
+            continue;
+        } else if line.starts_with(['.', '*', '$']) {
+            nodes.push(parse_leaf(&mut universe, line)?);
+        } else {
+            nodes.push(parse_node(&mut universe, line, &nodes)?);
+        }
+    }
+    if let Some(&root) = nodes.last() {
+        universe.set_root(root);
+    }
+    universe.set_generation(generation);
+    Ok(universe)
+}
+
+/// Appends the line of `id` and of its children not written yet, returning
+/// the line number of `id` (0 for empty nodes).
+fn write_node(
+    universe: &HashLife,
+    id: NodeId,
+    text: &mut String,
+    lines: &mut HashMap<NodeId, usize>,
+) -> usize {
+    if universe.node_population(id) == 0 {
+        return 0;
+    }
+    if let Some(&line) = lines.get(&id) {
+        return line;
+    }
+    let level = universe.node_level(id);
+    if level == LEAF_LEVEL {
+        let mut cells = [[false; 8]; 8];
+        read_leaf(universe, id, 0, 0, &mut cells);
+        // trailing dead cells and trailing empty rows are implied
+        let last_row = cells
+            .iter()
+            .rposition(|row| row.contains(&true))
+            .unwrap_or(0);
+        for row in &cells[..=last_row] {
+            let length = row.iter().rposition(|&alive| alive).map_or(0, |x| x + 1);
+            text.extend(
+                row[..length]
+                    .iter()
+                    .map(|&alive| if alive { '*' } else { '.' }),
+            );
+            text.push('$');
+        }
+    } else {
+        let children = universe
+            .node_children(id)
+            .map(|child| write_node(universe, child, text, lines));
+        *text += &format!(
+            "{} {} {} {} {}",
+            level, children[0], children[1], children[2], children[3]
+        );
+    }
+    text.push('\n');
+    let line = lines.len() + 1;
+    lines.insert(id, line);
+    line
+}
+
+/// Copies the cells of a node of at most the leaf level into an 8x8 picture, at `(x, y)`.
+fn read_leaf(universe: &HashLife, id: NodeId, x: usize, y: usize, cells: &mut [[bool; 8]; 8]) {
+    let level = universe.node_level(id);
+    if level == 0 {
+        cells[y][x] = universe.node_population(id) > 0;
+        return;
+    }
+    let half = 1 << (level - 1);
+    let [nw, ne, sw, se] = universe.node_children(id);
+    read_leaf(universe, nw, x, y, cells);
+    read_leaf(universe, ne, x + half, y, cells);
+    read_leaf(universe, sw, x, y + half, cells);
+    read_leaf(universe, se, x + half, y + half, cells);
+}
+
+fn write_universe(universe: &HashLife, metadata: &str) -> String {
+    let mut text = format!("{} (rust_of_life)\n", MACROCELL_HEADER);
+    text += &format!("#R {}\n", universe.get_rule());
+    if universe.get_generation() > 0 {
+        text += &format!("#G {}\n", universe.get_generation());
+    }
+    text += metadata;
+    write_node(
+        universe,
+        universe.get_root(),
+        &mut text,
+        &mut HashMap::new(),
+    );
+    text
+}
+
+/// Writes a universe in Golly's two-state macrocell format, with its rule as
+/// a `#R` line and its generation as a `#G` line. Identical nodes are written
+/// once, so the size of the file follows the complexity of the pattern rather
+/// than its area.
+pub fn write_macrocell(universe: &HashLife) -> String {
+    write_universe(universe, "")
+}
+
+/// Parses a pattern in macrocell format into a grid cropped to its live cells,
+/// keeping its `#N` (name), `#O` (author) and `#C` (comment) lines.
+/// Patterns spanning more than 2^28 cells are too large to be read into a grid.
+pub fn parse_macrocell_pattern(text: &str) -> Result<Pattern, String> {
+    let universe = parse_macrocell(text)?;
+    let mut pattern = Pattern::default();
+    for line in text.lines().map(str::trim) {
+        let (tag, comment) = (line.get(..2), line.get(2..).unwrap_or(""));
+        let comment = comment.trim().to_string();
+        match tag {
+            Some("#N") => pattern.set_name(Some(comment)),
+            Some("#O") => pattern.set_author(Some(comment)),
+            Some("#C" | "#c") => pattern.add_comment(comment),
+            _ => {}
+        }
+    }
+    if text.lines().any(|line| line.trim().starts_with("#R")) {
+        pattern.set_rule(Some(universe.get_rule()));
+    }
+    if let Some((min_x, min_y, max_x, max_y)) = universe.bounding_box() {
+        let (width, height) = ((max_x - min_x + 1) as u64, (max_y - min_y + 1) as u64);
+        if width.saturating_mul(height) > MAX_GRID_CELLS {
+            return Err(format!(
+                "Macrocell pattern of {}x{} cells is too large for a grid",
+                width, height
+            ));
+        }
+        let mut grid = Grid::new(width as usize, height as usize);
+        universe.write_to_grid(&mut grid, min_x, min_y);
+        pattern.set_grid(grid);
+    }
+    Ok(pattern)
+}
+
+/// Writes a pattern in macrocell format, with its metadata as `#N`, `#O` and
+/// `#C` lines. Patterns without a rule are written as `B3/S23`.
+pub fn write_macrocell_pattern(pattern: &Pattern) -> String {
+    let grid = pattern.get_grid();
+    let mut universe = HashLife::new(pattern.get_rule().unwrap_or_default());
+    universe.load_grid(
+        grid,
+        -(grid.get_width() as i64 / 2),
+        -(grid.get_height() as i64 / 2),
+    );
+    let mut metadata = String::new();
+    if let Some(name) = pattern.get_name() {
+        metadata += &format!("#N {}\n", name);
+    }
+    if let Some(author) = pattern.get_author() {
+        metadata += &format!("#O {}\n", author);
+    }
+    for comment in pattern.get_comments() {
+        metadata += &format!("#C {}\n", comment);
+    }
+    write_universe(&universe, &metadata)
+}
@@ -0,0 +1,62 @@
+use super::Pattern;
+use crate::grid::Grid;
+
+/// Parses a pattern in LifeWiki plaintext (`.cells`) format.
+///
+/// Lines starting with `!` are comments; `!Name:`, `!Author:` and `!Rule:`
+/// comments fill in the metadata of the pattern. In the other lines `O` or `*`
+/// is a live cell and anything else is a dead cell.
+pub fn parse_plaintext(text: &str) -> Result<Pattern, String> {
+    let mut pattern = Pattern::default();
+    let mut rows: Vec<Vec<bool>> = Vec::new();
+    for line in text.lines() {
+        let line = line.trim_end();
+        if let Some(comment) = line.strip_prefix('!') {
+            if let Some(name) = comment.strip_prefix("Name:") {
+                pattern.set_name(Some(name.trim().to_string()));
+            } else if let Some(author) = comment.strip_prefix("Author:") {
+                pattern.set_author(Some(author.trim().to_string()));
+            } else if let Some(rule) = comment.strip_prefix("Rule:") {
+                pattern.set_rule(Some(rule.parse()?));
+            } else {
+                pattern.add_comment(comment.strip_prefix(' ').unwrap_or(comment).to_string());
+            }
+            continue;
+        }
+        rows.push(line.chars().map(|c| c == 'O' || c == '*').collect());
+    }
+
+    let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    let mut grid = Grid::new(width, rows.len());
+    for (y, row) in rows.iter().enumerate() {
+        for (x, &cell) in row.iter().enumerate() {
+            grid.set(x, y, cell);
+        }
+    }
+    pattern.set_grid(grid);
+    Ok(pattern)
+}
+
+/// Writes a pattern in LifeWiki plaintext (`.cells`) format, with its metadata
+/// as `!Name:`, `!Author:` and `!Rule:` comments.
+pub fn write_plaintext(pattern: &Pattern) -> String {
+    let mut text = String::new();
+    if let Some(name) = pattern.get_name() {
+        text += &format!("!Name: {}\n", name);
+    }
+    if let Some(author) = pattern.get_author() {
+        text += &format!("!Author: {}\n", author);
+    }
+    if let Some(rule) = pattern.get_rule() {
+        text += &format!("!Rule: {}\n", rule);
+    }
+    for comment in pattern.get_comments() {
+        text += &format!("!{}\n", comment);
+    }
+    let grid = pattern.get_grid();
+    for y in 0..grid.get_height() {
+        text.extend((0..grid.get_width()).map(|x| if grid.get(x, y) { 'O' } else { '.' }));
+        text.push('\n');
+    }
+    text
+}
@@ -0,0 +1,220 @@
+use super::{Pattern, MAX_GRID_CELLS};
+use crate::grid::Grid;
+use crate::rule::Rule;
+
+/// Maximum length of a line of cells written in an RLE file.
+const MAX_LINE_LENGTH: usize = 70;
+
+fn parse_header(line: &str) -> Result<(usize, usize, Option<Rule>), String> {
+    let mut width = None;
+    let mut height = None;
+    let mut rule = None;
+    // the rule is always last and may itself contain commas
+    let (sizes, rule_field) = match line.find("rule") {
+        Some(index) => (&line[..index], Some(&line[index..])),
+        None => (line, None),
+    };
+    for field in sizes.split(',').filter(|field| !field.trim().is_empty()) {
+        let (key, value) = field
+            .split_once('=')
+            .ok_or_else(|| format!("Invalid RLE header field '{}'", field.trim()))?;
+        let value = value.trim();
+        match key.trim() {
+            "x" => {
+                width = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("Invalid RLE width '{}'", value))?,
+                )
+            }
+            "y" => {
+                height = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("Invalid RLE height '{}'", value))?,
+                )
+            }
+            _ => {}
+        }
+    }
+    if let Some((_, value)) = rule_field.and_then(|field| field.split_once('=')) {
+        // Golly appends the bounded grid size after a ':' (for example "B3/S23:T100,100")
+        let value = value.trim();
+        rule = Some(value.split(':').next().unwrap_or(value).parse()?);
+    }
+    match (width, height) {
+        (Some(width), Some(height)) => {
+            check_area(width, height)?;
+            Ok((width, height, rule))
+        }
+        _ => Err(format!("RLE header '{}' is missing x or y", line)),
+    }
+}
+
+/// Returns an error if a grid of `width` by `height` cells is too large.
+fn check_area(width: usize, height: usize) -> Result<(), String> {
+    if (width as u64).saturating_mul(height as u64) > MAX_GRID_CELLS {
+        return Err(format!(
+            "RLE pattern of {}x{} cells is too large for a grid",
+            width, height
+        ));
+    }
+    Ok(())
+}
+
+fn parse_comment(pattern: &mut Pattern, line: &str) -> Result<(), String> {
+    let mut chars = line.chars();
+    chars.next();
+    let tag = chars.next().unwrap_or(' ');
+    let text = chars.as_str().trim().to_string();
+    match tag {
+        'N' => pattern.set_name(Some(text)),
+        'O' => pattern.set_author(Some(text)),
+        'C' | 'c' => pattern.add_comment(text),
+        'r' => pattern.set_rule(Some(text.parse()?)),
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Parses a pattern in Run Length Encoded format, including its `x = , y = , rule =`
+/// header and its `#N` (name), `#O` (author) and `#C` (comment) lines.
+/// Every state other than `b` (or `.` in multi-state files) is read as alive.
+pub fn parse_rle(text: &str) -> Result<Pattern, String> {
+    let mut pattern = Pattern::default();
+    let mut size: Option<(usize, usize)> = None;
+    // runs of live cells, as (start x, end x, y)
+    let mut runs: Vec<(usize, usize, usize)> = Vec::new();
+    let (mut x, mut y) = (0usize, 0usize);
+    let mut run: usize = 0;
+
+    'lines: for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if size.is_none() {
+            if line.starts_with('#') {
+                parse_comment(&mut pattern, line)?;
+                continue;
+            }
+            let (width, height, rule) = parse_header(line)?;
+            size = Some((width, height));
+            if rule.is_some() {
+                pattern.set_rule(rule);
+            }
+            continue;
+        }
+
+        for c in line.chars() {
+            let count = run.max(1);
+            match c {
+                '0'..='9' => {
+                    run = run
+                        .checked_mul(10)
+                        .and_then(|run| run.checked_add(c.to_digit(10).unwrap_or(0) as usize))
+                        .ok_or_else(|| "RLE run count is too large".to_string())?;
+                    continue;
+                }
+                'b' | '.' => x = x.saturating_add(count),
+                '$' => {
+                    y = y.saturating_add(count);
+                    x = 0;
+                }
+                '!' => break 'lines,
+                // prefix of a two-letter multi-state cell, the state follows
+                'p'..='y' => continue,
+                c if c.is_ascii_alphabetic() => {
+                    let end = x.saturating_add(count);
+                    let (width, height) = size.unwrap_or_default();
+                    check_area(end.max(width), y.saturating_add(1).max(height))?;
+                    runs.push((x, end, y));
+                    x = end;
+                }
+                c if c.is_whitespace() => continue,
+                _ => return Err(format!("Invalid character '{}' in RLE data", c)),
+            }
+            run = 0;
+        }
+    }
+
+    let (width, height) = size.ok_or_else(|| "Missing RLE header".to_string())?;
+    let width = runs.iter().map(|&(_, end, _)| end).fold(width, usize::max);
+    let height = runs.iter().map(|&(_, _, y)| y + 1).fold(height, usize::max);
+    let mut grid = Grid::new(width, height);
+    for (start, end, y) in runs {
+        for x in start..end {
+            grid.set(x, y, true);
+        }
+    }
+    pattern.set_grid(grid);
+    Ok(pattern)
+}
+
+/// Appends `token` to the RLE body, starting a new line when the current one would get too long.
+fn push_token(body: &mut String, line_length: &mut usize, count: usize, tag: char) {
+    let token = if count > 1 {
+        format!("{}{}", count, tag)
+    } else {
+        tag.to_string()
+    };
+    if *line_length + token.len() > MAX_LINE_LENGTH {
+        body.push('\n');
+        *line_length = 0;
+    }
+    *line_length += token.len();
+    body.push_str(&token);
+}
+
+/// Writes a pattern in Run Length Encoded format, with its metadata as
+/// `#N`, `#O` and `#C` lines. Patterns without a rule are written as `B3/S23`.
+pub fn write_rle(pattern: &Pattern) -> String {
+    let grid = pattern.get_grid();
+    let mut text = String::new();
+    if let Some(name) = pattern.get_name() {
+        text += &format!("#N {}\n", name);
+    }
+    if let Some(author) = pattern.get_author() {
+        text += &format!("#O {}\n", author);
+    }
+    for comment in pattern.get_comments() {
+        text += &format!("#C {}\n", comment);
+    }
+    text += &format!(
+        "x = {}, y = {}, rule = {}\n",
+        grid.get_width(),
+        grid.get_height(),
+        pattern.get_rule().unwrap_or_default()
+    );
+
+    let mut body = String::new();
+    let mut line_length = 0;
+    let mut pending_rows = 0;
+    for y in 0..grid.get_height() {
+        let mut x = 0;
+        while x < grid.get_width() {
+            let alive = grid.get(x, y);
+            let start = x;
+            while x < grid.get_width() && grid.get(x, y) == alive {
+                x += 1;
+            }
+            // trailing dead cells of a row are implied
+            if !alive && x == grid.get_width() {
+                break;
+            }
+            if pending_rows > 0 {
+                push_token(&mut body, &mut line_length, pending_rows, '$');
+                pending_rows = 0;
+            }
+            push_token(
+                &mut body,
+                &mut line_length,
+                x - start,
+                if alive { 'o' } else { 'b' },
+            );
+        }
+        pending_rows += 1;
+    }
+    push_token(&mut body, &mut line_length, 1, '!');
+    text + &body + "\n"
+}
@@ -0,0 +1,137 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// Outer-totalistic birth/survival rule for a two-state Life-like automaton.
+///
+/// Each set is stored as a bitmask where bit `n` means "`n` live neighbors".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rule {
+    birth: u16,
+    survival: u16,
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        Self::conway()
+    }
+}
+
+impl Rule {
+    /// Creates a rule from the neighbor counts that cause a birth or a survival.
+    /// Counts above 8 are ignored.
+    pub fn new(birth: &[u8], survival: &[u8]) -> Self {
+        let mask = |counts: &[u8]| {
+            counts
+                .iter()
+                .filter(|&&count| count <= 8)
+                .fold(0u16, |acc, &count| acc | (1 << count))
+        };
+        Self {
+            birth: mask(birth),
+            survival: mask(survival),
+        }
+    }
+
+    /// Returns Conway's Game of Life rule (B3/S23).
+    pub fn conway() -> Self {
+        Self::new(&[3], &[2, 3])
+    }
+
+    /// Returns true if a dead cell with `count` live neighbors becomes alive.
+    pub fn is_born(&self, count: u8) -> bool {
+        count <= 8 && self.birth & (1 << count) != 0
+    }
+
+    /// Returns true if a live cell with `count` live neighbors stays alive.
+    pub fn survives(&self, count: u8) -> bool {
+        count <= 8 && self.survival & (1 << count) != 0
+    }
+
+    /// Returns the next state of a cell given its current state and neighbor count.
+    pub fn next_state(&self, alive: bool, count: u8) -> bool {
+        if alive {
+            self.survives(count)
+        } else {
+            self.is_born(count)
+        }
+    }
+
+    /// Returns the neighbor counts that cause a birth, in ascending order.
+    pub fn get_birth(&self) -> Vec<u8> {
+        (0..=8).filter(|&count| self.is_born(count)).collect()
+    }
+
+    /// Returns the neighbor counts that keep a cell alive, in ascending order.
+    pub fn get_survival(&self) -> Vec<u8> {
+        (0..=8).filter(|&count| self.survives(count)).collect()
+    }
+}
+
+fn parse_counts(part: &str, rule: &str) -> Result<Vec<u8>, String> {
+    part.chars()
+        .map(|c| match c.to_digit(10) {
+            Some(digit) if digit <= 8 => Ok(digit as u8),
+            _ => Err(format!("Invalid neighbor count '{}' in rule '{}'", c, rule)),
+        })
+        .collect()
+}
+
+impl FromStr for Rule {
+    type Err = String;
+
+    /// Parses a rule in `B36/S23` notation (the `/` is optional and letters are
+    /// case-insensitive) or in the older `23/36` survival/birth notation.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rule = s.trim();
+        if rule.is_empty() {
+            return Err("Rule string is empty".to_string());
+        }
+
+        let upper = rule.to_ascii_uppercase();
+        if upper.starts_with('B') || upper.starts_with('S') {
+            let mut birth: Option<&str> = None;
+            let mut survival: Option<&str> = None;
+            let mut rest = upper.as_str();
+            while let Some(tag) = rest.chars().next() {
+                let slot = match tag {
+                    'B' => &mut birth,
+                    'S' => &mut survival,
+                    _ => return Err(format!("Invalid rule '{}'", rule)),
+                };
+                // the tag is ASCII, so the counts start at byte 1
+                let end = rest[1..]
+                    .find(|c: char| !c.is_ascii_digit())
+                    .map_or(rest.len(), |i| i + 1);
+                if slot.replace(&rest[1..end]).is_some() {
+                    return Err(format!("Duplicate section in rule '{}'", rule));
+                }
+                rest = rest[end..].trim_start_matches('/');
+            }
+            return Ok(Self::new(
+                &parse_counts(birth.unwrap_or(""), rule)?,
+                &parse_counts(survival.unwrap_or(""), rule)?,
+            ));
+        }
+
+        match rule.split_once('/') {
+            Some((survival, birth)) => Ok(Self::new(
+                &parse_counts(birth, rule)?,
+                &parse_counts(survival, rule)?,
+            )),
+            None => Err(format!("Invalid rule '{}'", rule)),
+        }
+    }
+}
+
+impl fmt::Display for Rule {
+    /// Formats the rule in `B3/S23` notation.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = |counts: Vec<u8>| counts.iter().map(|c| c.to_string()).collect::<String>();
+        write!(
+            f,
+            "B{}/S{}",
+            digits(self.get_birth()),
+            digits(self.get_survival())
+        )
+    }
+}
@@ -0,0 +1,115 @@
+use std::time::Duration;
+
+/// Slowest speed, in generations per second.
+pub const MIN_ITERATION_PER_SECOND: f64 = 0.1;
+
+/// Speeds the speed keys go through, in generations per second.
+/// `f64::INFINITY` computes as many generations as fit in each frame.
+pub const SPEEDS: [f64; 16] = [
+    0.1,
+    0.2,
+    0.5,
+    1.0,
+    2.0,
+    5.0,
+    10.0,
+    20.0,
+    50.0,
+    100.0,
+    200.0,
+    500.0,
+    1000.0,
+    10000.0,
+    100000.0,
+    f64::INFINITY,
+];
+
+/// Longest time between two frames taken into account. After a slow frame
+/// the game drops the generations it is late on instead of catching up.
+pub const MAX_FRAME_TIME: Duration = Duration::from_millis(250);
+
+/// Time the simulation may use in one frame before the frame is drawn,
+/// leaving the rest of a 60 Hz frame for the rendering.
+pub const FRAME_BUDGET: Duration = Duration::from_millis(12);
+
+/// Returns the next speed of `SPEEDS` above `speed`.
+pub fn faster_speed(speed: f64) -> f64 {
+    SPEEDS
+        .iter()
+        .copied()
+        .find(|&s| s > speed)
+        .unwrap_or(f64::INFINITY)
+}
+
+/// Returns the next speed of `SPEEDS` below `speed`.
+pub fn slower_speed(speed: f64) -> f64 {
+    SPEEDS
+        .iter()
+        .rev()
+        .copied()
+        .find(|&s| s < speed)
+        .unwrap_or(MIN_ITERATION_PER_SECOND)
+}
+
+/// Formats a speed for the HUD and the command line, "max" being the unlimited speed.
+pub fn format_speed(speed: f64) -> String {
+    if speed.is_infinite() {
+        "max".to_string()
+    } else {
+        format!("{}", speed)
+    }
+}
+
+/// Parses a speed in generations per second, or "max" for the unlimited speed.
+pub fn parse_speed(text: &str) -> Result<f64, String> {
+    let speed = match text.trim().to_ascii_lowercase().as_str() {
+        "max" | "unlimited" => f64::INFINITY,
+        text => text
+            .parse::<f64>()
+            .map_err(|_| format!("Invalid speed '{}'", text))?,
+    };
+    if speed.is_nan() || speed < MIN_ITERATION_PER_SECOND {
+        return Err(format!(
+            "Speed must be at least {} generations per second",
+            MIN_ITERATION_PER_SECOND
+        ));
+    }
+    Ok(speed)
+}
+
+/// Fixed-timestep scheduler deciding how many generations to compute in a
+/// frame, so that the speed of the simulation does not depend on the frame rate.
+#[derive(Debug, Clone, Default)]
+pub struct Scheduler {
+    pending: f64,
+}
+
+impl Scheduler {
+    /// Creates a scheduler with no generation due.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of generations due after `elapsed` at `speed`
+    /// generations per second.
+    ///
+    /// Fractions of a generation are carried over to the next frames, so a
+    /// speed of 0.1 computes one generation every ten seconds. An infinite
+    /// speed returns `u64::MAX`: the caller computes generations until its
+    /// frame budget runs out.
+    pub fn advance(&mut self, elapsed: Duration, speed: f64) -> u64 {
+        if speed.is_infinite() {
+            self.pending = 0.0;
+            return u64::MAX;
+        }
+        self.pending += elapsed.min(MAX_FRAME_TIME).as_secs_f64() * speed;
+        let due = self.pending.floor();
+        self.pending -= due;
+        due as u64
+    }
+
+    /// Forgets the fraction of generation carried over, when the game is paused.
+    pub fn reset(&mut self) {
+        self.pending = 0.0;
+    }
+}
@@ -1,86 +1,120 @@
-use crate::game::{Game, GameStatus};
-use chrono::Local;
+use crate::frontend::{handle_input, InputEvent, InputSource, Key, MouseButton};
+use crate::game::Game;
+use crate::grid::Grid;
 use sdl2::event::Event;
 use sdl2::keyboard::Keycode;
+use sdl2::mouse::{self, MouseWheelDirection};
 
-fn calculate_cell_position(x: i32, y: i32, game_info: &Game) -> (i32, i32) {
-    let cell_x = (x as f32 / game_info.get_unit_grid()) as i32;
-    let cell_y = (y as f32 / game_info.get_unit_grid()) as i32;
-    (cell_x, cell_y)
+/// Converts a keycode into a `Key`, or `None` for keys the game ignores.
+fn convert_keycode(keycode: Keycode) -> Option<Key> {
+    match keycode {
+        Keycode::Escape => Some(Key::Escape),
+        Keycode::Space => Some(Key::Space),
+        Keycode::Left => Some(Key::Left),
+        Keycode::Right => Some(Key::Right),
+        Keycode::Up => Some(Key::Up),
+        Keycode::Down => Some(Key::Down),
+        Keycode::LShift | Keycode::RShift => Some(Key::Shift),
+        Keycode::LCtrl | Keycode::RCtrl | Keycode::LGui | Keycode::RGui => Some(Key::Ctrl),
+        Keycode::Delete | Keycode::Backspace => Some(Key::Delete),
+        Keycode::KpPlus => Some(Key::Char('+')),
+        Keycode::KpMinus => Some(Key::Char('-')),
+        // printable keys have the keycode of their ASCII character
+        _ => u32::try_from(keycode.into_i32())
+            .ok()
+            .and_then(char::from_u32)
+            .filter(char::is_ascii_graphic)
+            .map(|c| Key::Char(c.to_ascii_lowercase())),
+    }
 }
 
-fn check_cell_in_map(cell_x: i32, cell_y: i32, game_info: &Game) -> bool {
-    cell_x >= 0
-        && cell_x < game_info.get_size_grid() as i32
-        && cell_y >= 0
-        && cell_y < game_info.get_size_grid() as i32
+fn convert_mouse_button(button: mouse::MouseButton) -> Option<MouseButton> {
+    match button {
+        mouse::MouseButton::Left => Some(MouseButton::Left),
+        mouse::MouseButton::Middle => Some(MouseButton::Middle),
+        mouse::MouseButton::Right => Some(MouseButton::Right),
+        _ => None,
+    }
 }
 
-/// Handles SDL2 events and updates game state accordingly.
-pub fn handle_event(
-    event_pump: &mut sdl2::EventPump,
-    list_color: &mut [Vec<bool>],
-    game_info: &mut Game,
-) {
-    for event in event_pump.poll_iter() {
-        match event {
-            Event::Quit { .. }
-            | Event::KeyDown {
-                keycode: Some(Keycode::Escape),
-                ..
-            } => {
-                game_info.set_game_state(GameStatus::Exit);
-            }
-            Event::KeyDown {
-                keycode: Some(Keycode::Space),
-                ..
-            } => match game_info.get_game_state() {
-                GameStatus::Pause => {
-                    game_info.set_game_state(GameStatus::Running);
-                    game_info.set_start_time(Local::now());
-                    game_info.set_start_time_iteration(game_info.get_iteration());
-                }
-                GameStatus::Running => {
-                    game_info.set_game_state(GameStatus::Pause);
-                }
-                _ => {}
+/// Converts an SDL2 event into an `InputEvent`, or `None` for events the game ignores.
+pub fn convert_event(event: Event) -> Option<InputEvent> {
+    match event {
+        Event::Quit { .. } => Some(InputEvent::Quit),
+        Event::KeyDown {
+            keycode: Some(keycode),
+            ..
+        } => convert_keycode(keycode).map(InputEvent::KeyDown),
+        Event::KeyUp {
+            keycode: Some(keycode),
+            ..
+        } => convert_keycode(keycode).map(InputEvent::KeyUp),
+        Event::DropFile { filename, .. } => Some(InputEvent::DropFile(filename)),
+        Event::MouseMotion {
+            x,
+            y,
+            xrel,
+            yrel,
+            mousestate,
+            ..
+        } => Some(InputEvent::MouseMotion {
+            x,
+            y,
+            xrel,
+            yrel,
+            left: mousestate.left(),
+            middle: mousestate.middle(),
+            right: mousestate.right(),
+        }),
+        Event::MouseWheel {
+            y,
+            direction,
+            mouse_x,
+            mouse_y,
+            ..
+        } => Some(InputEvent::MouseWheel {
+            x: mouse_x,
+            y: mouse_y,
+            delta: match direction {
+                MouseWheelDirection::Flipped => -y,
+                _ => y,
             },
-            Event::KeyDown {
-                keycode: Some(Keycode::R),
-                ..
-            } => {
-                game_info.reset();
-                list_color.iter_mut().for_each(|row| {
-                    row.iter_mut().for_each(|cell| {
-                        *cell = false;
-                    });
-                });
-            }
-            Event::MouseMotion {
-                x, y, mousestate, ..
-            } => {
-                if mousestate.left() {
-                    let (cell_x, cell_y) = calculate_cell_position(x, y, game_info);
-                    if check_cell_in_map(cell_x, cell_y, game_info) {
-                        list_color[cell_y as usize][cell_x as usize] = true;
-                    }
-                } else if mousestate.right() {
-                    let (cell_x, cell_y) = calculate_cell_position(x, y, game_info);
-                    if check_cell_in_map(cell_x, cell_y, game_info) {
-                        list_color[cell_y as usize][cell_x as usize] = false;
-                    }
-                }
-            }
-            Event::MouseButtonDown { x, y, .. } => {
-                let (cell_x, cell_y) = calculate_cell_position(x, y, game_info);
-                if check_cell_in_map(cell_x, cell_y, game_info) {
-                    match list_color[cell_y as usize][cell_x as usize] {
-                        true => list_color[cell_y as usize][cell_x as usize] = false,
-                        false => list_color[cell_y as usize][cell_x as usize] = true,
-                    }
-                }
-            }
-            _ => {}
-        }
+        }),
+        Event::MouseButtonDown {
+            x, y, mouse_btn, ..
+        } => convert_mouse_button(mouse_btn).map(|button| InputEvent::MouseButtonDown {
+            x,
+            y,
+            button,
+        }),
+        _ => None,
+    }
+}
+
+/// Input source reading the SDL2 event queue.
+pub struct SdlInput {
+    event_pump: sdl2::EventPump,
+}
+
+impl SdlInput {
+    /// Creates an input source reading the events of `event_pump`.
+    pub fn new(event_pump: sdl2::EventPump) -> Self {
+        Self { event_pump }
+    }
+}
+
+impl InputSource for SdlInput {
+    fn poll_events(&mut self) -> Vec<InputEvent> {
+        self.event_pump
+            .poll_iter()
+            .filter_map(convert_event)
+            .collect()
+    }
+}
+
+/// Handles SDL2 events and updates game state accordingly.
+pub fn handle_event(event_pump: &mut sdl2::EventPump, list_color: &mut Grid, game_info: &mut Game) {
+    for event in event_pump.poll_iter().filter_map(convert_event) {
+        handle_input(&event, list_color, game_info);
     }
 }
@@ -0,0 +1,79 @@
+use crate::frontend::Label;
+use crate::sdl_lib::render::{generate_texture, get_target_for_texture};
+use sdl2::pixels::Color;
+use sdl2::rect::FRect;
+use sdl2::render::{Canvas, Texture, TextureCreator};
+use sdl2::ttf::Font;
+use sdl2::video::{Window, WindowContext};
+
+/// Label of the HUD and the texture its text was rendered into.
+struct HudLabel<'a> {
+    text: String,
+    texture: Texture<'a>,
+    target: FRect,
+}
+
+/// Text shown over the grid, rendered into textures that are kept from one
+/// frame to the next. A label is rendered again only when its text changes,
+/// since rendering a TTF surface and uploading it costs more than computing
+/// a generation of a small grid.
+pub struct Hud<'a> {
+    texture_creator: &'a TextureCreator<WindowContext>,
+    color: Color,
+    labels: Vec<HudLabel<'a>>,
+    render_count: usize,
+}
+
+impl<'a> Hud<'a> {
+    /// Creates an empty HUD whose text is drawn in `color`.
+    pub fn new(texture_creator: &'a TextureCreator<WindowContext>, color: Color) -> Self {
+        Self {
+            texture_creator,
+            color,
+            labels: Vec::new(),
+            render_count: 0,
+        }
+    }
+
+    /// Returns the number of labels rendered since the HUD was created.
+    pub fn get_render_count(&self) -> usize {
+        self.render_count
+    }
+
+    /// Shows `labels`, rendering with `font` only the ones whose text changed.
+    pub fn update(&mut self, font: &Font, labels: &[Label]) -> Result<(), String> {
+        self.labels.truncate(labels.len());
+        for (index, label) in labels.iter().enumerate() {
+            match self.labels.get_mut(index) {
+                Some(cached) if cached.text == label.text => {
+                    cached.target.set_x(label.x as f32);
+                    cached.target.set_y(label.y as f32);
+                }
+                cached => {
+                    let texture =
+                        generate_texture(font, &label.text, self.color, self.texture_creator)?;
+                    let hud_label = HudLabel {
+                        text: label.text.clone(),
+                        target: get_target_for_texture(&texture, label.x, label.y),
+                        texture,
+                    };
+                    self.render_count += 1;
+                    match cached {
+                        Some(cached) => *cached = hud_label,
+                        None => self.labels.push(hud_label),
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Copies the labels onto `canvas`.
+    pub fn draw(&self, canvas: &mut Canvas<Window>) {
+        for label in &self.labels {
+            if let Err(e) = canvas.copy_f(&label.texture, None, Some(label.target)) {
+                eprintln!("Error copying texture of '{}': {}", label.text, e);
+            }
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use crate::frontend::{self, Frame, Point, Rect, Renderer};
+use crate::sdl_lib::hud::Hud;
 use sdl2::pixels::Color;
 use sdl2::rect::{FPoint, FRect};
 use sdl2::render::{Canvas, Texture, TextureCreator, TextureQuery};
@@ -5,12 +7,6 @@ use sdl2::surface::Surface;
 use sdl2::ttf::{self, Font, Sdl2TtfContext};
 use sdl2::video::{Window, WindowContext};
 
-/// Structure holding a texture and its target rectangle.
-pub struct TextureWithRect<'a> {
-    pub texture: &'a Texture<'a>,
-    pub target: FRect,
-}
-
 /// Generates a texture from rendered text using the provided font and color.
 pub fn generate_texture<'a>(
     font: &Font,
@@ -76,6 +72,8 @@ pub fn init_canvas(
 
 pub const WHITE: Color = Color::RGB(255, 255, 255);
 pub const BLACK: Color = Color::RGB(0, 0, 0);
+pub const GRAY: Color = Color::RGB(160, 160, 160);
+pub const BLUE: Color = Color::RGB(0, 100, 255);
 
 pub fn get_target_for_texture(
     texture: &Texture,
@@ -95,33 +93,125 @@ pub fn get_target_for_texture(
     )
 }
 
-pub fn draw_game(
+impl From<Point> for FPoint {
+    fn from(point: Point) -> Self {
+        FPoint::new(point.x, point.y)
+    }
+}
+
+impl From<Rect> for FRect {
+    fn from(rect: Rect) -> Self {
+        FRect::new(rect.x, rect.y, rect.width, rect.height)
+    }
+}
+
+impl From<frontend::Color> for Color {
+    fn from(color: frontend::Color) -> Self {
+        Color::RGB(color.r, color.g, color.b)
+    }
+}
+
+/// Fills `rects` on `canvas`, each in the color of `colors` at the same
+/// index, in one batch per run of the same color.
+pub fn fill_colored_rects(
     canvas: &mut Canvas<Window>,
-    list_lines: &[FPoint],
-    cell_rects: &[FRect],
-    iteration: TextureWithRect,
-    population: TextureWithRect,
-    iteration_per_second: TextureWithRect,
+    rects: &[FRect],
+    colors: &[frontend::Color],
 ) {
+    let mut start = 0;
+    while start < rects.len().min(colors.len()) {
+        let color = colors[start];
+        let end = start + colors[start..].iter().take_while(|&&c| c == color).count();
+        canvas.set_draw_color(Color::from(color));
+        if let Err(e) = canvas.fill_frects(&rects[start..end.min(rects.len())]) {
+            eprintln!("Error filling rectangles: {}", e);
+        }
+        start = end;
+    }
+}
+
+/// Draws the outline of the population graph and its series in their colors on `canvas`.
+pub fn draw_graph(canvas: &mut Canvas<Window>, frame: &Frame) {
+    if let Some(area) = frame.graph_area.map(FRect::from) {
+        canvas.set_draw_color(GRAY);
+        if let Err(e) = canvas.draw_frect(area) {
+            eprintln!("Error drawing graph outline: {}", e);
+        }
+    }
+    for series in frame.graph {
+        let points: Vec<FPoint> = series.points.iter().map(|&p| p.into()).collect();
+        canvas.set_draw_color(Color::from(series.color));
+        if let Err(e) = canvas.draw_flines(points.as_slice()) {
+            eprintln!("Error drawing graph: {}", e);
+        }
+    }
+}
+
+/// Draws the fade trail, the grid lines, the live cells in their colors, the
+/// ghost of the clipboard being pasted, the outline of the selection, the
+/// population graph and the HUD on `canvas`.
+pub fn draw_game(canvas: &mut Canvas<Window>, frame: &Frame, hud: &Hud) {
+    let list_lines: Vec<FPoint> = frame.grid_lines.iter().map(|&p| p.into()).collect();
+    let cell_rects: Vec<FRect> = frame.cells.iter().map(|&r| r.into()).collect();
+    let trail_rects: Vec<FRect> = frame.trail.iter().map(|&r| r.into()).collect();
+    let ghost_rects: Vec<FRect> = frame.ghost.iter().map(|&r| r.into()).collect();
+
+    fill_colored_rects(canvas, &trail_rects, frame.trail_colors);
     canvas.set_draw_color(BLACK);
-    if let Err(e) = canvas.draw_flines(list_lines) {
+    if let Err(e) = canvas.draw_flines(list_lines.as_slice()) {
         eprintln!("Error drawing lines: {}", e);
     }
-    if let Err(e) = canvas.fill_frects(cell_rects) {
-        eprintln!("Error filling rectangles: {}", e);
+    fill_colored_rects(canvas, &cell_rects, frame.cell_colors);
+    canvas.set_draw_color(GRAY);
+    if let Err(e) = canvas.fill_frects(&ghost_rects) {
+        eprintln!("Error filling ghost rectangles: {}", e);
+    }
+    if let Some(selection) = frame.selection.map(FRect::from) {
+        canvas.set_draw_color(BLUE);
+        if let Err(e) = canvas.draw_frect(selection) {
+            eprintln!("Error drawing selection: {}", e);
+        }
     }
+    draw_graph(canvas, frame);
     canvas.set_draw_color(WHITE);
-    if let Err(e) = canvas.copy_f(iteration.texture, None, Some(iteration.target)) {
-        eprintln!("Error copying texture_iteration: {}", e);
+    hud.draw(canvas);
+}
+
+/// Renderer drawing the frames in an SDL2 window, with the HUD text rendered by SDL2_ttf.
+pub struct SdlRenderer<'a> {
+    canvas: Canvas<Window>,
+    font: Font<'a, 'a>,
+    hud: Hud<'a>,
+}
+
+impl<'a> SdlRenderer<'a> {
+    /// Creates a renderer drawing on `canvas` and writing the HUD with `font`
+    /// into textures created by `texture_creator`.
+    pub fn new(
+        canvas: Canvas<Window>,
+        texture_creator: &'a TextureCreator<WindowContext>,
+        font: Font<'a, 'a>,
+    ) -> Self {
+        Self {
+            canvas,
+            font,
+            hud: Hud::new(texture_creator, BLACK),
+        }
     }
-    if let Err(e) = canvas.copy_f(population.texture, None, Some(population.target)) {
-        eprintln!("Error copying texture_population: {}", e);
+}
+
+impl Renderer for SdlRenderer<'_> {
+    fn get_size(&self) -> (u32, u32) {
+        self.canvas.window().size()
     }
-    if let Err(e) = canvas.copy_f(
-        iteration_per_second.texture,
-        None,
-        Some(iteration_per_second.target),
-    ) {
-        eprintln!("Error copying texture_iteration_per_second: {}", e);
+
+    fn draw(&mut self, frame: &Frame) -> Result<(), String> {
+        self.hud.update(&self.font, frame.labels)?;
+
+        self.canvas.set_draw_color(WHITE);
+        self.canvas.clear();
+        draw_game(&mut self.canvas, frame, &self.hud);
+        self.canvas.present();
+        Ok(())
     }
 }
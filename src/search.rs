@@ -0,0 +1,610 @@
+use crate::census::{find_objects, Census, CensusFormat, Object, MERGE_DISTANCE};
+use crate::cli::Options;
+use crate::cycle::CycleDetector;
+use crate::engine::{Engine, Universe};
+use crate::game::Game;
+use crate::grid::Grid;
+use crate::headless::DEFAULT_MAX_GENERATIONS;
+use crate::rule::Rule;
+use crate::sparse::SparseLife;
+use crate::utils::{fill_random, format_density};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Number of soups of a search when no other one is given.
+pub const DEFAULT_SOUP_COUNT: u64 = 1000;
+
+/// Number of cells on each side of a soup when no grid size is given.
+pub const DEFAULT_SOUP_SIZE: usize = 16;
+
+/// Number of longest-lived soups reported when no other one is given.
+pub const DEFAULT_TOP_COUNT: usize = 10;
+
+/// Number of generations the population of a soup must repeat over before
+/// the soup counts as settled.
+pub const SETTLE_WINDOW: usize = 500;
+
+/// Longest period of the population of a settled soup, and of what is left
+/// of it once the spaceships escaping it are left out.
+pub const MAX_SETTLE_PERIOD: usize = 60;
+
+/// Population beyond which a soup is given up as explosive.
+pub const MAX_SOUP_POPULATION: u64 = 1 << 16;
+
+/// Number of seeds kept as samples of each spaceship and uncommon oscillator found.
+pub const SAMPLE_SEEDS: usize = 3;
+
+/// Oscillators found in most soups, which are not reported as outliers:
+/// the blinker, the toad and the beacon.
+pub const COMMON_OSCILLATORS: [&str; 3] = ["xp2_7", "xp2_7e", "xp2_318c"];
+
+/// Returns true if `apgcode` names a spaceship, oversized or not.
+fn is_spaceship(apgcode: &str) -> bool {
+    apgcode.starts_with("xq") || apgcode.starts_with("ov_q")
+}
+
+/// Returns true if `apgcode` names an outlier worth reporting with its
+/// seeds: a spaceship, or an oscillator that is not one of the common ones.
+fn is_outlier(apgcode: &str) -> bool {
+    is_spaceship(apgcode)
+        || ((apgcode.starts_with("xp") || apgcode.starts_with("ov_p"))
+            && !COMMON_OSCILLATORS.contains(&apgcode))
+}
+
+/// Settings of a batch soup search.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchOptions {
+    soups: u64,
+    generations: Option<u64>,
+    top: usize,
+    output_path: Option<String>,
+    census_path: Option<String>,
+    census_format: Option<CensusFormat>,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        Self {
+            soups: DEFAULT_SOUP_COUNT,
+            generations: None,
+            top: DEFAULT_TOP_COUNT,
+            output_path: None,
+            census_path: None,
+            census_format: None,
+        }
+    }
+}
+
+impl SearchOptions {
+    /// Returns the number of soups to run.
+    pub fn get_soups(&self) -> u64 {
+        self.soups
+    }
+
+    /// Returns the number of generations after which a soup is given up,
+    /// `DEFAULT_MAX_GENERATIONS` if `None`.
+    pub fn get_generations(&self) -> Option<u64> {
+        self.generations
+    }
+
+    /// Returns the number of longest-lived soups reported.
+    pub fn get_top(&self) -> usize {
+        self.top
+    }
+
+    /// Returns the file the report is written to, stdout if `None`.
+    pub fn get_output_path(&self) -> Option<&str> {
+        self.output_path.as_deref()
+    }
+
+    /// Returns the file the census of every soup is written to, if any.
+    pub fn get_census_path(&self) -> Option<&str> {
+        self.census_path.as_deref()
+    }
+
+    /// Returns the format of the census: the one given, the one matching the
+    /// extension of the census file, or text.
+    pub fn get_census_format(&self) -> CensusFormat {
+        self.census_format
+            .or_else(|| {
+                self.census_path
+                    .as_deref()
+                    .and_then(CensusFormat::from_path)
+            })
+            .unwrap_or_default()
+    }
+
+    /// Sets the number of soups to run.
+    pub fn set_soups(&mut self, soups: u64) {
+        self.soups = soups;
+    }
+
+    /// Sets the number of generations after which a soup is given up.
+    pub fn set_generations(&mut self, generations: Option<u64>) {
+        self.generations = generations;
+    }
+
+    /// Sets the number of longest-lived soups reported.
+    pub fn set_top(&mut self, top: usize) {
+        self.top = top;
+    }
+
+    /// Sets the file the report is written to.
+    pub fn set_output_path(&mut self, output_path: Option<String>) {
+        self.output_path = output_path;
+    }
+
+    /// Sets the file the census of every soup is written to.
+    pub fn set_census_path(&mut self, census_path: Option<String>) {
+        self.census_path = census_path;
+    }
+
+    /// Sets the format of the census.
+    pub fn set_census_format(&mut self, census_format: Option<CensusFormat>) {
+        self.census_format = census_format;
+    }
+}
+
+/// Outcome of one soup of a search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SoupResult {
+    seed: u64,
+    lifespan: Option<u64>,
+    census: Census,
+}
+
+impl SoupResult {
+    /// Returns the seed the soup was generated from.
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Returns the generation from which the population of the soup repeats,
+    /// or `None` if it did not settle before the limit or exploded.
+    pub fn get_lifespan(&self) -> Option<u64> {
+        self.lifespan
+    }
+
+    /// Returns the objects the soup settled into, empty if it did not settle.
+    pub fn get_census(&self) -> &Census {
+        &self.census
+    }
+}
+
+/// Returns the generation from which `populations` has been repeating with
+/// a period of at most `MAX_SETTLE_PERIOD`, if it has for the last
+/// `SETTLE_WINDOW` generations.
+fn settled_since(populations: &[u64]) -> Option<u64> {
+    let last = populations.len().checked_sub(1)?;
+    (1..=MAX_SETTLE_PERIOD)
+        .filter(|&period| last >= SETTLE_WINDOW + period)
+        .find(|&period| {
+            (0..SETTLE_WINDOW).all(|i| populations[last - i] == populations[last - i - period])
+        })
+        .map(|period| {
+            let mut start = last - SETTLE_WINDOW - period + 1;
+            while start > 0 && populations[start - 1] == populations[start - 1 + period] {
+                start -= 1;
+            }
+            start as u64
+        })
+}
+
+/// Returns `(min_x, min_y, max_x, max_y)` of `cells`, or `None` if there are none.
+fn bounds<'a>(cells: impl IntoIterator<Item = &'a (i64, i64)>) -> Option<(i64, i64, i64, i64)> {
+    cells.into_iter().fold(None, |bounds, &(x, y)| {
+        Some(match bounds {
+            None => (x, y, x, y),
+            Some((min_x, min_y, max_x, max_y)) => {
+                (min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y))
+            }
+        })
+    })
+}
+
+/// Returns how far the object `object` of apgcode `apgcode` moves in a
+/// period and the period: `(0, 0, 1)` for objects that stay in place.
+fn get_velocity(object: &[(i64, i64)], apgcode: &str, rule: Rule) -> (i64, i64, u64) {
+    let period = apgcode
+        .trim_start_matches("xq")
+        .trim_start_matches("ov_q")
+        .split('_')
+        .next()
+        .and_then(|period| period.parse::<u64>().ok());
+    let Some(period) = period.filter(|_| is_spaceship(apgcode)) else {
+        return (0, 0, 1);
+    };
+    let mut spaceship = SparseLife::new(rule);
+    for &(x, y) in object {
+        spaceship.set(x, y, true);
+    }
+    let before = spaceship.bounding_box();
+    for _ in 0..period {
+        spaceship.next_generation();
+    }
+    match (before, spaceship.bounding_box()) {
+        (Some((x, y, _, _)), Some((later_x, later_y, _, _))) => (later_x - x, later_y - y, period),
+        _ => (0, 0, 1),
+    }
+}
+
+/// Returns true if two objects within the bounding boxes `a` and `b`,
+/// moving at the velocities `velocity_a` and `velocity_b`, never come within
+/// `MERGE_DISTANCE` cells of each other: on some axis, they are already
+/// further apart and do not get any closer.
+fn never_meet(
+    a: (i64, i64, i64, i64),
+    velocity_a: (i64, i64, u64),
+    b: (i64, i64, i64, i64),
+    velocity_b: (i64, i64, u64),
+) -> bool {
+    let (dx_a, dy_a, period_a) = velocity_a;
+    let (dx_b, dy_b, period_b) = velocity_b;
+    // the sign of the velocity of `a` relative to `b` on each axis
+    let relative_x = (dx_a * period_b as i64 - dx_b * period_a as i64).signum();
+    let relative_y = (dy_a * period_b as i64 - dy_b * period_a as i64).signum();
+    (a.2 + MERGE_DISTANCE < b.0 && relative_x <= 0)
+        || (b.2 + MERGE_DISTANCE < a.0 && relative_x >= 0)
+        || (a.3 + MERGE_DISTANCE < b.1 && relative_y <= 0)
+        || (b.3 + MERGE_DISTANCE < a.1 && relative_y >= 0)
+}
+
+/// Returns the objects of the live `cells` of a soup whose population
+/// repeats with their apgcode, if the soup did settle: once the spaceships
+/// escaping it are left out, the rest must come back in place within
+/// `MAX_SETTLE_PERIOD` generations, and not only its population.
+///
+/// A spaceship escapes if it never meets any other object again, whether
+/// the other object stays in place or moves away as well.
+pub fn settled_objects(cells: Vec<(i64, i64)>, rule: Rule) -> Option<Vec<Object>> {
+    let objects = find_objects(cells, rule);
+    // the objects found are never empty
+    let moving: Vec<_> = objects
+        .iter()
+        .map(|(object, code)| {
+            let bounds = bounds(object).unwrap_or_default();
+            (bounds, get_velocity(object, code, rule))
+        })
+        .collect();
+    let mut rest = SparseLife::new(rule);
+    for (index, (object, code)) in objects.iter().enumerate() {
+        let escaping = is_spaceship(code)
+            && moving
+                .iter()
+                .enumerate()
+                .all(|(other, &(bounds, velocity))| {
+                    let (object_bounds, object_velocity) = moving[index];
+                    other == index || never_meet(object_bounds, object_velocity, bounds, velocity)
+                });
+        if !escaping {
+            for &(x, y) in object {
+                rest.set(x, y, true);
+            }
+        }
+    }
+    let mut detector = CycleDetector::new();
+    for _ in 0..=MAX_SETTLE_PERIOD {
+        if let Some(cycle) = detector.record_universe(&rest) {
+            return (cycle.get_start() == 0 && cycle.get_displacement() == (0, 0))
+                .then_some(objects);
+        }
+        rest.next_generation();
+    }
+    None
+}
+
+/// Runs the random soup of `size` by `size` cells generated from `seed` with
+/// the rule and density of `game_info` on the unbounded plane, until it
+/// settles, it exceeds `MAX_SOUP_POPULATION` or `limit` generations have
+/// passed, then takes the census of what it settled into. A soup settles
+/// when its population repeats and, escaping spaceships aside, its cells do.
+///
+/// The soup is the one `rust_of_life -e sparse --seed <seed>` fills a grid of
+/// the same size with, so every result can be looked at again: the report
+/// gives the whole command.
+pub fn run_soup(seed: u64, size: usize, game_info: &Game, limit: u64) -> SoupResult {
+    let rule = game_info.get_rule();
+    let mut grid = Grid::new(size, size);
+    fill_random(&mut grid, game_info.get_density(), seed);
+    let mut universe = SparseLife::new(rule);
+    universe.load_grid(&grid, 0, 0);
+
+    let mut populations = Vec::new();
+    let mut lifespan = None;
+    let mut objects = Vec::new();
+    let mut next_check = 0;
+    for generation in 0..=limit {
+        let population = universe.population();
+        if population > MAX_SOUP_POPULATION {
+            break;
+        }
+        populations.push(population);
+        if let Some(since) = settled_since(&populations).filter(|_| generation >= next_check) {
+            if let Some(settled) = settled_objects(universe.live_cells(), rule) {
+                lifespan = Some(since);
+                objects = settled;
+                break;
+            }
+            // the population repeats but the cells do not: look again later
+            next_check = generation + SETTLE_WINDOW as u64;
+        }
+        universe.next_generation();
+    }
+    let mut census = Census::new(rule);
+    for (_, code) in &objects {
+        census.add(code, 1);
+    }
+    SoupResult {
+        seed,
+        lifespan,
+        census,
+    }
+}
+
+/// Results of a batch soup search, gathered over every soup.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SearchReport {
+    rule: Rule,
+    size: usize,
+    density: f64,
+    soups: u64,
+    census: Census,
+    total_lifespan: u64,
+    longest: Vec<(u64, u64)>,
+    top: usize,
+    unsettled: Vec<u64>,
+    samples: BTreeMap<String, Vec<u64>>,
+    elapsed: Duration,
+}
+
+impl SearchReport {
+    /// Creates the report of a search with no soup yet, keeping the `top`
+    /// longest-lived soups.
+    pub fn new(game_info: &Game, size: usize, top: usize) -> Self {
+        Self {
+            rule: game_info.get_rule(),
+            size,
+            density: game_info.get_density(),
+            census: Census::new(game_info.get_rule()),
+            top,
+            ..Self::default()
+        }
+    }
+
+    /// Returns the number of soups run.
+    pub fn get_soups(&self) -> u64 {
+        self.soups
+    }
+
+    /// Returns the objects of every settled soup.
+    pub fn get_census(&self) -> &Census {
+        &self.census
+    }
+
+    /// Returns the average lifespan of the settled soups.
+    pub fn get_average_lifespan(&self) -> f64 {
+        let settled = self.soups - self.unsettled.len() as u64;
+        if settled == 0 {
+            return 0.0;
+        }
+        self.total_lifespan as f64 / settled as f64
+    }
+
+    /// Returns the lifespan and seed of the longest-lived soups, the longest first.
+    pub fn get_longest(&self) -> &[(u64, u64)] {
+        &self.longest
+    }
+
+    /// Returns the seeds of the soups that did not settle, in ascending order.
+    pub fn get_unsettled(&self) -> &[u64] {
+        &self.unsettled
+    }
+
+    /// Returns the smallest seeds of the soups each spaceship and uncommon
+    /// oscillator was found in, by apgcode.
+    pub fn get_samples(&self) -> &BTreeMap<String, Vec<u64>> {
+        &self.samples
+    }
+
+    /// Returns the time spent running the soups.
+    pub fn get_elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Adds the outcome of one soup.
+    pub fn add(&mut self, result: &SoupResult) {
+        self.soups += 1;
+        match result.lifespan {
+            Some(lifespan) => {
+                self.total_lifespan += lifespan;
+                self.longest.push((lifespan, result.seed));
+                self.trim_longest();
+            }
+            None => {
+                self.unsettled.push(result.seed);
+                self.unsettled.sort_unstable();
+            }
+        }
+        self.census.merge(&result.census);
+        for (apgcode, _) in result.census.get_objects() {
+            if is_outlier(apgcode) {
+                let seeds = self.samples.entry(apgcode.to_string()).or_default();
+                seeds.push(result.seed);
+                seeds.sort_unstable();
+                seeds.truncate(SAMPLE_SEEDS);
+            }
+        }
+    }
+
+    /// Adds the soups of `other`, a report of another part of the same search.
+    pub fn merge(&mut self, other: &SearchReport) {
+        self.soups += other.soups;
+        self.total_lifespan += other.total_lifespan;
+        self.longest.extend_from_slice(&other.longest);
+        self.trim_longest();
+        self.unsettled.extend_from_slice(&other.unsettled);
+        self.unsettled.sort_unstable();
+        self.census.merge(&other.census);
+        for (apgcode, other_seeds) in &other.samples {
+            let seeds = self.samples.entry(apgcode.clone()).or_default();
+            seeds.extend_from_slice(other_seeds);
+            seeds.sort_unstable();
+            seeds.truncate(SAMPLE_SEEDS);
+        }
+    }
+
+    /// Keeps the `top` longest lifespans, the smallest seed first among equals,
+    /// so that the report does not depend on the order the soups ran in.
+    fn trim_longest(&mut self) {
+        self.longest
+            .sort_unstable_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        self.longest.truncate(self.top);
+    }
+}
+
+impl fmt::Display for SearchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let seeds = |seeds: &[u64]| {
+            seeds
+                .iter()
+                .map(u64::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        writeln!(f, "rule: {}", self.rule)?;
+        writeln!(
+            f,
+            "soups: {} of {}x{} cells at {}",
+            self.soups,
+            self.size,
+            self.size,
+            format_density(self.density)
+        )?;
+        // the soups run on the unbounded plane, so on the sparse engine
+        writeln!(
+            f,
+            "look again: rust_of_life -e sparse -s {} -r {} --density {} --seed <SEED>",
+            self.size,
+            self.rule,
+            format_density(self.density)
+        )?;
+        match self.unsettled.len() {
+            0 => writeln!(f, "unsettled: none")?,
+            _ => writeln!(f, "unsettled: seeds {}", seeds(&self.unsettled))?,
+        }
+        writeln!(f, "average lifespan: {:.1}", self.get_average_lifespan())?;
+        writeln!(f, "longest-lived:")?;
+        for (lifespan, seed) in &self.longest {
+            writeln!(f, "  {} generations: seed {}", lifespan, seed)?;
+        }
+        // the outliers, the rarest first
+        let mut outliers: Vec<(&String, &Vec<u64>)> = self.samples.iter().collect();
+        outliers.sort_by_key(|(apgcode, _)| (self.census.get_count(apgcode), *apgcode));
+        for (title, spaceships) in [("spaceships:", true), ("uncommon oscillators:", false)] {
+            let found: Vec<_> = outliers
+                .iter()
+                .filter(|(apgcode, _)| is_spaceship(apgcode) == spaceships)
+                .collect();
+            match found.len() {
+                0 => writeln!(f, "{} none", title)?,
+                _ => writeln!(f, "{}", title)?,
+            }
+            for (apgcode, samples) in found {
+                writeln!(
+                    f,
+                    "  {} {}: seeds {}",
+                    apgcode,
+                    self.census.get_count(apgcode),
+                    seeds(samples)
+                )?;
+            }
+        }
+        writeln!(f, "objects: {}", self.census.get_total())?;
+        for (apgcode, count) in self.census.get_objects() {
+            writeln!(f, "  {} {}", apgcode, count)?;
+        }
+        let seconds = self.elapsed.as_secs_f64();
+        writeln!(f, "elapsed: {:.3} s", seconds)?;
+        if seconds > 0.0 {
+            writeln!(f, "soups / s: {:.1}", self.soups as f64 / seconds)?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs `options.get_soups()` random soups of `size` by `size` cells, of
+/// seeds `first_seed`, `first_seed + 1` and so on, with the rule and density
+/// of `game_info`, on `game_info.get_thread_count()` threads, and gathers
+/// their results.
+///
+/// The report is the same whatever the number of threads.
+pub fn run_search(
+    game_info: &Game,
+    size: usize,
+    first_seed: u64,
+    options: &SearchOptions,
+) -> Result<SearchReport, String> {
+    // the soups run on the unbounded plane
+    Engine::Sparse.check_rule(&game_info.get_rule())?;
+    let limit = options.generations.unwrap_or(DEFAULT_MAX_GENERATIONS);
+    let next = AtomicU64::new(0);
+    let report = Mutex::new(SearchReport::new(game_info, size, options.top));
+    let start = Instant::now();
+    std::thread::scope(|scope| {
+        for _ in 0..game_info.get_thread_count().max(1) {
+            scope.spawn(|| {
+                let mut part = SearchReport::new(game_info, size, options.top);
+                loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    if index >= options.soups {
+                        break;
+                    }
+                    let seed = first_seed.wrapping_add(index);
+                    part.add(&run_soup(seed, size, game_info, limit));
+                }
+                if let Ok(mut report) = report.lock() {
+                    report.merge(&part);
+                }
+            });
+        }
+    });
+    let mut report = report
+        .into_inner()
+        .map_err(|_| "A soup search thread panicked".to_string())?;
+    report.elapsed = start.elapsed();
+    Ok(report)
+}
+
+/// Runs the soup search described on the command line, then writes the
+/// report to the output file (stdout by default) and the census of every
+/// soup to the census file, if any.
+pub fn run_search_with(options: &Options, search: &SearchOptions) -> Result<(), String> {
+    let mut game_info = Game::new();
+    options.apply(&mut game_info);
+    let size = options
+        .get_size_grid()
+        .map_or(DEFAULT_SOUP_SIZE, |size| size as usize);
+    let first_seed = game_info.get_seed().unwrap_or_else(rand::random);
+    eprintln!(
+        "Soup search seeds: {} to {} (density {})",
+        first_seed,
+        first_seed.wrapping_add(search.get_soups().saturating_sub(1)),
+        format_density(game_info.get_density())
+    );
+
+    let report = run_search(&game_info, size, first_seed, search)?;
+    match search.get_output_path() {
+        Some(path) => fs::write(path, report.to_string())
+            .map_err(|e| format!("Failed to write {}: {}", path, e))?,
+        None => print!("{}", report),
+    }
+    if let Some(path) = search.get_census_path() {
+        fs::write(path, report.get_census().write(search.get_census_format()))
+            .map_err(|e| format!("Failed to write {}: {}", path, e))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,232 @@
+use crate::grid::Grid;
+
+/// Rectangle of cells of the grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Region {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Region {
+    /// Creates the region of `width` by `height` cells whose top-left corner is `(x, y)`.
+    pub fn new(x: usize, y: usize, width: usize, height: usize) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Creates the smallest region containing the cells `a` and `b`.
+    pub fn from_corners(a: (usize, usize), b: (usize, usize)) -> Self {
+        Self::new(
+            a.0.min(b.0),
+            a.1.min(b.1),
+            a.0.abs_diff(b.0) + 1,
+            a.1.abs_diff(b.1) + 1,
+        )
+    }
+
+    /// Returns the part of the region inside `grid`, or `None` if it is all outside.
+    pub fn clip(&self, grid: &Grid) -> Option<Region> {
+        let width = self.width.min(grid.get_width().saturating_sub(self.x));
+        let height = self.height.min(grid.get_height().saturating_sub(self.y));
+        (width > 0 && height > 0).then(|| Region::new(self.x, self.y, width, height))
+    }
+}
+
+/// Selected region of the grid, clipboard and paste in progress.
+///
+/// While pasting, the clipboard follows the cursor centered on it, until it
+/// is placed or the paste is canceled.
+#[derive(Debug, Clone, Default)]
+pub struct Selection {
+    region: Option<Region>,
+    anchor: Option<(usize, usize)>,
+    clipboard: Option<Grid>,
+    pasting: bool,
+    cursor: (i64, i64),
+}
+
+impl Selection {
+    /// Creates an empty selection with an empty clipboard.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the selected region.
+    pub fn get_region(&self) -> Option<Region> {
+        self.region
+    }
+
+    /// Returns the cells copied or cut last.
+    pub fn get_clipboard(&self) -> Option<&Grid> {
+        self.clipboard.as_ref()
+    }
+
+    /// Returns the cell under the mouse.
+    pub fn get_cursor(&self) -> (i64, i64) {
+        self.cursor
+    }
+
+    /// Returns true while the clipboard follows the cursor to be pasted.
+    pub fn is_pasting(&self) -> bool {
+        self.pasting
+    }
+
+    /// Selects `region` of `grid`, or nothing.
+    pub fn set_region(&mut self, region: Option<Region>, grid: &Grid) {
+        self.region = region.and_then(|region| region.clip(grid));
+        self.anchor = None;
+    }
+
+    /// Sets the cell under the mouse.
+    pub fn set_cursor(&mut self, cursor: (i64, i64)) {
+        self.cursor = cursor;
+    }
+
+    /// Sets the cells copied or cut last.
+    pub fn set_clipboard(&mut self, clipboard: Option<Grid>) {
+        self.clipboard = clipboard;
+    }
+
+    /// Starts selecting from the cell `(x, y)` of `grid`.
+    pub fn start_drag(&mut self, x: i64, y: i64, grid: &Grid) {
+        if grid.contains(x, y) {
+            self.anchor = Some((x as usize, y as usize));
+            self.region = Some(Region::new(x as usize, y as usize, 1, 1));
+        }
+    }
+
+    /// Extends the region being selected to the cell `(x, y)`, kept inside `grid`.
+    pub fn drag_to(&mut self, x: i64, y: i64, grid: &Grid) {
+        if let Some(anchor) = self.anchor {
+            let clamp = |value: i64, length: usize| value.clamp(0, length as i64 - 1) as usize;
+            let corner = (clamp(x, grid.get_width()), clamp(y, grid.get_height()));
+            self.region = Some(Region::from_corners(anchor, corner));
+        }
+    }
+
+    /// Copies the selected cells to the clipboard. Returns false if nothing is selected.
+    pub fn copy(&mut self, grid: &Grid) -> bool {
+        match self.region {
+            Some(region) => {
+                self.clipboard = Some(grid.crop(region.x, region.y, region.width, region.height));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Copies the selected cells to the clipboard and kills them.
+    pub fn cut(&mut self, grid: &mut Grid) -> bool {
+        self.copy(grid) && self.clear(grid)
+    }
+
+    /// Kills the selected cells.
+    pub fn clear(&self, grid: &mut Grid) -> bool {
+        self.fill_with(grid, false)
+    }
+
+    /// Brings the selected cells to life.
+    pub fn fill(&self, grid: &mut Grid) -> bool {
+        self.fill_with(grid, true)
+    }
+
+    fn fill_with(&self, grid: &mut Grid, alive: bool) -> bool {
+        match self.region {
+            Some(region) => {
+                grid.fill_region(region.x, region.y, region.width, region.height, alive);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Makes the clipboard follow the cursor. Returns false if the clipboard is empty.
+    pub fn start_paste(&mut self) -> bool {
+        self.pasting = self.clipboard.is_some();
+        self.pasting
+    }
+
+    /// Stops pasting without changing the grid.
+    pub fn cancel_paste(&mut self) {
+        self.pasting = false;
+    }
+
+    /// Returns the cell where the top-left corner of the clipboard is pasted,
+    /// the clipboard being centered on the cursor.
+    pub fn get_paste_origin(&self) -> (i64, i64) {
+        let (width, height) = self.clipboard.as_ref().map_or((0, 0), |clipboard| {
+            (clipboard.get_width(), clipboard.get_height())
+        });
+        (
+            self.cursor.0 - width as i64 / 2,
+            self.cursor.1 - height as i64 / 2,
+        )
+    }
+
+    /// Returns the cells the live cells of the clipboard would be pasted on.
+    pub fn get_ghost_cells(&self) -> Vec<(i64, i64)> {
+        match (&self.clipboard, self.pasting) {
+            (Some(clipboard), true) => {
+                let (origin_x, origin_y) = self.get_paste_origin();
+                clipboard
+                    .live_cells()
+                    .map(|(x, y)| (origin_x + x as i64, origin_y + y as i64))
+                    .collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Pastes the clipboard at the cursor, replacing the cells under it, and
+    /// selects the pasted cells. Returns false if nothing was being pasted.
+    pub fn paste(&mut self, grid: &mut Grid) -> bool {
+        let Some(clipboard) = self.clipboard.as_ref().filter(|_| self.pasting) else {
+            return false;
+        };
+        let (origin_x, origin_y) = self.get_paste_origin();
+        grid.paste(clipboard, origin_x, origin_y);
+        // select the part of the pasted cells inside the grid
+        let (x, y) = (origin_x.max(0), origin_y.max(0));
+        let right = origin_x + clipboard.get_width() as i64;
+        let bottom = origin_y + clipboard.get_height() as i64;
+        self.region = (right > x && bottom > y)
+            .then(|| {
+                Region::new(
+                    x as usize,
+                    y as usize,
+                    (right - x) as usize,
+                    (bottom - y) as usize,
+                )
+            })
+            .and_then(|region| region.clip(grid));
+        self.pasting = false;
+        true
+    }
+
+    /// Applies `transform` to the clipboard while pasting, otherwise to the
+    /// selected cells, which keep their top-left corner. Returns false if
+    /// there was nothing to transform.
+    pub fn transform(&mut self, grid: &mut Grid, transform: impl Fn(&Grid) -> Grid) -> bool {
+        if self.pasting {
+            if let Some(clipboard) = &self.clipboard {
+                self.clipboard = Some(transform(clipboard));
+                return true;
+            }
+        }
+        let Some(region) = self.region else {
+            return false;
+        };
+        let cells = transform(&grid.crop(region.x, region.y, region.width, region.height));
+        grid.fill_region(region.x, region.y, region.width, region.height, false);
+        grid.paste(&cells, region.x as i64, region.y as i64);
+        self.region =
+            Region::new(region.x, region.y, cells.get_width(), cells.get_height()).clip(grid);
+        true
+    }
+}
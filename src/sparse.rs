@@ -0,0 +1,148 @@
+use crate::engine::Universe;
+use crate::grid::Grid;
+use crate::rule::Rule;
+use std::collections::{HashMap, HashSet};
+
+/// Unbounded universe storing only the coordinates of its live cells.
+///
+/// Each generation only visits the live cells and their neighbors, so the cost
+/// depends on the population and not on the area covered by the pattern.
+/// Rules with `B0` are not supported: dead cells with no live neighbor stay
+/// dead, and [`Engine::check_rule`](crate::engine::Engine::check_rule) rejects
+/// them. Coordinates wrap around at the ends of the `i64` range.
+#[derive(Debug, Clone, Default)]
+pub struct SparseLife {
+    rule: Rule,
+    cells: HashSet<(i64, i64)>,
+    generation: u64,
+}
+
+impl SparseLife {
+    /// Creates an empty universe using `rule`.
+    pub fn new(rule: Rule) -> Self {
+        Self {
+            rule,
+            cells: HashSet::new(),
+            generation: 0,
+        }
+    }
+
+    /// Returns the coordinates of every live cell, sorted by row then column.
+    pub fn live_cells(&self) -> Vec<(i64, i64)> {
+        let mut cells: Vec<(i64, i64)> = self.cells.iter().copied().collect();
+        cells.sort_by_key(|&(x, y)| (y, x));
+        cells
+    }
+
+    /// Returns `(min_x, min_y, max_x, max_y)` of the live cells, or `None` if the universe is empty.
+    pub fn bounding_box(&self) -> Option<(i64, i64, i64, i64)> {
+        self.cells.iter().fold(None, |bounds, &(x, y)| {
+            Some(match bounds {
+                None => (x, y, x, y),
+                Some((min_x, min_y, max_x, max_y)) => {
+                    (min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y))
+                }
+            })
+        })
+    }
+
+    /// Advances the universe by one generation.
+    pub fn next_generation(&mut self) {
+        let mut counts: HashMap<(i64, i64), u8> = HashMap::with_capacity(self.cells.len() * 8);
+        for &(x, y) in &self.cells {
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    if (dx, dy) != (0, 0) {
+                        *counts
+                            .entry((x.wrapping_add(dx), y.wrapping_add(dy)))
+                            .or_insert(0) += 1;
+                    }
+                }
+            }
+        }
+        let mut next: HashSet<(i64, i64)> = counts
+            .into_iter()
+            .filter(|(cell, count)| self.rule.next_state(self.cells.contains(cell), *count))
+            .map(|(cell, _)| cell)
+            .collect();
+        // isolated live cells have no entry in `counts`
+        if self.rule.survives(0) {
+            next.extend(self.cells.iter().filter(|&&(x, y)| {
+                (-1..=1).all(|dy| {
+                    (-1..=1).all(|dx| {
+                        (dx, dy) == (0, 0)
+                            || !self
+                                .cells
+                                .contains(&(x.wrapping_add(dx), y.wrapping_add(dy)))
+                    })
+                })
+            }));
+        }
+        self.cells = next;
+        self.generation = self.generation.saturating_add(1);
+    }
+}
+
+impl Universe for SparseLife {
+    fn get_rule(&self) -> Rule {
+        self.rule
+    }
+
+    fn set_rule(&mut self, rule: Rule) {
+        self.rule = rule;
+    }
+
+    fn get(&self, x: i64, y: i64) -> bool {
+        self.cells.contains(&(x, y))
+    }
+
+    fn set(&mut self, x: i64, y: i64, alive: bool) {
+        if alive {
+            self.cells.insert((x, y));
+        } else {
+            self.cells.remove(&(x, y));
+        }
+    }
+
+    fn clear(&mut self) {
+        self.cells.clear();
+        self.generation = 0;
+    }
+
+    fn population(&self) -> u64 {
+        self.cells.len() as u64
+    }
+
+    fn get_generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn set_generation(&mut self, generation: u64) {
+        self.generation = generation;
+    }
+
+    /// Advances the universe by 2^`step_log2` generations, one at a time.
+    fn step(&mut self, step_log2: u8) {
+        for _ in 0..1u64 << step_log2.min(63) {
+            self.next_generation();
+        }
+    }
+
+    fn live_cells(&self) -> Vec<(i64, i64)> {
+        SparseLife::live_cells(self)
+    }
+
+    fn bounding_box(&self) -> Option<(i64, i64, i64, i64)> {
+        SparseLife::bounding_box(self)
+    }
+
+    fn write_to_grid(&self, grid: &mut Grid, origin_x: i64, origin_y: i64) {
+        grid.clear();
+        for &(x, y) in &self.cells {
+            let (x, y) = (x.wrapping_sub(origin_x), y.wrapping_sub(origin_y));
+            if grid.contains(x, y) {
+                grid.set(x as usize, y as usize, true);
+            }
+        }
+    }
+}
@@ -0,0 +1,375 @@
+use crate::engine::Universe;
+use crate::grid::Grid;
+use std::collections::VecDeque;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Number of generations whose statistics are kept when no other one is given.
+pub const DEFAULT_STATS_LENGTH: usize = 100_000;
+
+/// Largest population whose births and deaths the window counts. Larger
+/// universes are not recorded, as listing their cells every step would cost
+/// more than stepping them.
+pub const MAX_STATS_POPULATION: u64 = 1 << 20;
+
+/// Number of generations shown in the population graph when no other one is given.
+pub const DEFAULT_GRAPH_LENGTH: usize = 500;
+
+/// Format the statistics of the generations are written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum StatsFormat {
+    /// One line per generation, after a header line.
+    #[default]
+    Csv,
+    /// A JSON array with one object per generation.
+    Json,
+}
+
+impl StatsFormat {
+    /// All statistics formats.
+    pub const ALL: [StatsFormat; 2] = [StatsFormat::Csv, StatsFormat::Json];
+
+    /// Returns the format matching the extension of `path`, if any.
+    pub fn from_path(path: &str) -> Option<Self> {
+        let extension = Path::new(path).extension()?.to_str()?;
+        match extension.to_ascii_lowercase().as_str() {
+            "csv" => Some(StatsFormat::Csv),
+            "json" => Some(StatsFormat::Json),
+            _ => None,
+        }
+    }
+
+    /// Returns the usual file extension of the format.
+    pub fn get_extension(&self) -> &'static str {
+        match self {
+            StatsFormat::Csv => "csv",
+            StatsFormat::Json => "json",
+        }
+    }
+}
+
+impl fmt::Display for StatsFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            StatsFormat::Csv => "csv",
+            StatsFormat::Json => "json",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for StatsFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "csv" => Ok(StatsFormat::Csv),
+            "json" => Ok(StatsFormat::Json),
+            other => Err(format!("Unknown statistics format '{}'", other)),
+        }
+    }
+}
+
+/// Returns the number of cells born and of cells that died between
+/// `previous` and `grid`, two grids of the same size.
+pub fn count_changes(previous: &Grid, grid: &Grid) -> (u64, u64) {
+    let (mut births, mut deaths) = (0, 0);
+    for y in 0..grid.get_height().min(previous.get_height()) {
+        for (&before, &after) in previous.get_row(y).iter().zip(grid.get_row(y)) {
+            births += (after & !before).count_ones() as u64;
+            deaths += (before & !after).count_ones() as u64;
+        }
+    }
+    (births, deaths)
+}
+
+/// Returns the number of cells born and of cells that died between the live
+/// cells `previous` and `cells`, both sorted by row then column.
+pub fn count_cell_changes(previous: &[(i64, i64)], cells: &[(i64, i64)]) -> (u64, u64) {
+    let key = |&(x, y): &(i64, i64)| (y, x);
+    let (mut births, mut deaths) = (0, 0);
+    let (mut before, mut after) = (previous.iter().peekable(), cells.iter().peekable());
+    loop {
+        match (before.peek(), after.peek()) {
+            (Some(old), Some(new)) if key(old) == key(new) => {
+                before.next();
+                after.next();
+            }
+            (Some(old), Some(new)) if key(old) < key(new) => {
+                deaths += 1;
+                before.next();
+            }
+            (_, Some(_)) => {
+                births += 1;
+                after.next();
+            }
+            (Some(_), None) => {
+                deaths += 1;
+                before.next();
+            }
+            (None, None) => break,
+        }
+    }
+    (births, deaths)
+}
+
+/// Statistics of one generation.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct GenerationStats {
+    generation: u64,
+    population: u64,
+    births: u64,
+    deaths: u64,
+    bounding_box: Option<(i64, i64, i64, i64)>,
+    density: f64,
+    step_time: Duration,
+    span: u64,
+}
+
+impl GenerationStats {
+    /// Creates the statistics of `generation`, whose `population` live cells
+    /// cover `area` cells, computed in `step_time`.
+    pub fn new(
+        generation: u64,
+        population: u64,
+        (births, deaths): (u64, u64),
+        bounding_box: Option<(i64, i64, i64, i64)>,
+        area: u64,
+        step_time: Duration,
+    ) -> Self {
+        Self {
+            generation,
+            population,
+            births,
+            deaths,
+            bounding_box,
+            density: if area > 0 {
+                population as f64 / area as f64
+            } else {
+                0.0
+            },
+            step_time,
+            span: 1,
+        }
+    }
+
+    /// Creates the statistics of `grid` at `generation`, computed from
+    /// `previous` in `step_time`. The density is the one of the whole grid.
+    pub fn of_grid(generation: u64, previous: &Grid, grid: &Grid, step_time: Duration) -> Self {
+        let bounding_box = grid.bounding_box().map(|(min_x, min_y, max_x, max_y)| {
+            (min_x as i64, min_y as i64, max_x as i64, max_y as i64)
+        });
+        Self::new(
+            generation,
+            grid.population() as u64,
+            count_changes(previous, grid),
+            bounding_box,
+            (grid.get_width() * grid.get_height()) as u64,
+            step_time,
+        )
+    }
+
+    /// Creates the statistics of `universe` at its generation, computed in
+    /// `step_time` from the generation `span` generations before, whose live
+    /// cells were `previous`. The births and deaths are the cells that changed
+    /// over the whole span, and the density is the one of the bounding box of
+    /// the live cells.
+    pub fn of_universe(
+        universe: &dyn Universe,
+        previous: &[(i64, i64)],
+        span: u64,
+        step_time: Duration,
+    ) -> Self {
+        let bounding_box = universe.bounding_box();
+        let area = bounding_box.map_or(0, |(min_x, min_y, max_x, max_y)| {
+            ((max_x - min_x + 1) as u64).saturating_mul((max_y - min_y + 1) as u64)
+        });
+        Self {
+            span,
+            ..Self::new(
+                universe.get_generation(),
+                universe.population(),
+                count_cell_changes(previous, &universe.live_cells()),
+                bounding_box,
+                area,
+                step_time,
+            )
+        }
+    }
+
+    /// Returns the generation the statistics are about.
+    pub fn get_generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Returns the number of live cells.
+    pub fn get_population(&self) -> u64 {
+        self.population
+    }
+
+    /// Returns the number of cells born since the previous generation.
+    pub fn get_births(&self) -> u64 {
+        self.births
+    }
+
+    /// Returns the number of cells that died since the previous generation.
+    pub fn get_deaths(&self) -> u64 {
+        self.deaths
+    }
+
+    /// Returns `(min_x, min_y, max_x, max_y)` of the live cells, or `None` if there are none.
+    pub fn get_bounding_box(&self) -> Option<(i64, i64, i64, i64)> {
+        self.bounding_box
+    }
+
+    /// Returns the part of the cells alive, from 0 to 1.
+    pub fn get_density(&self) -> f64 {
+        self.density
+    }
+
+    /// Returns the time spent computing the generation.
+    pub fn get_step_time(&self) -> Duration {
+        self.step_time
+    }
+
+    /// Returns the number of generations the births and deaths were counted
+    /// over: 1, unless an unbounded engine stepped several at once.
+    pub fn get_span(&self) -> u64 {
+        self.span
+    }
+}
+
+/// Statistics of the last generations, oldest first, kept up to a length.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct StatsRecorder {
+    records: VecDeque<GenerationStats>,
+    length: usize,
+}
+
+impl StatsRecorder {
+    /// Creates a recorder keeping the statistics of the last `length` generations.
+    pub fn new(length: usize) -> Self {
+        Self {
+            records: VecDeque::new(),
+            length,
+        }
+    }
+
+    /// Returns the number of generations whose statistics are kept.
+    pub fn get_length(&self) -> usize {
+        self.length
+    }
+
+    /// Returns the statistics kept, oldest first.
+    pub fn get_records(&self) -> impl DoubleEndedIterator<Item = &GenerationStats> + '_ {
+        self.records.iter()
+    }
+
+    /// Returns the statistics of the last generation recorded.
+    pub fn get_last(&self) -> Option<&GenerationStats> {
+        self.records.back()
+    }
+
+    /// Returns the number of generations recorded.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Returns true if no generation is recorded.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Forgets every generation recorded.
+    pub fn clear(&mut self) {
+        self.records.clear();
+    }
+
+    /// Records the statistics of a generation. The ones of the same or of
+    /// later generations, left by an undo or a reset, are dropped first.
+    pub fn record(&mut self, stats: GenerationStats) {
+        while self
+            .records
+            .back()
+            .is_some_and(|last| last.generation >= stats.generation)
+        {
+            self.records.pop_back();
+        }
+        self.records.push_back(stats);
+        while self.records.len() > self.length {
+            self.records.pop_front();
+        }
+    }
+
+    /// Writes the statistics kept in `format`.
+    pub fn write(&self, format: StatsFormat) -> String {
+        match format {
+            StatsFormat::Csv => self.to_csv(),
+            StatsFormat::Json => self.to_json(),
+        }
+    }
+
+    /// Writes the statistics kept as CSV, the bounding box of empty
+    /// generations being left blank, the step time in microseconds and the
+    /// span the births and deaths were counted over.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "generation,population,births,deaths,min_x,min_y,max_x,max_y,density,step_us,span\n",
+        );
+        for stats in &self.records {
+            let bounding_box = match stats.bounding_box {
+                Some((min_x, min_y, max_x, max_y)) => {
+                    format!("{},{},{},{}", min_x, min_y, max_x, max_y)
+                }
+                None => ",,,".to_string(),
+            };
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{},{}\n",
+                stats.generation,
+                stats.population,
+                stats.births,
+                stats.deaths,
+                bounding_box,
+                stats.density,
+                stats.step_time.as_micros(),
+                stats.span
+            ));
+        }
+        csv
+    }
+
+    /// Writes the statistics kept as a JSON array, the bounding box being
+    /// `[min_x, min_y, max_x, max_y]` or `null`, the step time in microseconds
+    /// and the span the births and deaths were counted over.
+    pub fn to_json(&self) -> String {
+        let records: Vec<String> = self
+            .records
+            .iter()
+            .map(|stats| {
+                let bounding_box = match stats.bounding_box {
+                    Some((min_x, min_y, max_x, max_y)) => {
+                        format!("[{}, {}, {}, {}]", min_x, min_y, max_x, max_y)
+                    }
+                    None => "null".to_string(),
+                };
+                format!(
+                    "  {{\"generation\": {}, \"population\": {}, \"births\": {}, \"deaths\": {}, \"bounding_box\": {}, \"density\": {}, \"step_us\": {}, \"span\": {}}}",
+                    stats.generation,
+                    stats.population,
+                    stats.births,
+                    stats.deaths,
+                    bounding_box,
+                    stats.density,
+                    stats.step_time.as_micros(),
+                    stats.span
+                )
+            })
+            .collect();
+        if records.is_empty() {
+            return "[]\n".to_string();
+        }
+        format!("[\n{}\n]\n", records.join(",\n"))
+    }
+}
@@ -0,0 +1,98 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// Describes how the edges of the grid are connected to each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Topology {
+    /// Everything outside the grid is dead.
+    #[default]
+    Dead,
+    /// Opposite edges are joined, so the grid wraps in both directions.
+    Torus,
+    /// Left and right edges are joined; top and bottom edges are joined with a twist.
+    KleinBottle,
+    /// Both pairs of opposite edges are joined with a twist (real projective plane).
+    CrossSurface,
+    /// The grid is reflected at its edges, so border cells see a mirror image of themselves.
+    Mirror,
+}
+
+impl Topology {
+    /// All topologies, in the order they are cycled through.
+    pub const ALL: [Topology; 5] = [
+        Topology::Dead,
+        Topology::Torus,
+        Topology::KleinBottle,
+        Topology::CrossSurface,
+        Topology::Mirror,
+    ];
+
+    /// Maps the coordinates `(x, y)` of a possibly out-of-grid cell onto the
+    /// grid of size `width` x `height`. Returns `None` when the cell is dead.
+    pub fn resolve(&self, x: i64, y: i64, width: i64, height: i64) -> Option<(i64, i64)> {
+        if width <= 0 || height <= 0 {
+            return None;
+        }
+        let inside = (0..width).contains(&x) && (0..height).contains(&y);
+        let odd_x_wrap = x.div_euclid(width) % 2 != 0;
+        let odd_y_wrap = y.div_euclid(height) % 2 != 0;
+        match self {
+            Topology::Dead => inside.then_some((x, y)),
+            Topology::Torus => Some((x.rem_euclid(width), y.rem_euclid(height))),
+            Topology::KleinBottle => {
+                let x = if odd_y_wrap { width - 1 - x } else { x };
+                Some((x.rem_euclid(width), y.rem_euclid(height)))
+            }
+            Topology::CrossSurface => {
+                let new_x = if odd_y_wrap { width - 1 - x } else { x };
+                let new_y = if odd_x_wrap { height - 1 - y } else { y };
+                Some((new_x.rem_euclid(width), new_y.rem_euclid(height)))
+            }
+            Topology::Mirror => {
+                let reflect = |value: i64, length: i64| {
+                    let m = value.rem_euclid(2 * length);
+                    if m >= length {
+                        2 * length - 1 - m
+                    } else {
+                        m
+                    }
+                };
+                Some((reflect(x, width), reflect(y, height)))
+            }
+        }
+    }
+
+    /// Returns the topology that follows this one when cycling through them.
+    pub fn next(&self) -> Self {
+        let index = Self::ALL.iter().position(|t| t == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
+impl fmt::Display for Topology {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Topology::Dead => "dead",
+            Topology::Torus => "torus",
+            Topology::KleinBottle => "klein",
+            Topology::CrossSurface => "cross-surface",
+            Topology::Mirror => "mirror",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Topology {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "dead" | "plane" => Ok(Topology::Dead),
+            "torus" => Ok(Topology::Torus),
+            "klein" | "klein-bottle" => Ok(Topology::KleinBottle),
+            "cross-surface" | "cross" => Ok(Topology::CrossSurface),
+            "mirror" => Ok(Topology::Mirror),
+            other => Err(format!("Unknown topology '{}'", other)),
+        }
+    }
+}
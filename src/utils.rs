@@ -1,302 +1,219 @@
-use crate::game::{Game, GameStatus};
-use crate::sdl_lib::{
-    draw_game, generate_texture, get_target_for_texture, handle_event, init_canvas, init_font,
-    init_ttf_context, TextureWithRect, BLACK,
-};
-use sdl2::rect::{FPoint, FRect};
-use sdl2::render::Texture;
-
-/// Generates a list of grid points for drawing the grid based on size and window dimensions.
+use crate::camera::{Camera, MIN_GRID_LINE_ZOOM};
+#[cfg(feature = "sdl")]
+use crate::cli::Options;
+#[cfg(feature = "sdl")]
+use crate::frontend::run_frontend_with_universe;
+use crate::frontend::{Color, Point, Rect};
+#[cfg(feature = "sdl")]
+use crate::game::Game;
+use crate::grid::{BandPool, Grid};
+use crate::rule::Rule;
+#[cfg(feature = "sdl")]
+use crate::sdl_lib::{init_canvas, init_font, init_ttf_context, SdlInput, SdlRenderer, BLACK};
+use crate::selection::Region;
+use crate::topology::Topology;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Generates the polyline drawing the lines of a grid of `grid_width` by
+/// `grid_height` cells seen through `camera`, in a view of `view_width` by
+/// `view_height` pixels.
+///
+/// Only the lines in view are generated, and none when the cells are smaller
+/// than `MIN_GRID_LINE_ZOOM` pixels. The vertical lines are drawn first, in
+/// alternate directions, then the horizontal ones, the path between them
+/// following the edges of the visible part of the grid.
 pub fn get_grid_point_list(
-    size_grid: u32,
-    unit_grid: f32,
-    window_height: u32,
-    window_width: u32,
-) -> Vec<FPoint> {
+    camera: &Camera,
+    grid_width: usize,
+    grid_height: usize,
+    view_width: u32,
+    view_height: u32,
+) -> Vec<Point> {
+    let zoom = camera.get_zoom();
+    if zoom < MIN_GRID_LINE_ZOOM {
+        return Vec::new();
+    }
+    let top_left = camera.cell_to_screen(0.0, 0.0);
+    let bottom_right = camera.cell_to_screen(grid_width as f32, grid_height as f32);
+    let left = top_left.x.max(0.0);
+    let top = top_left.y.max(0.0);
+    let right = bottom_right.x.min(view_width as f32);
+    let bottom = bottom_right.y.min(view_height as f32);
+    if left >= right || top >= bottom {
+        return Vec::new();
+    }
+    let visible = |start: f32, length: f32, cells: usize| {
+        let first = start.ceil().max(0.0) as usize;
+        let last = ((start + length / zoom).floor().max(0.0) as usize).min(cells);
+        first..=last
+    };
+
     let mut grid_point_list = Vec::new();
-    for i in 0..size_grid {
-        if (i % 2) == 0 {
-            grid_point_list.push(FPoint::new(unit_grid * i as f32, 0.0));
-            grid_point_list.push(FPoint::new(unit_grid * i as f32, window_height as f32));
+    for (n, column) in visible(camera.get_x(), view_width as f32, grid_width).enumerate() {
+        let x = camera.cell_to_screen(column as f32, 0.0).x;
+        if n % 2 == 0 {
+            grid_point_list.push(Point::new(x, top));
+            grid_point_list.push(Point::new(x, bottom));
         } else {
-            grid_point_list.push(FPoint::new(unit_grid * i as f32, window_height as f32));
-            grid_point_list.push(FPoint::new(unit_grid * i as f32, 0.0));
+            grid_point_list.push(Point::new(x, bottom));
+            grid_point_list.push(Point::new(x, top));
         }
     }
-    grid_point_list.push(FPoint::new(window_width as f32, 0.0));
-    grid_point_list.push(FPoint::new(0.0, 0.0));
-    for i in 0..size_grid {
-        if (i % 2) == 0 {
-            grid_point_list.push(FPoint::new(0.0, unit_grid * i as f32));
-            grid_point_list.push(FPoint::new(window_width as f32, unit_grid * i as f32));
+    let at_top = grid_point_list.last().is_none_or(|point| point.y == top);
+    grid_point_list.push(Point::new(right, if at_top { top } else { bottom }));
+    let rows = visible(camera.get_y(), view_height as f32, grid_height);
+    let rows: Vec<usize> = if at_top {
+        rows.collect()
+    } else {
+        rows.rev().collect()
+    };
+    for (n, row) in rows.into_iter().enumerate() {
+        let y = camera.cell_to_screen(0.0, row as f32).y;
+        if n % 2 == 0 {
+            grid_point_list.push(Point::new(right, y));
+            grid_point_list.push(Point::new(left, y));
         } else {
-            grid_point_list.push(FPoint::new(window_width as f32, unit_grid * i as f32));
-            grid_point_list.push(FPoint::new(0.0, unit_grid * i as f32));
+            grid_point_list.push(Point::new(left, y));
+            grid_point_list.push(Point::new(right, y));
         }
     }
     grid_point_list
 }
 
-fn get_number_black_around_cell(list: &[Vec<bool>], x: i32, y: i32) -> i32 {
-    let mut count = 0;
-    let directions = [
-        (-1, -1),
-        (-1, 0),
-        (-1, 1),
-        (0, -1),
-        (0, 1),
-        (1, -1),
-        (1, 0),
-        (1, 1),
-    ];
-
-    for (dx, dy) in directions.iter() {
-        let nx = x + dx;
-        let ny = y + dy;
-        if nx >= 0
-            && nx < list.len() as i32
-            && ny >= 0
-            && ny < list[0].len() as i32
-            && list[nx as usize][ny as usize]
-        {
-            count += 1;
-        }
-    }
-
-    count
+/// Computes the next state of the grid using the given birth/survival rule,
+/// with neighbors beyond the edges resolved through `topology`.
+pub fn game_of_life(grid: &Grid, rule: &Rule, topology: Topology) -> Grid {
+    let mut next = Grid::new(grid.get_width(), grid.get_height());
+    grid.next_generation_into(&mut next, rule, topology, &mut BandPool::new(1));
+    next
 }
 
-/// Computes the next state of the grid using Conway's Game of Life rules.
-pub fn game_of_life(list: Vec<Vec<bool>>) -> Vec<Vec<bool>> {
-    let column_count = match list.first() {
-        Some(row) => row.len(),
-        None => return list,
-    };
+/// Computes the next state of `grid` into `next`, splitting the rows into
+/// bands computed in parallel by the threads of `pool`.
+/// The result is identical to the one of `game_of_life`.
+pub fn game_of_life_threaded(
+    grid: &Grid,
+    next: &mut Grid,
+    rule: &Rule,
+    topology: Topology,
+    pool: &mut BandPool,
+) {
+    grid.next_generation_into(next, rule, topology, pool);
+}
 
-    if column_count == 0 {
-        return list;
-    }
+/// Replaces the cells of `grid` with a random soup in which each cell is alive
+/// with probability `density`. The same seed always gives the same soup.
+pub fn fill_random(grid: &mut Grid, density: f64, seed: u64) {
+    let region = Region::new(0, 0, grid.get_width(), grid.get_height());
+    fill_random_region(grid, region, density, seed);
+}
 
-    let mut new_list = vec![vec![false; column_count]; list.len()];
+/// Formats a density from 0 to 1 as a percentage, to a tenth of a percent.
+pub fn format_density(density: f64) -> String {
+    format!("{}%", (density * 1000.0).round() / 10.0)
+}
 
-    for (i, row) in list.iter().enumerate() {
-        for (j, &cell) in row.iter().enumerate() {
-            let count_black_neighbor = get_number_black_around_cell(&list, i as i32, j as i32);
-            new_list[i][j] = if cell {
-                (2..=3).contains(&count_black_neighbor)
-            } else {
-                count_black_neighbor == 3
-            };
+/// Replaces the cells of `region` of `grid` with a random soup in which each
+/// cell is alive with probability `density`. The same seed always gives the
+/// same soup in a region of the same size.
+pub fn fill_random_region(grid: &mut Grid, region: Region, density: f64, seed: u64) {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let density = density.clamp(0.0, 1.0);
+    for y in region.y..region.y + region.height {
+        for x in region.x..region.x + region.width {
+            grid.set(x, y, rng.gen_bool(density));
         }
     }
+}
 
-    new_list
+/// Returns the rectangles of the live cells of `grid` seen through `camera`
+/// that are in a view of `view_width` by `view_height` pixels.
+pub fn get_rect_list(grid: &Grid, camera: &Camera, view_width: u32, view_height: u32) -> Vec<Rect> {
+    let zoom = camera.get_zoom();
+    let (min_x, min_y) = camera.screen_to_cell(0.0, 0.0);
+    let (max_x, max_y) = camera.screen_to_cell(view_width as f32, view_height as f32);
+    grid.live_cells()
+        .filter(|&(x, y)| {
+            (min_x..=max_x).contains(&(x as i64)) && (min_y..=max_y).contains(&(y as i64))
+        })
+        .map(|(x, y)| {
+            let corner = camera.cell_to_screen(x as f32, y as f32);
+            Rect::new(corner.x, corner.y, zoom, zoom)
+        })
+        .collect()
 }
 
-/// Returns a list of rectangles representing live cells in the grid.
-pub fn get_rect_list(list: &[Vec<bool>], unit_grid: f32) -> Vec<FRect> {
-    let mut list_rect: Vec<FRect> = Vec::new();
-    for (i, row) in list.iter().enumerate() {
-        for (j, &cell) in row.iter().enumerate() {
-            if cell {
-                list_rect.push(FRect::new(
-                    j as f32 * unit_grid,
-                    i as f32 * unit_grid,
-                    unit_grid,
-                    unit_grid,
-                ));
-            }
-        }
-    }
-    list_rect
+/// Returns the rectangles of `cells` seen through `camera` that are in a view
+/// of `view_width` by `view_height` pixels, and the color of each. The cells
+/// are sorted by color, so that each color is drawn in one batch.
+pub fn get_colored_rect_list(
+    cells: impl Iterator<Item = (usize, usize, Color)>,
+    camera: &Camera,
+    view_width: u32,
+    view_height: u32,
+) -> (Vec<Rect>, Vec<Color>) {
+    let zoom = camera.get_zoom();
+    let (min_x, min_y) = camera.screen_to_cell(0.0, 0.0);
+    let (max_x, max_y) = camera.screen_to_cell(view_width as f32, view_height as f32);
+    let mut colored: Vec<(Color, Rect)> = cells
+        .filter(|&(x, y, _)| {
+            (min_x..=max_x).contains(&(x as i64)) && (min_y..=max_y).contains(&(y as i64))
+        })
+        .map(|(x, y, color)| {
+            let corner = camera.cell_to_screen(x as f32, y as f32);
+            (color, Rect::new(corner.x, corner.y, zoom, zoom))
+        })
+        .collect();
+    colored.sort_by_key(|&(color, _)| color);
+    colored
+        .into_iter()
+        .map(|(color, rect)| (rect, color))
+        .unzip()
 }
 
-/// Runs the main game loop, handling events and rendering.
+/// Runs the main game loop with the default settings, handling events and rendering.
+#[cfg(feature = "sdl")]
 pub fn run_game() -> Result<(), String> {
+    run_game_with(&Options::default())
+}
+
+/// Runs the main game loop in an SDL2 window with the settings given on the command line.
+#[cfg(feature = "sdl")]
+pub fn run_game_with(options: &Options) -> Result<(), String> {
     let mut game_info: Game = Game::new();
+    options.apply(&mut game_info);
 
-    let (sdl_context, mut canvas) = init_canvas(
+    let (sdl_context, canvas) = init_canvas(
         &game_info.get_name(),
-        game_info.get_window_min_length(),
-        game_info.get_window_min_length(),
+        game_info.get_window_width(),
+        game_info.get_window_height(),
         BLACK,
     )?;
-
-    let mut event_pump = sdl_context.event_pump()?;
-
-    let grid_point_list = get_grid_point_list(
-        game_info.get_size_grid(),
-        game_info.get_unit_grid(),
-        game_info.get_window_min_length(),
-        game_info.get_window_min_length(),
-    );
-    // Convert Vec<Point> into a borrowed slice
-    let points_slice: &[FPoint] = grid_point_list.as_slice();
-    // The following demonstrates a type that implements Into<&[Point]>
-    let mut tmp_vec = points_slice.to_vec();
-    let mut borrowed_slice: &[FPoint] = &tmp_vec[..];
+    let mut input = SdlInput::new(sdl_context.event_pump()?);
 
     // Initialize TTF context
     let ttf_context = init_ttf_context()?;
 
     // Load font
-    let font = init_font("./assets/Roboto-Medium.ttf", 40, &ttf_context)?;
-
-    // Render the text to a surface, then create a texture
+    let font = init_font(&game_info.get_font_path(), 40, &ttf_context)?;
     let texture_creator = canvas.texture_creator();
-    let mut texture_iteration = generate_texture(&font, "iteration: 0", BLACK, &texture_creator)?;
-    let mut texture_iteration_per_second: Texture<'_> =
-        generate_texture(&font, "iteration / s: 0", BLACK, &texture_creator)?;
-
-    // Query the texture for width and height
-    let mut target_iteration = get_target_for_texture(&texture_iteration, 0, 0);
-    let mut target_population: FRect;
-    let mut target_iteration_per_second: FRect =
-        get_target_for_texture(&texture_iteration_per_second, 0, 200);
-
-    // Draw the texture to the canvas
-    // let mut list_color_save: Vec<Vec<Vec<bool>>> = Vec::new();
-    let mut list_color: Vec<Vec<bool>> =
-        vec![vec![false; game_info.get_size_grid() as usize]; game_info.get_size_grid() as usize];
-
-    canvas.set_draw_color(BLACK);
-
-    let mut start_text_point = [0, 0];
-
-    while game_info.get_game_state() != GameStatus::Exit {
-        handle_event(&mut event_pump, &mut list_color, &mut game_info);
-
-        // if the window is resized, update the grid
-        if canvas.window().size().0 != game_info.get_window_width()
-            || canvas.window().size().1 != game_info.get_window_height()
-        {
-            game_info.set_window_width(canvas.window().size().0);
-            game_info.set_window_height(canvas.window().size().1);
+    let mut renderer = SdlRenderer::new(canvas, &texture_creator, font);
 
-            let tmp_grid_point_list = get_grid_point_list(
-                game_info.get_size_grid(),
-                game_info.get_unit_grid(),
-                game_info.get_window_min_length(),
-                game_info.get_window_min_length(),
-            );
-            // Convert Vec<Point> into a borrowed slice
-            let tmp_slice: &[FPoint] = tmp_grid_point_list.as_slice();
-            // The following demonstrates a type that implements Into<&[Point]>
-            tmp_vec = tmp_slice.to_vec();
-            borrowed_slice = &tmp_vec[..];
-            start_text_point = [0, 0];
-
-            if game_info.get_window_height() as f32
-                - game_info.get_size_grid() as f32 * game_info.get_unit_grid()
-                > 100.0
-            {
-                start_text_point[1] =
-                    (game_info.get_size_grid() as f32 * game_info.get_unit_grid()) as u32;
-            } else if game_info.get_window_width() as f32
-                - game_info.get_size_grid() as f32 * game_info.get_unit_grid()
-                > 100.0
-            {
-                start_text_point[0] =
-                    (game_info.get_size_grid() as f32 * game_info.get_unit_grid()) as u32;
-            }
-
-            texture_iteration = generate_texture(
-                &font,
-                &("iteration: ".to_string() + &game_info.get_iteration().to_string()),
-                BLACK,
-                &texture_creator,
-            )?;
-            target_iteration = get_target_for_texture(
-                &texture_iteration,
-                start_text_point[0] as i32,
-                start_text_point[1] as i32,
-            );
-            texture_iteration_per_second = generate_texture(
-                &font,
-                &("iteration / s: ".to_string()
-                    + &game_info.get_iteration_per_second().to_string()),
-                BLACK,
-                &texture_creator,
-            )?;
-            target_iteration_per_second = get_target_for_texture(
-                &texture_iteration_per_second,
-                start_text_point[0] as i32,
-                start_text_point[1] as i32 + 200,
-            );
-        }
-
-        if game_info.get_game_state() != GameStatus::Pause
-        // && game_info.get_iteration_per_second()
-        //     < game_info.get_max_iteration_per_second() as f64
-        {
-            //let ticks = timer.ticks() as i32;
-
-            // save the grid
-            // list_color_save.push(list_color.clone());
-            // update the grid
-            list_color = game_of_life(list_color);
-
-            texture_iteration = generate_texture(
-                &font,
-                &("iteration: ".to_string() + &game_info.get_iteration().to_string()),
-                BLACK,
-                &texture_creator,
-            )?;
-            target_iteration = get_target_for_texture(
-                &texture_iteration,
-                start_text_point[0] as i32,
-                start_text_point[1] as i32,
-            );
-            texture_iteration_per_second = generate_texture(
-                &font,
-                &("iteration / s: ".to_string()
-                    + &game_info.get_iteration_per_second().to_string()),
-                BLACK,
-                &texture_creator,
-            )?;
-            target_iteration_per_second = get_target_for_texture(
-                &texture_iteration_per_second,
-                start_text_point[0] as i32,
-                start_text_point[1] as i32 + 200,
-            );
-            game_info.set_iteration(game_info.get_iteration() + 1);
-        }
-
-        // display the grid
-        canvas.clear();
-        if game_info.get_game_state() != GameStatus::Exit {
-            let cell_rects = get_rect_list(&list_color, game_info.get_unit_grid());
-            let texture_population = generate_texture(
-                &font,
-                &("population: ".to_string() + &cell_rects.len().to_string()),
-                BLACK,
-                &texture_creator,
-            )?;
-            target_population = get_target_for_texture(
-                &texture_population,
-                start_text_point[0] as i32,
-                start_text_point[1] as i32 + 100,
-            );
-            draw_game(
-                &mut canvas,
-                borrowed_slice,
-                &cell_rects,
-                TextureWithRect {
-                    texture: &texture_iteration,
-                    target: target_iteration,
-                },
-                TextureWithRect {
-                    texture: &texture_population,
-                    target: target_population,
-                },
-                TextureWithRect {
-                    texture: &texture_iteration_per_second,
-                    target: target_iteration_per_second,
-                },
-            );
-            canvas.present();
-        }
+    let mut list_color = Grid::new(
+        game_info.get_size_grid() as usize,
+        game_info.get_size_grid() as usize,
+    );
+    let universe = options.load_start_universe(&mut game_info)?;
+    if universe.is_none() {
+        options.load_start_grid(&mut list_color, &mut game_info)?;
     }
 
-    Ok(())
+    run_frontend_with_universe(
+        &mut game_info,
+        list_color,
+        universe,
+        &mut renderer,
+        &mut input,
+    )
 }
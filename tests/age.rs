@@ -0,0 +1,139 @@
+#[cfg(test)]
+mod tests {
+
+    use rust_of_life::age::{CellAges, ColorMode, Gradient, DEFAULT_GRADIENT_SPAN};
+    use rust_of_life::frontend::Color;
+    use rust_of_life::grid::{BandPool, Grid};
+    use rust_of_life::rule::Rule;
+    use rust_of_life::topology::Topology;
+
+    fn blinker() -> Grid {
+        let mut grid = Grid::new(5, 5);
+        for x in 1..4 {
+            grid.set(x, 2, true);
+        }
+        grid
+    }
+
+    fn next(grid: &Grid) -> Grid {
+        let mut next = Grid::default();
+        grid.next_generation_into(
+            &mut next,
+            &Rule::conway(),
+            Topology::Dead,
+            &mut BandPool::new(1),
+        );
+        next
+    }
+
+    #[test]
+    fn test_color_mode() {
+        assert_eq!(ColorMode::default(), ColorMode::Trail);
+        assert_eq!(ColorMode::Trail.next(), ColorMode::Flat);
+        assert!(!ColorMode::Flat.shows_age());
+        assert!(ColorMode::Age.shows_age() && !ColorMode::Age.shows_trail());
+        for mode in ColorMode::ALL {
+            assert_eq!(mode.to_string().parse::<ColorMode>(), Ok(mode));
+        }
+        assert!("rainbow".parse::<ColorMode>().is_err());
+    }
+
+    #[test]
+    fn test_color() {
+        let color: Color = "#FF8000".parse().unwrap();
+        assert_eq!(color, Color::new(255, 128, 0));
+        assert_eq!(color.to_string(), "#ff8000");
+        assert_eq!("00ff00".parse(), Ok(Color::new(0, 255, 0)));
+        assert!("#ff80".parse::<Color>().is_err());
+        assert!("#+f8000".parse::<Color>().is_err());
+        assert_eq!(
+            Color::new(0, 0, 0).lerp(Color::new(255, 100, 10), 0.5),
+            Color::new(128, 50, 5)
+        );
+    }
+
+    #[test]
+    fn test_gradient() {
+        let gradient: Gradient = "#ff0000,#0000ff".parse().unwrap();
+        assert_eq!(gradient.get_span(), DEFAULT_GRADIENT_SPAN);
+        assert_eq!(gradient.get_color(1), Color::new(255, 0, 0));
+        assert_eq!(
+            gradient.get_color(DEFAULT_GRADIENT_SPAN),
+            Color::new(0, 0, 255)
+        );
+        assert_eq!(gradient.get_color(u32::MAX), Color::new(0, 0, 255));
+        assert_eq!(gradient.to_string(), "#ff0000,#0000ff");
+
+        let gradient = Gradient::new(
+            vec![
+                Color::new(0, 0, 0),
+                Color::new(200, 0, 0),
+                Color::new(200, 200, 0),
+            ],
+            5,
+        )
+        .unwrap();
+        assert_eq!(gradient.get_color(3), Color::new(200, 0, 0));
+        assert_eq!(gradient.get_color(4), Color::new(200, 100, 0));
+        assert_eq!(
+            Gradient::new(vec![Color::new(1, 2, 3)], 10)
+                .unwrap()
+                .get_color(7),
+            Color::new(1, 2, 3)
+        );
+        assert!(Gradient::new(Vec::new(), 10).is_err());
+        assert!("#ff0000,red".parse::<Gradient>().is_err());
+    }
+
+    #[test]
+    fn test_ages_and_trail() {
+        let mut grid = blinker();
+        let mut ages = CellAges::new(&grid);
+        assert_eq!(ages.get_age(2, 2), 1);
+
+        // the center of the blinker survives, its ends die and leave a trail
+        grid = next(&grid);
+        ages.advance(&grid, 3);
+        assert_eq!(ages.get_age(2, 2), 2);
+        assert_eq!(ages.get_age(2, 1), 1);
+        assert_eq!(ages.get_age(1, 2), 0);
+        assert_eq!(ages.get_fade(1, 2), 3);
+        assert_eq!(ages.live_cells().count(), 3);
+        assert_eq!(ages.fading_cells().count(), 2);
+
+        // the ends are born again, the cells that died fade
+        grid = next(&grid);
+        ages.advance(&grid, 3);
+        assert_eq!(ages.get_age(1, 2), 1);
+        assert_eq!(ages.get_fade(1, 2), 0);
+        assert_eq!(ages.get_fade(2, 1), 3);
+        for _ in 0..4 {
+            grid.clear();
+            ages.advance(&grid, 3);
+        }
+        assert_eq!(ages.live_cells().count(), 0);
+        assert_eq!(ages.fading_cells().count(), 0);
+    }
+
+    #[test]
+    fn test_sync_edits() {
+        let mut grid = blinker();
+        let mut ages = CellAges::new(&grid);
+        grid = next(&grid);
+        ages.advance(&grid, 3);
+
+        // edited cells are newborn, erased cells leave no trail
+        grid.set(0, 0, true);
+        grid.set(2, 2, false);
+        ages.sync(&grid);
+        assert_eq!(ages.get_age(0, 0), 1);
+        assert_eq!((ages.get_age(2, 2), ages.get_fade(2, 2)), (0, 0));
+        assert_eq!(ages.get_fade(1, 2), 3);
+        assert_eq!(ages.get_age(2, 1), 1);
+
+        // a grid of another size starts over
+        ages.advance(&Grid::new(3, 3), 3);
+        assert_eq!(ages.fading_cells().count(), 0);
+        assert_eq!(ages.get_age(9, 9), 0);
+    }
+}
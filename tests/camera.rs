@@ -0,0 +1,48 @@
+#[cfg(test)]
+mod tests {
+
+    use rust_of_life::camera::{Camera, MAX_ZOOM, MIN_ZOOM};
+    use rust_of_life::frontend::Point;
+
+    #[test]
+    fn test_screen_to_cell() {
+        let camera = Camera::new(10.0);
+        assert_eq!(camera.screen_to_cell(0.0, 0.0), (0, 0));
+        assert_eq!(camera.screen_to_cell(25.0, 39.9), (2, 3));
+        assert_eq!(camera.screen_to_cell(-1.0, 5.0), (-1, 0));
+        assert_eq!(camera.cell_to_screen(2.0, 3.0), Point::new(20.0, 30.0));
+    }
+
+    #[test]
+    fn test_pan() {
+        let mut camera = Camera::new(10.0);
+        camera.pan(50.0, -20.0);
+        assert_eq!((camera.get_x(), camera.get_y()), (5.0, -2.0));
+        assert_eq!(camera.screen_to_cell(0.0, 0.0), (5, -2));
+        assert_eq!(camera.cell_to_screen(5.0, -2.0), Point::new(0.0, 0.0));
+    }
+
+    #[test]
+    fn test_zoom_keeps_the_cell_under_the_mouse() {
+        let mut camera = Camera::new(10.0);
+        camera.zoom_at(4.0, 55.0, 35.0);
+        assert_eq!(camera.get_zoom(), 40.0);
+        assert_eq!(camera.screen_to_cell(55.0, 35.0), (5, 3));
+        assert_eq!(camera.cell_to_screen(5.5, 3.5), Point::new(55.0, 35.0));
+
+        camera.zoom_at(1e9, 0.0, 0.0);
+        assert_eq!(camera.get_zoom(), MAX_ZOOM);
+        camera.zoom_at(1e-9, 0.0, 0.0);
+        assert_eq!(camera.get_zoom(), MIN_ZOOM);
+    }
+
+    #[test]
+    fn test_fit() {
+        let mut camera = Camera::new(1.0);
+        camera.fit((10.0, 20.0, 19.0, 24.0), 200, 100);
+        // 10 by 5 cells fit 18 pixels per cell in 90% of 200 by 100 pixels
+        assert_eq!(camera.get_zoom(), 18.0);
+        let center = camera.cell_to_screen(15.0, 22.5);
+        assert!((center.x - 100.0).abs() < 1e-3 && (center.y - 50.0).abs() < 1e-3);
+    }
+}
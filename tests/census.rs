@@ -0,0 +1,139 @@
+#[cfg(test)]
+mod tests {
+
+    use rust_of_life::census::{classify, take_census, wechsler, CensusFormat, PATHOLOGICAL};
+    use rust_of_life::rule::Rule;
+
+    /// Returns the cells of `rows`, where `o` is a live cell, placed at `(x, y)`.
+    fn cells(rows: &[&str], x: i64, y: i64) -> Vec<(i64, i64)> {
+        rows.iter()
+            .enumerate()
+            .flat_map(|(row, line)| {
+                line.chars()
+                    .enumerate()
+                    .filter(|&(_, c)| c == 'o')
+                    .map(move |(column, _)| (x + column as i64, y + row as i64))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_wechsler() {
+        assert_eq!(wechsler(&cells(&["oo", "oo"], 7, -3)), "33");
+        assert_eq!(wechsler(&cells(&["o..o"], 0, 0)), "1w1");
+        assert_eq!(
+            wechsler(&cells(&["o", ".", ".", ".", ".", "o"], 0, 0)),
+            "1z1"
+        );
+        let far = format!("o{}o", ".".repeat(10));
+        assert_eq!(wechsler(&cells(&[&far], 0, 0)), "1y61");
+        assert_eq!(wechsler(&[]), "");
+    }
+
+    #[test]
+    fn test_classify() {
+        let conway = Rule::conway();
+        assert_eq!(classify(&cells(&["oo", "oo"], 0, 0), conway), "xs4_33");
+        assert_eq!(
+            classify(&cells(&[".oo.", "o..o", ".oo."], 0, 0), conway),
+            "xs6_696"
+        );
+        assert_eq!(
+            classify(&cells(&["oo.", "o.o", ".o."], 0, 0), conway),
+            "xs5_253"
+        );
+        assert_eq!(classify(&cells(&["ooo"], 0, 0), conway), "xp2_7");
+        assert_eq!(classify(&cells(&["o", "o", "o"], 0, 0), conway), "xp2_7");
+        assert_eq!(
+            classify(&cells(&[".o.", "..o", "ooo"], 0, 0), conway),
+            "xq4_153"
+        );
+        assert_eq!(
+            classify(&cells(&[".o..o", "o....", "o...o", "oooo."], 0, 0), conway),
+            "xq4_6frc"
+        );
+        // an R-pentomino does not settle on its own, a lone cell dies
+        assert_eq!(
+            classify(&cells(&[".oo", "oo.", ".o."], 0, 0), conway),
+            PATHOLOGICAL
+        );
+        assert_eq!(classify(&[(0, 0)], conway), PATHOLOGICAL);
+    }
+
+    #[test]
+    fn test_census_merges_pathological_parts() {
+        // the four quadrants of a pulsar in this phase do not touch
+        let pulsar = cells(
+            &[
+                "..ooo...ooo..",
+                ".............",
+                "o....o.o....o",
+                "o....o.o....o",
+                "o....o.o....o",
+                "..ooo...ooo..",
+                ".............",
+                "..ooo...ooo..",
+                "o....o.o....o",
+                "o....o.o....o",
+                "o....o.o....o",
+                ".............",
+                "..ooo...ooo..",
+            ],
+            0,
+            0,
+        );
+        let census = take_census(pulsar, Rule::conway());
+        assert_eq!(
+            census.get_objects(),
+            vec![("xp3_co9nas0san9oczgoldlo0oldlogz1047210127401", 1)]
+        );
+    }
+
+    #[test]
+    fn test_take_census() {
+        let mut ash = cells(&["oo", "oo"], 0, 0);
+        ash.extend(cells(&["oo", "oo"], 10, 10));
+        ash.extend(cells(&["ooo"], 20, 0));
+        ash.extend(cells(&[".oo.", "o..o", ".oo."], 0, 20));
+        let census = take_census(ash, Rule::conway());
+        assert_eq!(census.get_total(), 4);
+        assert_eq!(
+            census.get_objects(),
+            vec![("xs4_33", 2), ("xp2_7", 1), ("xs6_696", 1)]
+        );
+        assert_eq!(census.get_count("xq4_153"), 0);
+        assert_eq!(
+            census.write(CensusFormat::Text),
+            "rule: B3/S23\nobjects: 4\nxs4_33 2\nxp2_7 1\nxs6_696 1\n"
+        );
+        assert_eq!(
+            census.write(CensusFormat::Json),
+            "{\n  \"rule\": \"B3/S23\",\n  \"objects\": 4,\n  \"census\": {\n    \"xs4_33\": 2,\n    \"xp2_7\": 1,\n    \"xs6_696\": 1\n  }\n}\n"
+        );
+
+        let mut merged = census.clone();
+        merged.merge(&census);
+        assert_eq!(merged.get_count("xs4_33"), 4);
+        assert_eq!(
+            take_census(Vec::new(), Rule::conway())
+                .to_json()
+                .lines()
+                .last(),
+            Some("}")
+        );
+    }
+
+    #[test]
+    fn test_census_format() {
+        assert_eq!(
+            CensusFormat::from_path("ash.JSON"),
+            Some(CensusFormat::Json)
+        );
+        assert_eq!(CensusFormat::from_path("ash.txt"), Some(CensusFormat::Text));
+        assert_eq!(CensusFormat::from_path("ash"), None);
+        for format in CensusFormat::ALL {
+            assert_eq!(format.to_string().parse::<CensusFormat>(), Ok(format));
+        }
+        assert!("xml".parse::<CensusFormat>().is_err());
+    }
+}
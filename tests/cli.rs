@@ -0,0 +1,202 @@
+#[cfg(test)]
+mod tests {
+    use rust_of_life::age::ColorMode;
+    use rust_of_life::cli::{parse_args, Command, Options};
+    use rust_of_life::engine::Engine;
+    use rust_of_life::game::Game;
+    use rust_of_life::grid::Grid;
+    use rust_of_life::hashlife::HashLife;
+    use rust_of_life::pattern::write_macrocell;
+    use rust_of_life::topology::Topology;
+
+    fn parse(args: &[&str]) -> Result<Command, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    fn options(args: &[&str]) -> Options {
+        match parse(args) {
+            Ok(Command::Run(options)) => options,
+            other => panic!("expected run options, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_no_arguments_runs_with_defaults() {
+        assert_eq!(parse(&[]), Ok(Command::Run(Options::default())));
+        assert_eq!(parse(&["run"]), Ok(Command::Run(Options::default())));
+    }
+
+    #[test]
+    fn test_help_and_version() {
+        assert_eq!(parse(&["help"]), Ok(Command::Help));
+        assert_eq!(parse(&["run", "--size", "20", "-h"]), Ok(Command::Help));
+        assert_eq!(parse(&["version"]), Ok(Command::Version));
+        assert_eq!(parse(&["--version"]), Ok(Command::Version));
+    }
+
+    #[test]
+    fn test_flags_set_game_fields() {
+        let options = options(&[
+            "run",
+            "-s",
+            "50",
+            "--speed=30",
+            "--rule",
+            "B36/S23",
+            "-t",
+            "torus",
+            "--engine=hashlife",
+            "--step",
+            "4",
+            "--step-count",
+            "25",
+            "-j",
+            "2",
+            "--font",
+            "fonts/mono.ttf",
+            "--width",
+            "1200",
+            "--height",
+            "800",
+            "--seed",
+            "1234",
+            "--density",
+            "35",
+            "--colors",
+            "age",
+            "--gradient",
+            "#ffffff,#000000",
+            "--trail",
+            "12",
+            "--stats-length",
+            "500",
+            "--graph-length",
+            "200",
+        ]);
+        let mut game = Game::new();
+        options.apply(&mut game);
+        assert_eq!(game.get_size_grid(), 50);
+        assert_eq!(game.get_max_iteration_per_second(), 30.0);
+        assert_eq!(game.get_rule(), "B36/S23".parse().unwrap());
+        assert_eq!(game.get_topology(), Topology::Torus);
+        assert_eq!(game.get_engine(), Engine::HashLife);
+        assert_eq!(game.get_step_log2(), 4);
+        assert_eq!(game.get_step_count(), 25);
+        assert_eq!(game.get_thread_count(), 2);
+        assert_eq!(game.get_font_path(), "fonts/mono.ttf");
+        assert_eq!(
+            (game.get_window_width(), game.get_window_height()),
+            (1200, 800)
+        );
+        assert_eq!(game.get_unit_grid(), 16.0);
+        assert_eq!(game.get_seed(), Some(1234));
+        assert_eq!(game.get_density(), 0.35);
+        assert_eq!(game.get_color_mode(), ColorMode::Age);
+        assert_eq!(game.get_gradient().to_string(), "#ffffff,#000000");
+        assert_eq!(game.get_trail_length(), 12);
+        assert_eq!(game.get_stats_length(), 500);
+        assert_eq!(game.get_graph_length(), 200);
+        assert_eq!(options.get_pattern_path(), None);
+    }
+
+    #[test]
+    fn test_defaults_are_kept() {
+        let mut game = Game::new();
+        options(&["--size", "20"]).apply(&mut game);
+        assert_eq!(game.get_rule(), Game::new().get_rule());
+        assert_eq!(game.get_font_path(), Game::new().get_font_path());
+        assert_eq!(game.get_seed(), None);
+    }
+
+    #[test]
+    fn test_pattern_path() {
+        assert_eq!(
+            options(&["glider.rle"]).get_pattern_path(),
+            Some("glider.rle")
+        );
+        assert_eq!(
+            options(&["run", "-p", "gun.mc"]).get_pattern_path(),
+            Some("gun.mc")
+        );
+        assert!(parse(&["a.rle", "b.rle"]).is_err());
+    }
+
+    #[test]
+    fn test_pattern_rule_must_suit_the_engine() {
+        let path = std::env::temp_dir().join("rust_of_life-test_pattern_rule_engine.rle");
+        std::fs::write(&path, "x = 1, y = 1, rule = B03/S23\no!\n").unwrap();
+        let path = path.to_str().unwrap();
+        let mut grid = Grid::new(10, 10);
+
+        let mut game = Game::new();
+        let hashlife = options(&["-e", "hashlife", path]);
+        hashlife.apply(&mut game);
+        assert!(hashlife.load_start_grid(&mut grid, &mut game).is_err());
+
+        let mut game = Game::new();
+        let dense = options(&[path]);
+        dense.apply(&mut game);
+        assert_eq!(dense.load_start_grid(&mut grid, &mut game), Ok(()));
+        assert_eq!(game.get_rule(), "B03/S23".parse().unwrap());
+    }
+
+    #[test]
+    fn test_macrocell_loads_into_hashlife() {
+        let mut universe = HashLife::new("B36/S23".parse().unwrap());
+        for (x, y) in [
+            (0, 0),
+            (1, 0),
+            (2, 0),
+            (1000, 1000),
+            (1001, 1000),
+            (1002, 1000),
+        ] {
+            universe.set(x, y, true);
+        }
+        universe.set_generation(1234);
+        let path = std::env::temp_dir().join("rust_of_life-test_macrocell_loads_into_hashlife.mc");
+        std::fs::write(&path, write_macrocell(&universe)).unwrap();
+        let path = path.to_str().unwrap();
+
+        // the HashLife engine keeps the whole universe and its generation
+        let mut game = Game::new();
+        let hashlife = options(&["-e", "hashlife", "-s", "10", path]);
+        hashlife.apply(&mut game);
+        let loaded = hashlife.load_start_universe(&mut game).unwrap().unwrap();
+        assert_eq!(loaded.live_cells(), universe.live_cells());
+        assert_eq!(loaded.get_generation(), 1234);
+        assert_eq!(game.get_iteration(), 1234);
+        assert_eq!(game.get_rule(), "B36/S23".parse().unwrap());
+        assert_eq!(game.get_viewport_origin(), (496, 495));
+
+        // the other engines load it into the grid, which it does not fit in
+        let mut game = Game::new();
+        let dense = options(&["-s", "10", path]);
+        dense.apply(&mut game);
+        assert!(dense.load_start_universe(&mut game).unwrap().is_none());
+        let mut grid = Grid::new(10, 10);
+        dense.load_start_grid(&mut grid, &mut game).unwrap();
+        assert_eq!(grid.population(), 0);
+        assert_eq!(game.get_iteration(), 0);
+    }
+
+    #[test]
+    fn test_invalid_arguments() {
+        assert!(parse(&["--size"]).is_err());
+        assert!(parse(&["--size", "0"]).is_err());
+        assert!(parse(&["--size", "big"]).is_err());
+        assert!(parse(&["--rule", "B9"]).is_err());
+        assert!(parse(&["--topology", "sphere"]).is_err());
+        assert!(parse(&["--seed", "-1"]).is_err());
+        assert!(parse(&["--density", "101"]).is_err());
+        assert!(parse(&["--colors", "rainbow"]).is_err());
+        assert!(parse(&["--gradient", "#fff"]).is_err());
+        assert!(parse(&["--trail", "300"]).is_err());
+        assert!(parse(&["--stats-length", "0"]).is_err());
+        assert!(parse(&["--graph-length", "-1"]).is_err());
+        assert!(parse(&["-e", "hashlife", "--rule", "B03/S23"]).is_err());
+        assert!(parse(&["-e", "sparse", "--rule", "B03/S23"]).is_err());
+        assert!(parse(&["--density", "-5"]).is_err());
+        assert!(parse(&["--unknown", "1"]).is_err());
+    }
+}
@@ -0,0 +1,143 @@
+#[cfg(test)]
+mod tests {
+
+    use rust_of_life::cycle::{CycleDetector, CycleKind};
+    use rust_of_life::engine::Universe;
+    use rust_of_life::grid::{BandPool, Grid};
+    use rust_of_life::rule::Rule;
+    use rust_of_life::sparse::SparseLife;
+    use rust_of_life::topology::Topology;
+    use rust_of_life::utils::game_of_life;
+
+    fn grid_with(cells: &[(usize, usize)], size: usize) -> Grid {
+        let mut grid = Grid::new(size, size);
+        for &(x, y) in cells {
+            grid.set(x, y, true);
+        }
+        grid
+    }
+
+    /// Runs `grid` for `generations` generations and returns the cycle found at the end.
+    fn run(mut grid: Grid, generations: u64) -> CycleDetector {
+        let mut detector = CycleDetector::new();
+        let mut next = Grid::default();
+        for generation in 0..=generations {
+            detector.record_grid(generation, &grid);
+            grid.next_generation_into(
+                &mut next,
+                &Rule::conway(),
+                Topology::Dead,
+                &mut BandPool::new(1),
+            );
+            std::mem::swap(&mut grid, &mut next);
+        }
+        detector
+    }
+
+    const GLIDER: &[(usize, usize)] = &[(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)];
+
+    #[test]
+    fn test_still_life_and_oscillator() {
+        let block = grid_with(&[(1, 1), (2, 1), (1, 2), (2, 2)], 5);
+        let cycle = run(block, 3).get_cycle().unwrap();
+        assert_eq!(cycle.get_kind(), CycleKind::StillLife);
+        assert_eq!((cycle.get_start(), cycle.get_period()), (0, 1));
+        assert_eq!(cycle.to_string(), "still life from generation 0");
+
+        let blinker = grid_with(&[(1, 2), (2, 2), (3, 2)], 5);
+        assert_eq!(run(blinker.clone(), 1).get_cycle(), None);
+        let cycle = run(blinker, 5).get_cycle().unwrap();
+        assert_eq!(cycle.get_kind(), CycleKind::Oscillator);
+        assert_eq!((cycle.get_start(), cycle.get_period()), (0, 2));
+
+        let cycle = run(grid_with(&[(1, 1)], 5), 3).get_cycle().unwrap();
+        assert_eq!(cycle.get_kind(), CycleKind::Empty);
+        assert_eq!(cycle.get_start(), 1);
+    }
+
+    #[test]
+    fn test_spaceship() {
+        let cycle = run(grid_with(GLIDER, 30), 9).get_cycle().unwrap();
+        assert_eq!(cycle.get_kind(), CycleKind::Spaceship);
+        assert_eq!(cycle.get_period(), 4);
+        assert_eq!(cycle.get_displacement(), (1, 1));
+        assert_eq!(cycle.get_start(), 0);
+        assert_eq!(
+            cycle.to_string(),
+            "spaceship, period 4, moving (1, 1) from generation 0"
+        );
+
+        // the glider becomes a block in the corner of a bounded grid
+        let cycle = run(grid_with(GLIDER, 8), 40).get_cycle().unwrap();
+        assert_eq!(cycle.get_kind(), CycleKind::StillLife);
+
+        // on the unbounded plane
+        let mut sparse = SparseLife::new(Rule::conway());
+        sparse.load_grid(&grid_with(GLIDER, 3), -10, 5);
+        let mut detector = CycleDetector::new();
+        for _ in 0..8 {
+            detector.record_universe(&sparse);
+            Universe::step(&mut sparse, 0);
+        }
+        assert_eq!(detector.get_cycle().unwrap().get_displacement(), (1, 1));
+    }
+
+    #[test]
+    fn test_cycle_starts_at_its_first_generation() {
+        // a glider on a torus comes back in place after crossing it
+        let mut grid = grid_with(GLIDER, 20);
+        let mut next = Grid::default();
+        let mut detector = CycleDetector::new();
+        for generation in 0..=200 {
+            detector.record_grid(generation, &grid);
+            grid.next_generation_into(
+                &mut next,
+                &Rule::conway(),
+                Topology::Torus,
+                &mut BandPool::new(1),
+            );
+            std::mem::swap(&mut grid, &mut next);
+        }
+        let cycle = detector.get_cycle().unwrap();
+        assert_eq!(cycle.get_kind(), CycleKind::Oscillator);
+        assert_eq!((cycle.get_start(), cycle.get_period()), (0, 80));
+
+        // a T tetromino becomes a traffic light after some generations
+        let t = grid_with(&[(9, 9), (10, 9), (11, 9), (10, 10)], 21);
+        let mut grids = vec![t.clone()];
+        for _ in 0..20 {
+            let grid = grids.last().unwrap();
+            grids.push(game_of_life(grid, &Rule::conway(), Topology::Dead));
+        }
+        let settled = (0..20).find(|&g| grids[g] == grids[g + 2]).unwrap();
+        assert!(settled > 0);
+        let cycle = run(t, 20).get_cycle().unwrap();
+        assert_eq!(cycle.get_kind(), CycleKind::Oscillator);
+        assert_eq!((cycle.get_start(), cycle.get_period()), (settled as u64, 2));
+
+        // an L tromino becomes a block
+        let cycle = run(grid_with(&[(1, 1), (2, 1), (1, 2)], 5), 4)
+            .get_cycle()
+            .unwrap();
+        assert_eq!(cycle.get_kind(), CycleKind::StillLife);
+        assert_eq!(cycle.get_start(), 1);
+    }
+
+    #[test]
+    fn test_edits_start_over() {
+        let block = grid_with(&[(1, 1), (2, 1), (1, 2), (2, 2)], 5);
+        let mut detector = CycleDetector::new();
+        detector.record_grid(0, &block);
+        assert!(detector.record_grid(1, &block).is_some());
+        // the same generation shown again changes nothing
+        assert!(detector.record_grid(1, &block).is_some());
+
+        // an edit, then an undo to an earlier generation
+        let mut edited = block.clone();
+        edited.set(4, 4, true);
+        assert_eq!(detector.record_grid(1, &edited), None);
+        assert_eq!(detector.record_grid(0, &block), None);
+        let cycle = detector.record_grid(1, &block).unwrap();
+        assert_eq!(cycle.get_start(), 0);
+    }
+}
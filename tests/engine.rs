@@ -0,0 +1,80 @@
+#[cfg(test)]
+mod tests {
+    use rust_of_life::engine::{Engine, Universe, UniverseView};
+    use rust_of_life::game::Game;
+    use rust_of_life::grid::Grid;
+    use rust_of_life::rule::Rule;
+    use rust_of_life::sparse::SparseLife;
+
+    #[test]
+    fn test_default_engine_is_dense() {
+        assert_eq!(Engine::default(), Engine::Dense);
+    }
+
+    #[test]
+    fn test_next_cycles_through_all() {
+        assert_eq!(Engine::Dense.next(), Engine::HashLife);
+        assert_eq!(Engine::HashLife.next(), Engine::Sparse);
+        assert_eq!(Engine::Sparse.next(), Engine::Dense);
+        assert!(!Engine::Dense.is_unbounded());
+        assert!(Engine::HashLife.is_unbounded());
+    }
+
+    #[test]
+    fn test_check_rule() {
+        let b0 = "B03/S23".parse::<Rule>().unwrap();
+        assert!(Engine::HashLife.check_rule(&b0).is_err());
+        assert!(Engine::Sparse.check_rule(&b0).is_err());
+        assert_eq!(Engine::HashLife.check_rule(&Rule::conway()), Ok(()));
+        assert_eq!(Engine::Dense.check_rule(&b0), Ok(()));
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        for engine in Engine::ALL {
+            assert_eq!(engine.to_string().parse::<Engine>(), Ok(engine));
+        }
+        assert!("quantum".parse::<Engine>().is_err());
+    }
+
+    #[test]
+    fn test_universe_view_sync() {
+        let mut game_info = Game::new();
+        let mut universe = SparseLife::new(game_info.get_rule());
+        let mut view = UniverseView::default();
+        let mut grid = Grid::new(10, 10);
+
+        // the grid is loaded at the viewport origin
+        game_info.set_viewport_origin((100, -50));
+        grid.set(1, 2, true);
+        view.sync(&mut universe, &mut grid, &game_info, false, false);
+        assert!(universe.get(101, -48));
+
+        // moving the viewport shows another part of the plane, keeping what was drawn
+        game_info.move_viewport(-1, -2);
+        view.sync(&mut universe, &mut grid, &game_info, false, false);
+        assert!(grid.get(2, 4));
+        assert_eq!(grid.population(), 1);
+
+        // edits are written at the coordinates the grid was showing
+        grid.set(2, 4, false);
+        grid.set(0, 0, true);
+        view.sync(&mut universe, &mut grid, &game_info, false, false);
+        assert_eq!(universe.population(), 1);
+        assert!(universe.get(99, -52));
+
+        // a blinker outside the viewport keeps running
+        for x in 200..203 {
+            universe.set(x, 0, true);
+        }
+        view.sync(&mut universe, &mut grid, &game_info, false, true);
+        assert!(universe.get(201, -1) && universe.get(201, 1));
+        assert_eq!(universe.get_generation(), 1);
+
+        // a reset of the game rebuilds the universe from the grid
+        game_info.reset();
+        grid.clear();
+        view.sync(&mut universe, &mut grid, &game_info, false, false);
+        assert_eq!(universe.population(), 0);
+    }
+}
@@ -0,0 +1,660 @@
+#[cfg(test)]
+mod tests {
+
+    use rust_of_life::camera::Camera;
+    use rust_of_life::engine::Engine;
+    use rust_of_life::frontend::{
+        get_graph, handle_input, run_frontend, Color, Frame, InputEvent, InputSource, Key,
+        MouseButton, Rect, Renderer, Series, BIRTH_COLOR, CELL_COLOR, DEATH_COLOR,
+        POPULATION_COLOR,
+    };
+    use rust_of_life::game::{Game, GameStatus};
+    use rust_of_life::grid::Grid;
+    use rust_of_life::hashlife::HashLife;
+    use rust_of_life::pattern::write_macrocell;
+    use rust_of_life::rule::Rule;
+    use rust_of_life::stats::{GenerationStats, StatsRecorder};
+    use std::collections::VecDeque;
+    use std::time::Duration;
+
+    /// Input source replaying one list of events per frame, then quitting.
+    struct ScriptedInput {
+        frames: VecDeque<Vec<InputEvent>>,
+    }
+
+    impl InputSource for ScriptedInput {
+        fn poll_events(&mut self) -> Vec<InputEvent> {
+            self.frames
+                .pop_front()
+                .unwrap_or_else(|| vec![InputEvent::Quit])
+        }
+    }
+
+    /// Renderer recording the population and the HUD text of every frame.
+    #[derive(Default)]
+    struct RecordingRenderer {
+        size: Option<(u32, u32)>,
+        frame_time: Duration,
+        populations: Vec<usize>,
+        labels: Vec<Vec<String>>,
+        ghosts: Vec<usize>,
+        selections: Vec<bool>,
+        trails: Vec<usize>,
+        cell_colors: Vec<Vec<Color>>,
+        graphs: Vec<(Vec<Series>, Option<Rect>)>,
+    }
+
+    impl Renderer for RecordingRenderer {
+        fn get_size(&self) -> (u32, u32) {
+            self.size.unwrap_or((1000, 1000))
+        }
+
+        fn draw(&mut self, frame: &Frame) -> Result<(), String> {
+            std::thread::sleep(self.frame_time);
+            self.populations.push(frame.cells.len());
+            self.labels
+                .push(frame.labels.iter().map(|l| l.text.clone()).collect());
+            self.ghosts.push(frame.ghost.len());
+            self.selections.push(frame.selection.is_some());
+            self.trails.push(frame.trail.len());
+            self.cell_colors.push(frame.cell_colors.to_vec());
+            self.graphs.push((frame.graph.to_vec(), frame.graph_area));
+            Ok(())
+        }
+    }
+
+    fn blinker() -> Grid {
+        let mut grid = Grid::new(10, 10);
+        for x in 3..6 {
+            grid.set(x, 4, true);
+        }
+        grid
+    }
+
+    #[test]
+    fn test_run_frontend_steps_while_running() {
+        let mut game_info = Game::new();
+        game_info.set_size_grid(10);
+        game_info.set_max_iteration_per_second(200.0);
+        let mut input = ScriptedInput {
+            frames: VecDeque::from(vec![vec![InputEvent::KeyDown(Key::Space)], vec![], vec![]]),
+        };
+        let mut renderer = RecordingRenderer {
+            frame_time: Duration::from_millis(20),
+            ..Default::default()
+        };
+
+        run_frontend(&mut game_info, blinker(), &mut renderer, &mut input).unwrap();
+
+        assert_eq!(game_info.get_game_state(), GameStatus::Exit);
+        // several generations are computed in each 20 ms frame at 200 generations per second
+        let iteration = game_info.get_iteration();
+        assert!(iteration >= 4, "{} generations", iteration);
+        assert_eq!(renderer.populations, vec![3, 3, 3]);
+        assert_eq!(renderer.labels[2][0], format!("iteration: {}", iteration));
+        assert_eq!(renderer.labels[2][1], "population: 3");
+    }
+
+    #[test]
+    fn test_run_frontend_respects_speed() {
+        let mut game_info = Game::new();
+        game_info.set_max_iteration_per_second(0.1);
+        let mut input = ScriptedInput {
+            frames: VecDeque::from(vec![vec![InputEvent::KeyDown(Key::Space)], vec![], vec![]]),
+        };
+        let mut renderer = RecordingRenderer {
+            frame_time: Duration::from_millis(20),
+            ..Default::default()
+        };
+
+        run_frontend(&mut game_info, blinker(), &mut renderer, &mut input).unwrap();
+
+        assert_eq!(game_info.get_iteration(), 0);
+    }
+
+    #[test]
+    fn test_handle_input_speed() {
+        let mut grid = Grid::new(10, 10);
+        let mut game_info = Game::new();
+        handle_input(
+            &InputEvent::KeyDown(Key::Char('+')),
+            &mut grid,
+            &mut game_info,
+        );
+        assert_eq!(game_info.get_max_iteration_per_second(), 20.0);
+        handle_input(
+            &InputEvent::KeyDown(Key::Char('-')),
+            &mut grid,
+            &mut game_info,
+        );
+        handle_input(
+            &InputEvent::KeyDown(Key::Char('-')),
+            &mut grid,
+            &mut game_info,
+        );
+        assert_eq!(game_info.get_max_iteration_per_second(), 5.0);
+    }
+
+    #[test]
+    fn test_run_frontend_paused_does_not_step() {
+        let mut game_info = Game::new();
+        let mut input = ScriptedInput {
+            frames: VecDeque::from(vec![vec![], vec![]]),
+        };
+        let mut renderer = RecordingRenderer::default();
+
+        run_frontend(&mut game_info, blinker(), &mut renderer, &mut input).unwrap();
+
+        assert_eq!(game_info.get_iteration(), 0);
+        assert_eq!(renderer.populations, vec![3, 3]);
+    }
+
+    #[test]
+    fn test_run_frontend_single_step_while_paused() {
+        let mut game_info = Game::new();
+        let mut input = ScriptedInput {
+            frames: VecDeque::from(vec![vec![InputEvent::KeyDown(Key::Char('n'))], vec![]]),
+        };
+        let mut renderer = RecordingRenderer::default();
+
+        run_frontend(&mut game_info, blinker(), &mut renderer, &mut input).unwrap();
+
+        assert_eq!(game_info.get_iteration(), 1);
+        assert_eq!(renderer.labels[0][0], "iteration: 1");
+        assert_eq!(renderer.labels[1][0], "iteration: 1");
+    }
+
+    #[test]
+    fn test_run_frontend_step_by_n_while_paused() {
+        let mut game_info = Game::new();
+        game_info.set_step_count(5);
+        let mut input = ScriptedInput {
+            frames: VecDeque::from(vec![
+                vec![InputEvent::KeyDown(Key::Char('g'))],
+                vec![InputEvent::KeyDown(Key::Char('n'))],
+                vec![],
+            ]),
+        };
+        let mut renderer = RecordingRenderer::default();
+
+        run_frontend(&mut game_info, blinker(), &mut renderer, &mut input).unwrap();
+
+        assert_eq!(game_info.get_iteration(), 6);
+        assert_eq!(renderer.labels[0][0], "iteration: 5");
+        assert_eq!(renderer.labels[2][0], "iteration: 6");
+        assert_eq!(renderer.populations, vec![3, 3, 3]);
+    }
+
+    #[test]
+    fn test_run_frontend_undo_redo_edit() {
+        let mut game_info = Game::new();
+        game_info.set_size_grid(10);
+        let unit = game_info.get_unit_grid() as i32;
+        let click = InputEvent::MouseButtonDown {
+            x: unit / 2,
+            y: unit / 2,
+            button: MouseButton::Left,
+        };
+        let mut input = ScriptedInput {
+            frames: VecDeque::from(vec![
+                vec![click],
+                vec![InputEvent::KeyDown(Key::Char('z'))],
+                vec![InputEvent::KeyDown(Key::Char('y'))],
+            ]),
+        };
+        let mut renderer = RecordingRenderer::default();
+
+        run_frontend(&mut game_info, blinker(), &mut renderer, &mut input).unwrap();
+
+        assert_eq!(renderer.populations, vec![4, 3, 4]);
+    }
+
+    #[test]
+    fn test_run_frontend_copy_paste() {
+        let mut game_info = Game::new();
+        game_info.set_size_grid(10);
+        let unit = game_info.get_unit_grid() as i32;
+        let motion = |x: i32, y: i32, left: bool| InputEvent::MouseMotion {
+            x: x * unit + unit / 2,
+            y: y * unit + unit / 2,
+            xrel: 0,
+            yrel: 0,
+            left,
+            middle: false,
+            right: false,
+        };
+        let click = |x: i32, y: i32| InputEvent::MouseButtonDown {
+            x: x * unit + unit / 2,
+            y: y * unit + unit / 2,
+            button: MouseButton::Left,
+        };
+        let mut input = ScriptedInput {
+            frames: VecDeque::from(vec![
+                // shift-drag over the blinker, without editing it
+                vec![
+                    InputEvent::KeyDown(Key::Shift),
+                    click(3, 4),
+                    motion(5, 4, true),
+                    InputEvent::KeyUp(Key::Shift),
+                ],
+                // copy, then paste with the ghost following the mouse
+                vec![
+                    InputEvent::KeyDown(Key::Ctrl),
+                    InputEvent::KeyDown(Key::Char('c')),
+                    InputEvent::KeyDown(Key::Char('v')),
+                    InputEvent::KeyUp(Key::Ctrl),
+                    motion(4, 7, false),
+                ],
+                vec![click(4, 7)],
+                vec![InputEvent::KeyDown(Key::Char('z'))],
+                // escape clears the selection before quitting
+                vec![InputEvent::KeyDown(Key::Escape)],
+            ]),
+        };
+        let mut renderer = RecordingRenderer::default();
+
+        run_frontend(&mut game_info, blinker(), &mut renderer, &mut input).unwrap();
+
+        assert_eq!(renderer.populations, vec![3, 3, 6, 3, 3]);
+        assert_eq!(renderer.ghosts, vec![0, 3, 0, 0, 0]);
+        assert_eq!(renderer.selections, vec![true, true, true, true, false]);
+    }
+
+    #[test]
+    fn test_run_frontend_random_fill() {
+        let mut game_info = Game::new();
+        game_info.set_size_grid(10);
+        game_info.set_seed(Some(7));
+        game_info.set_density(1.0);
+        let ctrl = |key| {
+            vec![
+                InputEvent::KeyDown(Key::Ctrl),
+                InputEvent::KeyDown(Key::Char(key)),
+                InputEvent::KeyUp(Key::Ctrl),
+            ]
+        };
+        let mut input = ScriptedInput {
+            frames: VecDeque::from(vec![ctrl('r'), ctrl('a'), ctrl('r')]),
+        };
+        let mut renderer = RecordingRenderer::default();
+
+        run_frontend(&mut game_info, Grid::new(10, 10), &mut renderer, &mut input).unwrap();
+
+        assert_eq!(renderer.populations, vec![100, 100, 100]);
+        assert_eq!(renderer.labels[0][6], "seed: 7 (density 100%)");
+        assert_eq!(game_info.get_last_seed(), Some(7));
+    }
+
+    #[test]
+    fn test_run_frontend_colors_by_age() {
+        let mut game_info = Game::new();
+        game_info.set_size_grid(10);
+        let gradient = game_info.get_gradient();
+        let mut input = ScriptedInput {
+            frames: VecDeque::from(vec![
+                vec![],
+                vec![InputEvent::KeyDown(Key::Char('n'))],
+                vec![InputEvent::KeyDown(Key::Char('c'))],
+            ]),
+        };
+        let mut renderer = RecordingRenderer::default();
+
+        run_frontend(&mut game_info, blinker(), &mut renderer, &mut input).unwrap();
+
+        // newborn cells, then the center is older and the ends that died fade
+        assert_eq!(renderer.cell_colors[0], vec![gradient.get_color(1); 3]);
+        assert_eq!(
+            renderer.cell_colors[1],
+            vec![
+                gradient.get_color(2),
+                gradient.get_color(1),
+                gradient.get_color(1)
+            ]
+        );
+        assert_eq!(renderer.trails, vec![0, 2, 0]);
+        assert_eq!(renderer.cell_colors[2], vec![CELL_COLOR; 3]);
+    }
+
+    #[test]
+    fn test_run_frontend_shows_cycle() {
+        let mut game_info = Game::new();
+        game_info.set_size_grid(10);
+        let unit = game_info.get_unit_grid() as i32;
+        let mut input = ScriptedInput {
+            frames: VecDeque::from(vec![
+                vec![InputEvent::KeyDown(Key::Char('n'))],
+                vec![InputEvent::KeyDown(Key::Char('n'))],
+                // an edit starts the detection over
+                vec![InputEvent::MouseButtonDown {
+                    x: unit / 2,
+                    y: unit / 2,
+                    button: MouseButton::Left,
+                }],
+            ]),
+        };
+        let mut renderer = RecordingRenderer::default();
+
+        run_frontend(&mut game_info, blinker(), &mut renderer, &mut input).unwrap();
+
+        let cycles: Vec<&str> = renderer.labels.iter().map(|l| l[4].as_str()).collect();
+        assert_eq!(
+            cycles,
+            vec![
+                "cycle: none",
+                "cycle: oscillator, period 2 from generation 0",
+                "cycle: none"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_run_frontend_shows_stats() {
+        for (engine, step_log2, expected) in [
+            (
+                Engine::Dense,
+                0,
+                "births: 2, deaths: 2, density: 3%, step: ",
+            ),
+            (
+                Engine::Sparse,
+                0,
+                "births: 2, deaths: 2, density: 100%, step: ",
+            ),
+            // the sparse engine ignores the step exponent
+            (
+                Engine::Sparse,
+                48,
+                "births: 2, deaths: 2, density: 100%, step: ",
+            ),
+            (
+                Engine::HashLife,
+                1,
+                "births: 0, deaths: 0 over 2 generations, density: 100%, step: ",
+            ),
+        ] {
+            let mut game_info = Game::new();
+            game_info.set_size_grid(10);
+            game_info.set_engine(engine);
+            game_info.set_step_log2(step_log2);
+            let mut input = ScriptedInput {
+                frames: VecDeque::from(vec![vec![], vec![InputEvent::KeyDown(Key::Char('n'))]]),
+            };
+            let mut renderer = RecordingRenderer::default();
+
+            run_frontend(&mut game_info, blinker(), &mut renderer, &mut input).unwrap();
+
+            assert_eq!(renderer.labels[0][5], "births: -, deaths: -");
+            assert!(renderer.labels[1][5].starts_with(expected), "{:?}", engine);
+            // the step exponent is only shown where it applies
+            let step = format!("step 2^{}", step_log2);
+            assert_eq!(
+                renderer.labels[1][3].contains(&step),
+                engine == Engine::HashLife,
+                "{}",
+                renderer.labels[1][3]
+            );
+        }
+    }
+
+    #[test]
+    fn test_dropped_macrocell_loads_into_hashlife() {
+        let mut universe = HashLife::new(Rule::conway());
+        for (x, y) in [(0, 0), (1, 0), (2, 0), (500, 500), (501, 500), (502, 500)] {
+            universe.set(x, y, true);
+        }
+        universe.set_generation(1000);
+        let path = std::env::temp_dir().join("rust_of_life-test_dropped_macrocell.mc");
+        std::fs::write(&path, write_macrocell(&universe)).unwrap();
+        let path = path.to_str().unwrap().to_string();
+
+        for (engine, iteration) in [(Engine::HashLife, 1000), (Engine::Dense, 0)] {
+            let mut game_info = Game::new();
+            game_info.set_size_grid(10);
+            game_info.set_engine(engine);
+            let mut input = ScriptedInput {
+                frames: VecDeque::from(vec![vec![InputEvent::DropFile(path.clone())]]),
+            };
+            let mut renderer = RecordingRenderer::default();
+
+            run_frontend(&mut game_info, blinker(), &mut renderer, &mut input).unwrap();
+
+            assert_eq!(
+                renderer.labels[0][0],
+                format!("iteration: {}", iteration),
+                "{}",
+                engine
+            );
+        }
+    }
+
+    #[test]
+    fn test_get_graph() {
+        let mut stats = StatsRecorder::new(10);
+        for (generation, population) in [(1, 4), (2, 8), (3, 6)] {
+            stats.record(GenerationStats::new(
+                generation,
+                population,
+                (2, 1),
+                None,
+                100,
+                Duration::ZERO,
+            ));
+        }
+        let area = Rect::new(10.0, 20.0, 100.0, 80.0);
+
+        let (graph, max) = get_graph(&stats, 5, area);
+        assert_eq!(max, 8);
+        let colors: Vec<Color> = graph.iter().map(|s| s.color).collect();
+        assert_eq!(colors, vec![POPULATION_COLOR, BIRTH_COLOR, DEATH_COLOR]);
+        // the newest generation is on the right, the highest value at the top
+        let population: Vec<(f32, f32)> = graph[0].points.iter().map(|p| (p.x, p.y)).collect();
+        assert_eq!(population, vec![(60.0, 60.0), (85.0, 20.0), (110.0, 40.0)]);
+        assert_eq!(graph[1].points[2].y, 80.0);
+
+        // only the last generations are shown, rescaled to their highest value
+        let (graph, max) = get_graph(&stats, 1, area);
+        assert_eq!(max, 6);
+        assert_eq!(graph[0].points.len(), 1);
+        assert_eq!((graph[0].points[0].x, graph[0].points[0].y), (110.0, 20.0));
+
+        let (graph, max) = get_graph(&StatsRecorder::new(10), 5, area);
+        assert_eq!(max, 1);
+        assert!(graph.iter().all(|s| s.points.is_empty()));
+    }
+
+    #[test]
+    fn test_run_frontend_shows_graph() {
+        for (size, graph_length, shown) in [
+            ((1400, 1000), 500, true),
+            ((1000, 1300), 500, true),
+            ((1400, 1000), 0, false),
+            ((1000, 1000), 500, false),
+        ] {
+            let mut game_info = Game::new();
+            game_info.set_size_grid(10);
+            game_info.set_graph_length(graph_length);
+            let mut input = ScriptedInput {
+                frames: VecDeque::from(vec![
+                    vec![InputEvent::KeyDown(Key::Char('n'))],
+                    vec![InputEvent::KeyDown(Key::Char('n'))],
+                ]),
+            };
+            let mut renderer = RecordingRenderer {
+                size: Some(size),
+                ..Default::default()
+            };
+
+            run_frontend(&mut game_info, blinker(), &mut renderer, &mut input).unwrap();
+
+            let (graph, area) = &renderer.graphs[1];
+            assert_eq!(area.is_some(), shown, "{:?}", size);
+            let lengths: Vec<usize> = graph.iter().map(|s| s.points.len()).collect();
+            if shown {
+                assert_eq!(lengths, vec![2, 2, 2]);
+                assert_eq!(renderer.labels[1].last().unwrap(), "graph max: 3");
+            } else {
+                assert!(lengths.is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn test_run_frontend_rewind() {
+        let mut game_info = Game::new();
+        game_info.set_step_count(3);
+        let mut input = ScriptedInput {
+            frames: VecDeque::from(vec![
+                vec![InputEvent::KeyDown(Key::Char('g'))],
+                vec![InputEvent::KeyDown(Key::Char('n'))],
+                vec![InputEvent::KeyDown(Key::Char('b'))],
+                vec![InputEvent::KeyDown(Key::Char('y'))],
+            ]),
+        };
+        let mut renderer = RecordingRenderer::default();
+
+        run_frontend(&mut game_info, blinker(), &mut renderer, &mut input).unwrap();
+
+        let iterations: Vec<&str> = renderer.labels.iter().map(|l| l[0].as_str()).collect();
+        assert_eq!(
+            iterations,
+            vec![
+                "iteration: 3",
+                "iteration: 4",
+                "iteration: 1",
+                "iteration: 2"
+            ]
+        );
+        assert_eq!(game_info.get_iteration(), 2);
+    }
+
+    #[test]
+    fn test_handle_input_quit() {
+        let mut grid = Grid::new(10, 10);
+        let mut game_info = Game::new();
+        handle_input(&InputEvent::Quit, &mut grid, &mut game_info);
+        assert_eq!(game_info.get_game_state(), GameStatus::Exit);
+    }
+
+    #[test]
+    fn test_handle_input_mouse() {
+        let mut grid = Grid::new(10, 10);
+        let mut game_info = Game::new();
+        game_info.set_size_grid(10);
+        let unit = game_info.get_unit_grid() as i32;
+
+        let click = InputEvent::MouseButtonDown {
+            x: 2 * unit + 1,
+            y: 3 * unit + 1,
+            button: MouseButton::Left,
+        };
+        handle_input(&click, &mut grid, &mut game_info);
+        assert!(grid.get(2, 3));
+
+        let erase = InputEvent::MouseMotion {
+            x: 2 * unit + 1,
+            y: 3 * unit + 1,
+            xrel: 1,
+            yrel: 0,
+            left: false,
+            middle: false,
+            right: true,
+        };
+        handle_input(&erase, &mut grid, &mut game_info);
+        assert!(!grid.get(2, 3));
+
+        // clicks outside the grid are ignored
+        let outside = InputEvent::MouseButtonDown {
+            x: 20 * unit,
+            y: 0,
+            button: MouseButton::Left,
+        };
+        handle_input(&outside, &mut grid, &mut game_info);
+        assert_eq!(grid.population(), 0);
+    }
+
+    #[test]
+    fn test_handle_input_camera() {
+        let mut grid = Grid::new(100, 100);
+        let mut game_info = Game::new();
+        assert_eq!(game_info.get_camera().get_zoom(), 10.0);
+
+        // the wheel zooms around the mouse, and clicks follow the camera
+        let wheel = InputEvent::MouseWheel {
+            x: 500,
+            y: 500,
+            delta: 2,
+        };
+        handle_input(&wheel, &mut grid, &mut game_info);
+        assert_eq!(game_info.get_camera().get_zoom(), 15.625);
+        let click = InputEvent::MouseButtonDown {
+            x: 500,
+            y: 500,
+            button: MouseButton::Left,
+        };
+        handle_input(&click, &mut grid, &mut game_info);
+        assert!(grid.get(50, 50));
+
+        // dragging with the middle button pans without editing
+        let drag = InputEvent::MouseMotion {
+            x: 400,
+            y: 500,
+            xrel: -125,
+            yrel: 0,
+            left: false,
+            middle: true,
+            right: false,
+        };
+        handle_input(&drag, &mut grid, &mut game_info);
+        assert_eq!(grid.population(), 1);
+        handle_input(&click, &mut grid, &mut game_info);
+        assert!(grid.get(58, 50));
+
+        // fitting centers the pattern, home shows the whole grid again
+        handle_input(
+            &InputEvent::KeyDown(Key::Char('f')),
+            &mut grid,
+            &mut game_info,
+        );
+        let camera = game_info.get_camera();
+        assert_eq!(camera.screen_to_cell(500.0, 500.0).1, 50);
+        assert!(camera.get_zoom() > 15.625);
+        handle_input(
+            &InputEvent::KeyDown(Key::Char('h')),
+            &mut grid,
+            &mut game_info,
+        );
+        assert_eq!(game_info.get_camera(), Camera::new(10.0));
+
+        // the arrow keys pan the dense grid by a tenth of the view
+        handle_input(&InputEvent::KeyDown(Key::Right), &mut grid, &mut game_info);
+        assert_eq!(game_info.get_camera().get_x(), 10.0);
+    }
+
+    #[test]
+    fn test_handle_input_engine_skips_unsupported_rules() {
+        let mut grid = blinker();
+        let mut game_info = Game::new();
+        let key_e = InputEvent::KeyDown(Key::Char('e'));
+        handle_input(&key_e, &mut grid, &mut game_info);
+        assert_eq!(game_info.get_engine(), Engine::HashLife);
+
+        game_info.set_engine(Engine::Dense);
+        game_info.set_rule("B03/S23".parse().unwrap());
+        handle_input(&key_e, &mut grid, &mut game_info);
+        assert_eq!(game_info.get_engine(), Engine::Dense);
+    }
+
+    #[test]
+    fn test_handle_input_reset() {
+        let mut grid = blinker();
+        let mut game_info = Game::new();
+        game_info.set_iteration(5);
+        handle_input(
+            &InputEvent::KeyDown(Key::Char('r')),
+            &mut grid,
+            &mut game_info,
+        );
+        assert_eq!(grid.population(), 0);
+        assert_eq!(game_info.get_iteration(), 0);
+    }
+}
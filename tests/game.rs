@@ -1,7 +1,10 @@
 #[cfg(test)]
 mod tests {
 
+    use rust_of_life::engine::Engine;
     use rust_of_life::game::{Game, GameStatus};
+    use rust_of_life::rule::Rule;
+    use rust_of_life::topology::Topology;
 
     #[test]
     fn test_new_game() {
@@ -14,7 +17,57 @@ mod tests {
         assert_eq!(game.get_window_min_length(), 1000);
         assert_eq!(game.get_unit_grid(), 10.0);
         assert_eq!(game.get_iteration(), 0);
-        assert_eq!(game.get_max_iteration_per_second(), 10);
+        assert_eq!(game.get_max_iteration_per_second(), 10.0);
+        assert_eq!(game.get_rule(), Rule::conway());
+        assert_eq!(game.get_topology(), Topology::Dead);
+        assert!(game.get_thread_count() >= 1);
+        assert_eq!(game.get_engine(), Engine::Dense);
+        assert_eq!(game.get_step_log2(), 0);
+        assert_eq!(game.get_viewport_origin(), (0, 0));
+    }
+
+    #[test]
+    fn test_move_viewport() {
+        let mut game = Game::new();
+        game.set_viewport_origin((-5, 7));
+        game.move_viewport(10, -20);
+        assert_eq!(game.get_viewport_origin(), (5, -13));
+        game.move_viewport(i64::MAX, 0);
+        assert_eq!(game.get_viewport_origin(), (i64::MAX, -13));
+    }
+
+    #[test]
+    fn test_set_engine_and_step_log2() {
+        let mut game = Game::new();
+        game.set_engine(Engine::HashLife);
+        game.set_step_log2(10);
+        assert_eq!(game.get_engine(), Engine::HashLife);
+        assert_eq!(game.get_step_log2(), 10);
+        game.set_step_log2(u8::MAX);
+        assert_eq!(game.get_step_log2(), 48);
+        assert_eq!(game.get_engine_step_log2(), 48);
+        for engine in [Engine::Dense, Engine::Sparse] {
+            game.set_engine(engine);
+            assert_eq!(game.get_engine_step_log2(), 0);
+            assert_eq!(game.get_step_log2(), 48);
+        }
+    }
+
+    #[test]
+    fn test_set_thread_count() {
+        let mut game = Game::new();
+        game.set_thread_count(4);
+        assert_eq!(game.get_thread_count(), 4);
+        game.set_thread_count(0);
+        assert_eq!(game.get_thread_count(), 1);
+    }
+
+    #[test]
+    fn test_set_rule() {
+        let mut game = Game::new();
+        let rule: Rule = "B36/S23".parse().unwrap();
+        game.set_rule(rule);
+        assert_eq!(game.get_rule(), rule);
     }
 
     #[test]
@@ -24,6 +77,51 @@ mod tests {
         assert_eq!(game.get_game_state(), GameStatus::Running);
     }
 
+    #[test]
+    fn test_set_topology() {
+        let mut game = Game::new();
+        game.set_topology(Topology::Torus);
+        assert_eq!(game.get_topology(), Topology::Torus);
+    }
+
+    #[test]
+    fn test_set_size_grid() {
+        let mut game = Game::new();
+        game.set_size_grid(50);
+        assert_eq!(game.get_size_grid(), 50);
+        assert_eq!(game.get_unit_grid(), 20.0);
+    }
+
+    #[test]
+    fn test_set_font_path_and_seed() {
+        let mut game = Game::new();
+        assert_eq!(game.get_font_path(), "./assets/Roboto-Medium.ttf");
+        assert_eq!(game.get_seed(), None);
+        game.set_font_path("fonts/mono.ttf".to_string());
+        game.set_seed(Some(42));
+        game.set_max_iteration_per_second(60.0);
+        assert_eq!(game.get_font_path(), "fonts/mono.ttf");
+        assert_eq!(game.get_seed(), Some(42));
+        assert_eq!(game.get_max_iteration_per_second(), 60.0);
+    }
+
+    #[test]
+    fn test_density_and_seed_of_random_fills() {
+        let mut game = Game::new();
+        assert_eq!(game.get_density(), 0.5);
+        assert_eq!(game.get_last_seed(), None);
+        game.set_density(1.5);
+        assert_eq!(game.get_density(), 1.0);
+        game.set_density(-0.5);
+        assert_eq!(game.get_density(), 0.0);
+
+        // a seed given on the command line makes every fill reproducible
+        game.set_seed(Some(42));
+        assert_eq!(game.next_seed(), 42);
+        game.set_last_seed(Some(42));
+        assert_eq!(game.get_last_seed(), Some(42));
+    }
+
     #[test]
     fn test_set_window_height() {
         let mut game = Game::new();
@@ -68,4 +166,21 @@ mod tests {
         let ips = game.get_iteration_per_second();
         assert!(ips >= 0.0);
     }
+
+    #[test]
+    fn test_step() {
+        let mut game = Game::new();
+        assert_eq!(game.get_step_count(), 10);
+        game.step(1);
+        assert_eq!(game.get_game_state(), GameStatus::Step(1));
+        game.step(game.get_step_count());
+        assert_eq!(game.get_game_state(), GameStatus::Step(11));
+        game.set_step_count(0);
+        assert_eq!(game.get_step_count(), 1);
+
+        // stepping a running game stops it after the steps
+        game.run();
+        game.step(3);
+        assert_eq!(game.get_game_state(), GameStatus::Step(3));
+    }
 }
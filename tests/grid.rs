@@ -0,0 +1,215 @@
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use rust_of_life::grid::{BandPool, Grid};
+    use rust_of_life::rule::Rule;
+    use rust_of_life::topology::Topology;
+
+    /// Cell-by-cell reference implementation of a generation.
+    fn reference_generation(rows: &[Vec<bool>], rule: &Rule, topology: Topology) -> Vec<Vec<bool>> {
+        let height = rows.len() as i64;
+        let width = rows[0].len() as i64;
+        let mut next = vec![vec![false; width as usize]; height as usize];
+        for y in 0..height {
+            for x in 0..width {
+                let mut count = 0;
+                for dy in -1..=1 {
+                    for dx in -1..=1 {
+                        if (dx, dy) == (0, 0) {
+                            continue;
+                        }
+                        if let Some((nx, ny)) = topology.resolve(x + dx, y + dy, width, height) {
+                            if rows[ny as usize][nx as usize] {
+                                count += 1;
+                            }
+                        }
+                    }
+                }
+                next[y as usize][x as usize] = rule.next_state(rows[y as usize][x as usize], count);
+            }
+        }
+        next
+    }
+
+    #[test]
+    fn test_new_grid_is_empty() {
+        let grid = Grid::new(130, 3);
+        assert_eq!(grid.get_width(), 130);
+        assert_eq!(grid.get_height(), 3);
+        assert_eq!(grid.get_words_per_row(), 3);
+        assert_eq!(grid.population(), 0);
+        assert_eq!(grid.live_cells().count(), 0);
+    }
+
+    #[test]
+    fn test_set_get_toggle() {
+        let mut grid = Grid::new(100, 10);
+        grid.set(63, 2, true);
+        grid.set(64, 2, true);
+        grid.toggle(99, 9);
+        assert!(grid.get(63, 2));
+        assert!(grid.get(64, 2));
+        assert!(grid.get(99, 9));
+        assert!(!grid.get(65, 2));
+        assert_eq!(grid.population(), 3);
+        grid.toggle(64, 2);
+        assert!(!grid.get(64, 2));
+        // cells outside the grid are dead and cannot be set
+        grid.set(100, 0, true);
+        assert!(!grid.get(100, 0));
+        assert_eq!(grid.population(), 2);
+        grid.clear();
+        assert_eq!(grid.population(), 0);
+    }
+
+    #[test]
+    fn test_contains() {
+        let grid = Grid::new(5, 4);
+        assert!(grid.contains(0, 0));
+        assert!(grid.contains(4, 3));
+        assert!(!grid.contains(-1, 0));
+        assert!(!grid.contains(5, 0));
+        assert!(!grid.contains(0, 4));
+    }
+
+    #[test]
+    fn test_live_cells_row_major() {
+        let mut grid = Grid::new(70, 3);
+        grid.set(69, 0, true);
+        grid.set(1, 0, true);
+        grid.set(0, 2, true);
+        let cells: Vec<(usize, usize)> = grid.live_cells().collect();
+        assert_eq!(cells, vec![(1, 0), (69, 0), (0, 2)]);
+    }
+
+    #[test]
+    fn test_changed_cells() {
+        let mut grid = Grid::new(70, 2);
+        let mut other = grid.clone();
+        grid.set(65, 0, true);
+        other.set(3, 1, true);
+        assert_eq!(grid.changed_cells(&other), vec![(65, 0), (3, 1)]);
+        assert_eq!(grid.changed_cells(&Grid::default()), vec![(65, 0)]);
+    }
+
+    #[test]
+    fn test_rows_round_trip() {
+        let rows = vec![vec![true, false, true], vec![false, true, false]];
+        let grid = Grid::from_rows(&rows);
+        assert_eq!(grid.get_width(), 3);
+        assert_eq!(grid.get_height(), 2);
+        assert_eq!(grid.to_rows(), rows);
+    }
+
+    #[test]
+    fn test_next_generation_matches_reference() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let rules: Vec<Rule> = ["B3/S23", "B36/S23", "B3678/S34678", "B2/S", "B0/S8"]
+            .iter()
+            .map(|rule| rule.parse().unwrap())
+            .collect();
+        for (width, height) in [(1, 1), (3, 5), (63, 4), (64, 6), (65, 7), (130, 9)] {
+            let rows: Vec<Vec<bool>> = (0..height)
+                .map(|_| (0..width).map(|_| rng.gen_bool(0.35)).collect())
+                .collect();
+            let grid = Grid::from_rows(&rows);
+            for rule in &rules {
+                for topology in Topology::ALL {
+                    let mut next = Grid::default();
+                    grid.next_generation_into(&mut next, rule, topology, &mut BandPool::new(1));
+                    assert_eq!(
+                        next.to_rows(),
+                        reference_generation(&rows, rule, topology),
+                        "{}x{} {} {}",
+                        width,
+                        height,
+                        rule,
+                        topology
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_band_pool_thread_count() {
+        assert_eq!(BandPool::new(0).get_thread_count(), 1);
+        assert_eq!(BandPool::new(1).get_thread_count(), 1);
+        assert_eq!(BandPool::new(4).get_thread_count(), 4);
+    }
+
+    #[test]
+    fn test_xor_delta() {
+        let mut a = Grid::new(100, 3);
+        a.set(1, 0, true);
+        a.set(70, 2, true);
+        let mut b = a.clone();
+        b.set(1, 0, false);
+        b.set(99, 1, true);
+        assert!(a.xor_delta(&a).is_empty());
+        let delta = a.xor_delta(&b);
+        assert_eq!(delta.len(), 2);
+        let mut c = b.clone();
+        c.apply_xor_delta(&delta);
+        assert_eq!(c, a);
+        c.apply_xor_delta(&delta);
+        assert_eq!(c, b);
+    }
+
+    #[test]
+    fn test_crop_and_paste() {
+        let mut grid = Grid::new(5, 4);
+        grid.set(1, 1, true);
+        grid.set(2, 3, true);
+        let cropped = grid.crop(1, 1, 5, 3);
+        assert_eq!((cropped.get_width(), cropped.get_height()), (5, 3));
+        assert_eq!(
+            cropped.live_cells().collect::<Vec<_>>(),
+            vec![(0, 0), (1, 2)]
+        );
+
+        // pasting replaces the cells under it and clips at the edges
+        let mut target = Grid::new(5, 4);
+        target.set(4, 2, true);
+        target.paste(&cropped, 3, 1);
+        assert_eq!(
+            target.live_cells().collect::<Vec<_>>(),
+            vec![(3, 1), (4, 3)]
+        );
+        target.paste(&cropped, -1, -1);
+        assert_eq!(
+            target.live_cells().collect::<Vec<_>>(),
+            vec![(0, 1), (4, 3)]
+        );
+
+        target.fill_region(3, 2, 10, 10, true);
+        assert_eq!(target.population(), 5);
+    }
+
+    #[test]
+    fn test_rotate_and_flip() {
+        // L shape in a 3x2 grid
+        let grid = Grid::from_rows(&[vec![true, false, false], vec![true, true, true]]);
+        let rotated = grid.rotate_clockwise();
+        assert_eq!(
+            rotated.to_rows(),
+            vec![vec![true, true], vec![true, false], vec![true, false]]
+        );
+        assert_eq!(
+            rotated
+                .rotate_clockwise()
+                .rotate_clockwise()
+                .rotate_clockwise(),
+            grid
+        );
+        assert_eq!(
+            grid.flip_horizontal().to_rows(),
+            vec![vec![false, false, true], vec![true, true, true]]
+        );
+        assert_eq!(
+            grid.flip_vertical().to_rows(),
+            vec![vec![true, true, true], vec![true, false, false]]
+        );
+    }
+}
@@ -0,0 +1,170 @@
+#[cfg(test)]
+mod tests {
+    use rust_of_life::grid::Grid;
+    use rust_of_life::hashlife::{HashLife, MAX_LEVEL};
+    use rust_of_life::rule::Rule;
+    use rust_of_life::topology::Topology;
+    use rust_of_life::utils::game_of_life;
+
+    const GLIDER: &[(usize, usize)] = &[(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)];
+    const BLINKER: &[(usize, usize)] = &[(0, 1), (1, 1), (2, 1)];
+    const R_PENTOMINO: &[(usize, usize)] = &[(1, 0), (2, 0), (0, 1), (1, 1), (1, 2)];
+    const ACORN: &[(usize, usize)] = &[(1, 0), (3, 1), (0, 2), (1, 2), (4, 2), (5, 2), (6, 2)];
+
+    /// Places `cells` in the middle of a grid large enough that nothing reaches its edges.
+    fn grid_with(cells: &[(usize, usize)], size: usize) -> Grid {
+        let mut grid = Grid::new(size, size);
+        for &(x, y) in cells {
+            grid.set(x + size / 2, y + size / 2, true);
+        }
+        grid
+    }
+
+    fn run_dense(mut grid: Grid, rule: &Rule, generations: u64) -> Grid {
+        for _ in 0..generations {
+            grid = game_of_life(&grid, rule, Topology::Dead);
+        }
+        grid
+    }
+
+    fn run_hashlife(grid: &Grid, rule: Rule, step_log2: u8, steps: u64) -> Grid {
+        let mut universe = HashLife::new(rule);
+        universe.load_grid(grid, 0, 0);
+        for _ in 0..steps {
+            universe.step(step_log2);
+        }
+        assert_eq!(universe.get_generation(), steps << step_log2);
+        let mut result = Grid::new(grid.get_width(), grid.get_height());
+        universe.write_to_grid(&mut result, 0, 0);
+        assert_eq!(universe.population(), result.population() as u64);
+        result
+    }
+
+    #[test]
+    fn test_set_and_get() {
+        let mut universe = HashLife::new(Rule::conway());
+        universe.set(0, 0, true);
+        universe.set(-1000, 2000, true);
+        universe.set(5, -3, true);
+        universe.set(5, -3, false);
+        assert!(universe.get(0, 0));
+        assert!(universe.get(-1000, 2000));
+        assert!(!universe.get(5, -3));
+        assert!(!universe.get(1, 0));
+        assert_eq!(universe.population(), 2);
+        assert_eq!(universe.live_cells(), vec![(0, 0), (-1000, 2000)]);
+        assert_eq!(universe.bounding_box(), Some((-1000, 0, 0, 2000)));
+        universe.clear();
+        assert_eq!(universe.population(), 0);
+        assert_eq!(universe.bounding_box(), None);
+    }
+
+    #[test]
+    fn test_matches_dense_engine_one_generation_at_a_time() {
+        let rule = Rule::conway();
+        for pattern in [GLIDER, BLINKER, R_PENTOMINO, ACORN] {
+            let grid = grid_with(pattern, 128);
+            assert_eq!(run_hashlife(&grid, rule, 0, 40), run_dense(grid, &rule, 40));
+        }
+    }
+
+    #[test]
+    fn test_matches_dense_engine_with_large_steps() {
+        let rule = Rule::conway();
+        for pattern in [GLIDER, BLINKER, R_PENTOMINO, ACORN] {
+            let grid = grid_with(pattern, 256);
+            let dense = run_dense(grid.clone(), &rule, 64);
+            assert_eq!(run_hashlife(&grid, rule, 6, 1), dense);
+            assert_eq!(run_hashlife(&grid, rule, 3, 8), dense);
+        }
+    }
+
+    #[test]
+    fn test_matches_dense_engine_with_other_rule() {
+        let highlife: Rule = "B36/S23".parse().unwrap();
+        let grid = grid_with(R_PENTOMINO, 128);
+        assert_eq!(
+            run_hashlife(&grid, highlife, 2, 8),
+            run_dense(grid, &highlife, 32)
+        );
+    }
+
+    #[test]
+    fn test_glider_travels_far() {
+        let mut universe = HashLife::new(Rule::conway());
+        for &(x, y) in GLIDER {
+            universe.set(x as i64, y as i64, true);
+        }
+        universe.step(20);
+        // a glider moves one cell diagonally every 4 generations
+        let shift = (1i64 << 20) / 4;
+        assert_eq!(universe.population(), 5);
+        assert_eq!(
+            universe.bounding_box(),
+            Some((shift, shift, shift + 2, shift + 2))
+        );
+    }
+
+    #[test]
+    fn test_load_grid_with_origin() {
+        let grid = grid_with(BLINKER, 8);
+        let mut universe = HashLife::new(Rule::conway());
+        universe.load_grid(&grid, -100, 50);
+        assert_eq!(universe.live_cells(), vec![(-96, 55), (-95, 55), (-94, 55)]);
+        let mut view = Grid::new(8, 8);
+        universe.write_to_grid(&mut view, -100, 50);
+        assert_eq!(view, grid);
+    }
+
+    #[test]
+    fn test_stays_within_the_largest_level() {
+        let edge = 1i64 << (MAX_LEVEL - 1);
+        let mut universe = HashLife::new(Rule::conway());
+        for (x, y) in [
+            (i64::MIN, 0),
+            (i64::MAX, i64::MAX),
+            (edge, 0),
+            (edge - 1, -edge),
+        ] {
+            universe.set(x, y, true);
+        }
+        assert_eq!(universe.get_root_level(), MAX_LEVEL);
+        assert_eq!(universe.live_cells(), vec![(edge - 1, -edge)]);
+
+        for origin in [i64::MIN, i64::MAX, edge - 1] {
+            universe.load_grid(&grid_with(BLINKER, 8), origin, origin);
+            assert!(universe.get_root_level() <= MAX_LEVEL);
+            let mut view = Grid::new(8, 8);
+            universe.write_to_grid(&mut view, origin, origin);
+            assert_eq!(view.population() as u64, universe.population());
+        }
+
+        // a glider flying off the edge is dropped, the blinker at the origin stays
+        let mut universe = HashLife::new(Rule::conway());
+        for &(x, y) in GLIDER {
+            universe.set(edge - 20 + x as i64, edge - 20 + y as i64, true);
+        }
+        for &(x, y) in BLINKER {
+            universe.set(x as i64, y as i64, true);
+        }
+        universe.step(8);
+        assert!(universe.get_root_level() <= MAX_LEVEL);
+        assert_eq!(universe.live_cells(), vec![(0, 1), (1, 1), (2, 1)]);
+    }
+
+    #[test]
+    fn test_collect_garbage_keeps_pattern() {
+        let mut universe = HashLife::new(Rule::conway());
+        for &(x, y) in ACORN {
+            universe.set(x as i64, y as i64, true);
+        }
+        universe.step(4);
+        let cells = universe.live_cells();
+        let nodes = universe.get_node_count();
+        universe.collect_garbage();
+        assert!(universe.get_node_count() <= nodes);
+        assert_eq!(universe.live_cells(), cells);
+        universe.step(4);
+        assert_eq!(universe.get_generation(), 32);
+    }
+}
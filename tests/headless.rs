@@ -0,0 +1,227 @@
+#[cfg(test)]
+mod tests {
+    use rust_of_life::census::CensusFormat;
+    use rust_of_life::cli::{parse_args, Command};
+    use rust_of_life::engine::Engine;
+    use rust_of_life::game::Game;
+    use rust_of_life::grid::Grid;
+    use rust_of_life::hashlife::HashLife;
+    use rust_of_life::headless::{run_headless, run_headless_universe, HeadlessOptions};
+    use rust_of_life::pattern::{parse_macrocell, parse_pattern, PatternFormat};
+    use rust_of_life::rule::Rule;
+    use rust_of_life::stats::StatsFormat;
+
+    fn grid_with(cells: &[(usize, usize)], size: usize) -> Grid {
+        let mut grid = Grid::new(size, size);
+        for &(x, y) in cells {
+            grid.set(x, y, true);
+        }
+        grid
+    }
+
+    fn generations(generations: u64) -> HeadlessOptions {
+        let mut options = HeadlessOptions::default();
+        options.set_generations(Some(generations));
+        options
+    }
+
+    const GLIDER: &[(usize, usize)] = &[(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)];
+    const BLINKER: &[(usize, usize)] = &[(4, 5), (5, 5), (6, 5)];
+
+    #[test]
+    fn test_run_generations() {
+        let game_info = Game::new();
+        let (text, report) =
+            run_headless(&grid_with(GLIDER, 20), &game_info, &generations(8)).unwrap();
+        assert_eq!(report.get_generation(), 8);
+        assert_eq!(report.get_population(), 5);
+        // a glider moves one cell diagonally every 4 generations
+        assert_eq!(report.get_bounding_box(), Some((2, 2, 4, 4)));
+        assert_eq!(report.get_stable(), None);
+        let pattern = parse_pattern(&text).unwrap();
+        assert_eq!(pattern.get_rule(), Some(game_info.get_rule()));
+        assert_eq!(pattern.get_grid().population(), 5);
+    }
+
+    #[test]
+    fn test_run_until_stable() {
+        let mut options = HeadlessOptions::default();
+        options.set_until_stable(true);
+        let (_, report) = run_headless(&grid_with(BLINKER, 12), &Game::new(), &options).unwrap();
+        assert_eq!(report.get_stable(), Some((0, 2)));
+        assert_eq!(report.get_generation(), 2);
+        assert!(report
+            .to_string()
+            .contains("stable: period 2 from generation 0"));
+
+        // a glider on a dead border turns into a block in the corner
+        let (_, report) = run_headless(&grid_with(GLIDER, 12), &Game::new(), &options).unwrap();
+        assert_eq!(report.get_population(), 4);
+        assert_eq!(report.get_stable().map(|(_, period)| period), Some(1));
+
+        // the limit stops runs that never settle
+        options.set_generations(Some(10));
+        let (_, report) = run_headless(&grid_with(GLIDER, 64), &Game::new(), &options).unwrap();
+        assert_eq!((report.get_generation(), report.get_stable()), (10, None));
+    }
+
+    #[test]
+    fn test_report_cycle() {
+        let (_, report) =
+            run_headless(&grid_with(BLINKER, 12), &Game::new(), &generations(5)).unwrap();
+        assert_eq!(report.get_cycle().map(|cycle| cycle.get_period()), Some(2));
+        assert!(report
+            .to_string()
+            .contains("cycle: oscillator, period 2 from generation 0"));
+
+        // a spaceship flies on forever on the unbounded plane
+        let mut game_info = Game::new();
+        game_info.set_engine(Engine::Sparse);
+        let mut options = HeadlessOptions::default();
+        options.set_until_stable(true);
+        let (_, report) = run_headless(&grid_with(GLIDER, 4), &game_info, &options).unwrap();
+        assert_eq!(report.get_generation(), 4);
+        assert_eq!(report.get_stable(), Some((0, 4)));
+        assert_eq!(
+            report.get_cycle().map(|cycle| cycle.get_displacement()),
+            Some((1, 1))
+        );
+        assert!(report
+            .to_string()
+            .contains("cycle: spaceship, period 4, moving (1, 1) from generation 0"));
+    }
+
+    #[test]
+    fn test_report_census() {
+        let (_, report) =
+            run_headless(&grid_with(BLINKER, 12), &Game::new(), &generations(3)).unwrap();
+        assert_eq!(report.get_census(), None);
+
+        let mut options = generations(3);
+        options.set_census_path(Some("ash.txt".to_string()));
+        let mut cells = BLINKER.to_vec();
+        cells.extend([(0, 0), (1, 0), (0, 1), (1, 1)]);
+        let (_, report) = run_headless(&grid_with(&cells, 12), &Game::new(), &options).unwrap();
+        let census = report.get_census().unwrap();
+        assert_eq!(census.get_objects(), vec![("xp2_7", 1), ("xs4_33", 1)]);
+    }
+
+    #[test]
+    fn test_report_generation_stats() {
+        let (_, report) =
+            run_headless(&grid_with(BLINKER, 12), &Game::new(), &generations(3)).unwrap();
+        assert_eq!(report.get_generation_stats(), None);
+
+        for engine in [Engine::Dense, Engine::Sparse, Engine::HashLife] {
+            let mut game_info = Game::new();
+            game_info.set_engine(engine);
+            let mut options = generations(3);
+            options.set_generation_stats_path(Some("run.json".to_string()));
+            assert_eq!(options.get_generation_stats_format(), StatsFormat::Json);
+            let (_, report) = run_headless(&grid_with(BLINKER, 12), &game_info, &options).unwrap();
+            let records: Vec<_> = report
+                .get_generation_stats()
+                .unwrap()
+                .get_records()
+                .map(|stats| {
+                    (
+                        stats.get_generation(),
+                        stats.get_births(),
+                        stats.get_deaths(),
+                    )
+                })
+                .collect();
+            assert_eq!(records, vec![(1, 2, 2), (2, 2, 2), (3, 2, 2)], "{}", engine);
+        }
+    }
+
+    #[test]
+    fn test_unbounded_engines_stop_exactly() {
+        for engine in [Engine::HashLife, Engine::Sparse] {
+            let mut game_info = Game::new();
+            game_info.set_engine(engine);
+            game_info.set_step_log2(3);
+            let (_, report) =
+                run_headless(&grid_with(GLIDER, 4), &game_info, &generations(21)).unwrap();
+            assert_eq!(report.get_generation(), 21, "{}", engine);
+            assert_eq!(report.get_population(), 5);
+            let (min_x, min_y, _, _) = report.get_bounding_box().unwrap();
+            assert_eq!((min_x, min_y), (5, 6), "{}", engine);
+        }
+    }
+
+    #[test]
+    fn test_run_universe_from_its_generation() {
+        let mut universe = HashLife::new(Rule::conway());
+        for &(x, y) in GLIDER {
+            universe.set(x as i64, y as i64, true);
+        }
+        universe.set_generation(100);
+        let mut game_info = Game::new();
+        game_info.set_engine(Engine::HashLife);
+        game_info.set_step_log2(2);
+        let (text, report) = run_headless_universe(universe, &game_info, &generations(8)).unwrap();
+        assert_eq!(report.get_generation(), 108);
+        assert_eq!(report.get_computed(), 8);
+        assert_eq!(report.get_bounding_box(), Some((2, 2, 4, 4)));
+        assert!(text.contains("x = 3, y = 3"));
+    }
+
+    #[test]
+    fn test_hashlife_writes_macrocell() {
+        let mut game_info = Game::new();
+        game_info.set_engine(Engine::HashLife);
+        game_info.set_step_log2(10);
+        let mut options = generations(1 << 20);
+        options.set_format(Some(PatternFormat::Macrocell));
+        let (text, report) = run_headless(&grid_with(GLIDER, 4), &game_info, &options).unwrap();
+        let universe = parse_macrocell(&text).unwrap();
+        assert_eq!(universe.get_generation(), 1 << 20);
+        assert_eq!(universe.bounding_box(), report.get_bounding_box());
+    }
+
+    #[test]
+    fn test_needs_generations_or_until_stable() {
+        assert!(run_headless(&Grid::new(4, 4), &Game::new(), &HeadlessOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_parse_headless_command() {
+        let args = [
+            "headless",
+            "-n",
+            "500",
+            "--until-stable",
+            "-o",
+            "out.cells",
+            "--stats=stats.txt",
+            "--census",
+            "ash.json",
+            "--generation-stats",
+            "run.txt",
+            "--generation-stats-format",
+            "json",
+            "glider.rle",
+        ];
+        let Ok(Command::Headless(options, headless)) =
+            parse_args(args.iter().map(|arg| arg.to_string()))
+        else {
+            panic!("expected a headless command");
+        };
+        assert_eq!(options.get_pattern_path(), Some("glider.rle"));
+        assert_eq!(headless.get_generations(), Some(500));
+        assert!(headless.get_until_stable());
+        assert_eq!(headless.get_output_path(), Some("out.cells"));
+        assert_eq!(headless.get_stats_path(), Some("stats.txt"));
+        assert_eq!(headless.get_format(), PatternFormat::Plaintext);
+        assert_eq!(headless.get_census_path(), Some("ash.json"));
+        assert_eq!(headless.get_census_format(), CensusFormat::Json);
+        assert_eq!(headless.get_generation_stats_path(), Some("run.txt"));
+        assert_eq!(headless.get_generation_stats_format(), StatsFormat::Json);
+
+        let args = ["run", "--until-stable"].iter().map(|arg| arg.to_string());
+        assert!(parse_args(args).is_err());
+        let args = ["headless", "-f", "png"].iter().map(|arg| arg.to_string());
+        assert!(parse_args(args).is_err());
+    }
+}
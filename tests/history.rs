@@ -0,0 +1,155 @@
+#[cfg(test)]
+mod tests {
+
+    use rust_of_life::grid::Grid;
+    use rust_of_life::history::{Change, History};
+    use rust_of_life::rule::Rule;
+    use rust_of_life::topology::Topology;
+    use rust_of_life::utils::game_of_life;
+
+    fn glider() -> Grid {
+        let mut grid = Grid::new(20, 20);
+        for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            grid.set(x, y, true);
+        }
+        grid
+    }
+
+    fn step(grid: &Grid) -> Grid {
+        game_of_life(grid, &Rule::conway(), Topology::Dead)
+    }
+
+    #[test]
+    fn test_undo_redo_edit() {
+        let mut grid = Grid::new(10, 10);
+        let mut history = History::new(10);
+        history.reset(&grid, 0);
+
+        grid.set(3, 4, true);
+        history.record(&grid, 0, Change::Edit, false);
+        assert_eq!(history.get_undo_count(), 1);
+
+        assert_eq!(history.undo(&mut grid), Some(0));
+        assert_eq!(grid.population(), 0);
+        assert_eq!(history.undo(&mut grid), None);
+
+        assert_eq!(history.redo(&mut grid), Some(0));
+        assert!(grid.get(3, 4));
+        assert_eq!(history.redo(&mut grid), None);
+    }
+
+    #[test]
+    fn test_record_without_change_is_ignored() {
+        let grid = glider();
+        let mut history = History::new(10);
+        history.reset(&grid, 0);
+        history.record(&grid, 0, Change::Edit, false);
+        assert_eq!(history.get_undo_count(), 0);
+    }
+
+    #[test]
+    fn test_strokes_are_merged() {
+        let mut grid = Grid::new(10, 10);
+        let mut history = History::new(10);
+        history.reset(&grid, 0);
+        for x in 0..5 {
+            grid.set(x, 2, true);
+            history.record(&grid, 0, Change::Edit, x > 0);
+        }
+        // drawing over a cell of the stroke again does not break the merge
+        grid.set(0, 2, false);
+        history.record(&grid, 0, Change::Edit, true);
+        assert_eq!(history.get_undo_count(), 1);
+        history.undo(&mut grid);
+        assert_eq!(grid.population(), 0);
+    }
+
+    #[test]
+    fn test_rewind_restores_generations_and_iteration() {
+        let start = glider();
+        let mut grid = start.clone();
+        let mut history = History::new(100);
+        history.reset(&grid, 7);
+        let mut generations = vec![grid.clone()];
+        for iteration in 8..=17 {
+            grid = step(&grid);
+            history.record(&grid, iteration, Change::Generation, false);
+            generations.push(grid.clone());
+        }
+
+        assert_eq!(history.rewind(&mut grid, 4), Some(13));
+        assert_eq!(grid, generations[6]);
+        assert_eq!(history.get_redo_count(), 4);
+
+        // rewinding past the start stops at the oldest entry
+        assert_eq!(history.rewind(&mut grid, 100), Some(7));
+        assert_eq!(grid, start);
+
+        assert_eq!(history.redo(&mut grid), Some(8));
+        assert_eq!(grid, generations[1]);
+    }
+
+    #[test]
+    fn test_rewind_undoes_edits_in_between() {
+        let mut grid = glider();
+        let mut history = History::new(100);
+        history.reset(&grid, 0);
+        grid = step(&grid);
+        history.record(&grid, 1, Change::Generation, false);
+        let after_one = grid.clone();
+        grid.set(15, 15, true);
+        history.record(&grid, 1, Change::Edit, false);
+        grid = step(&grid);
+        history.record(&grid, 2, Change::Generation, false);
+
+        assert_eq!(history.rewind(&mut grid, 1), Some(1));
+        assert!(grid.get(15, 15));
+        assert_eq!(history.rewind(&mut grid, 1), Some(0));
+        assert_eq!(grid, glider());
+        assert_ne!(grid, after_one);
+    }
+
+    #[test]
+    fn test_new_change_clears_redo() {
+        let mut grid = Grid::new(10, 10);
+        let mut history = History::new(10);
+        history.reset(&grid, 0);
+        grid.set(1, 1, true);
+        history.record(&grid, 0, Change::Edit, false);
+        history.undo(&mut grid);
+        grid.set(2, 2, true);
+        history.record(&grid, 0, Change::Edit, false);
+        assert_eq!(history.get_redo_count(), 0);
+        assert_eq!(history.redo(&mut grid), None);
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let mut grid = glider();
+        let mut history = History::new(3);
+        history.reset(&grid, 0);
+        for iteration in 1..=10 {
+            grid = step(&grid);
+            history.record(&grid, iteration, Change::Generation, false);
+        }
+        assert_eq!(history.get_undo_count(), 3);
+        assert_eq!(history.rewind(&mut grid, 10), Some(7));
+
+        let mut disabled = History::new(0);
+        disabled.reset(&grid, 0);
+        grid.set(0, 0, true);
+        disabled.record(&grid, 0, Change::Edit, false);
+        assert_eq!(disabled.undo(&mut grid), None);
+    }
+
+    #[test]
+    fn test_reset_undo_restores_iteration() {
+        let mut grid = glider();
+        let mut history = History::new(10);
+        history.reset(&grid, 42);
+        grid.clear();
+        history.record(&grid, 0, Change::Edit, false);
+        assert_eq!(history.undo(&mut grid), Some(42));
+        assert_eq!(grid, glider());
+    }
+}
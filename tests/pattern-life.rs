@@ -0,0 +1,99 @@
+#[cfg(test)]
+mod tests {
+    use rust_of_life::grid::Grid;
+    use rust_of_life::pattern::{
+        parse_life105, parse_life106, write_life105, write_life106, Pattern,
+    };
+    use rust_of_life::rule::Rule;
+
+    const GLIDER_105: &str = "#Life 1.05
+#D Glider
+#D The smallest spaceship.
+#N
+#P -1 -1
+.*
+..*
+***
+";
+
+    const GLIDER_106: &str = "#Life 1.06
+0 -1
+1 0
+-1 1
+0 1
+1 1
+";
+
+    fn glider_cells() -> Vec<(usize, usize)> {
+        vec![(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]
+    }
+
+    #[test]
+    fn test_parse_life105() {
+        let pattern = parse_life105(GLIDER_105).unwrap();
+        assert_eq!(
+            pattern.get_comments(),
+            ["Glider", "The smallest spaceship."]
+        );
+        assert_eq!(pattern.get_rule(), Some(Rule::conway()));
+        assert_eq!(
+            pattern.get_grid().live_cells().collect::<Vec<_>>(),
+            glider_cells()
+        );
+    }
+
+    #[test]
+    fn test_parse_life105_blocks_and_rule() {
+        let text = "#Life 1.05\n#R 23/36\n#P 0 0\n*\n#P 10 -2\n**\n";
+        let pattern = parse_life105(text).unwrap();
+        assert_eq!(pattern.get_rule(), Some("B36/S23".parse().unwrap()));
+        let grid = pattern.get_grid();
+        assert_eq!((grid.get_width(), grid.get_height()), (12, 3));
+        assert_eq!(
+            grid.live_cells().collect::<Vec<_>>(),
+            vec![(10, 0), (11, 0), (0, 2)]
+        );
+        assert!(parse_life105("#Life 1.05\n#P 0 0\n*o*").is_err());
+        assert!(parse_life105("#Life 1.05\n#P zero 0\n*").is_err());
+    }
+
+    #[test]
+    fn test_parse_life106() {
+        let pattern = parse_life106(GLIDER_106).unwrap();
+        assert_eq!(pattern.get_rule(), None);
+        assert_eq!(
+            pattern.get_grid().live_cells().collect::<Vec<_>>(),
+            glider_cells()
+        );
+        assert!(parse_life106("#Life 1.06\n0").is_err());
+        assert!(parse_life106("#Life 1.06\n0 0 0").is_err());
+        assert_eq!(
+            parse_life106("#Life 1.06\n")
+                .unwrap()
+                .get_grid()
+                .population(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_write_and_read_back() {
+        let mut cells = Grid::new(3, 3);
+        for (x, y) in glider_cells() {
+            cells.set(x, y, true);
+        }
+        let mut pattern = Pattern::new(cells);
+        pattern.set_name(Some("Glider".to_string()));
+        pattern.set_rule(Some("B36/S23".parse().unwrap()));
+        pattern.add_comment("The smallest spaceship.".to_string());
+
+        let text = write_life105(&pattern);
+        assert!(text.contains("#R 23/36\n#P -1 -1\n.*\n..*\n***\n"));
+        assert_eq!(parse_life105(&text).unwrap(), pattern);
+
+        let text = write_life106(&pattern);
+        assert!(text.starts_with("#Life 1.06\n#D Name: Glider\n"));
+        assert!(text.ends_with("1 0\n2 1\n0 2\n1 2\n2 2\n"));
+        assert_eq!(parse_life106(&text).unwrap(), pattern);
+    }
+}
@@ -0,0 +1,104 @@
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use rust_of_life::hashlife::HashLife;
+    use rust_of_life::pattern::{parse_macrocell, parse_macrocell_pattern, write_macrocell};
+    use rust_of_life::rule::Rule;
+
+    const GLIDER: &str = "[M2] (golly 2.0)
+#R B3/S23
+#G 12
+.*$..*$***$
+4 1 0 0 0
+";
+
+    const MULTI_STATE: &str = "[M2] (golly 2.0)
+1 0 1 2 0
+1 3 0 0 1
+2 1 2 0 0
+";
+
+    #[test]
+    fn test_parse_two_state() {
+        let universe = parse_macrocell(GLIDER).unwrap();
+        assert_eq!(universe.get_rule(), Rule::conway());
+        assert_eq!(universe.get_generation(), 12);
+        // the level 4 root spans -8..8 in both directions
+        assert_eq!(
+            universe.live_cells(),
+            vec![(-7, -8), (-6, -7), (-8, -6), (-7, -6), (-6, -6)]
+        );
+    }
+
+    #[test]
+    fn test_parse_multi_state() {
+        let universe = parse_macrocell(MULTI_STATE).unwrap();
+        assert_eq!(
+            universe.live_cells(),
+            vec![(-1, -2), (0, -2), (-2, -1), (1, -1)]
+        );
+        assert_eq!(universe.get_generation(), 0);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse_macrocell(".*$\n").is_err());
+        assert!(parse_macrocell("[M2]\n4 1 0 0 0\n").is_err());
+        assert!(parse_macrocell("[M2]\n.*$\n5 1 0 0 0\n").is_err());
+        assert!(parse_macrocell("[M2]\n.o$\n").is_err());
+        assert!(parse_macrocell("[M2]\n.........*$\n").is_err());
+        assert!(parse_macrocell("[M2]\n4 1 0 0\n").is_err());
+        assert!(parse_macrocell("[M2]\n#G soon\n").is_err());
+        assert_eq!(parse_macrocell("[M2]\n").unwrap().population(), 0);
+    }
+
+    #[test]
+    fn test_write_and_read_back() {
+        let mut rng = StdRng::seed_from_u64(9);
+        let mut universe = HashLife::new("B36/S23".parse().unwrap());
+        for _ in 0..500 {
+            universe.set(rng.gen_range(-40..40), rng.gen_range(-30..50), true);
+        }
+        universe.step(5);
+        let text = write_macrocell(&universe);
+        assert!(text.starts_with("[M2]"));
+        let read = parse_macrocell(&text).unwrap();
+        assert_eq!(read.live_cells(), universe.live_cells());
+        assert_eq!(read.get_rule(), universe.get_rule());
+        assert_eq!(read.get_generation(), 32);
+    }
+
+    #[test]
+    fn test_identical_nodes_are_written_once() {
+        let mut universe = HashLife::new(Rule::conway());
+        for block in 0..4096 {
+            for (x, y) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                universe.set(block * 8 + x, y, true);
+            }
+        }
+        let text = write_macrocell(&universe);
+        assert!(text.lines().count() < 64);
+        assert_eq!(parse_macrocell(&text).unwrap().population(), 4 * 4096);
+    }
+
+    #[test]
+    fn test_parse_pattern_keeps_metadata() {
+        let text = "[M2] (golly 2.0)\n#R B36/S23\n#N Glider\n#C moving\n.*$..*$***$\n";
+        let pattern = parse_macrocell_pattern(text).unwrap();
+        assert_eq!(pattern.get_name(), Some("Glider"));
+        assert_eq!(pattern.get_comments(), ["moving"]);
+        assert_eq!(pattern.get_rule(), Some("B36/S23".parse().unwrap()));
+        assert_eq!(
+            pattern.get_grid().live_cells().collect::<Vec<_>>(),
+            vec![(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]
+        );
+        // a pattern spanning far more cells than a grid can hold
+        let huge = "[M2]\n*$\n4 1 0 0 0\n5 2 0 0 0\n6 3 0 0 0\n7 4 0 0 0\n\
+                    8 5 0 0 0\n9 6 0 0 0\n10 7 0 0 0\n11 8 0 0 0\n12 9 0 0 0\n\
+                    13 10 0 0 0\n14 11 0 0 0\n15 12 0 0 0\n16 13 0 0 0\n17 14 0 0 0\n\
+                    18 15 0 0 0\n18 0 0 0 15\n19 16 0 0 17\n";
+        assert!(parse_macrocell_pattern(huge).is_err());
+        assert_eq!(parse_macrocell(huge).unwrap().population(), 2);
+    }
+}
@@ -0,0 +1,60 @@
+#[cfg(test)]
+mod tests {
+    use rust_of_life::grid::Grid;
+    use rust_of_life::pattern::{parse_plaintext, write_plaintext, Pattern};
+
+    const GLIDER: &str = "!Name: Glider
+!Author: Richard K. Guy
+!The smallest, most common, and first discovered spaceship.
+.O
+..O
+OOO
+";
+
+    #[test]
+    fn test_parse_glider() {
+        let pattern = parse_plaintext(GLIDER).unwrap();
+        assert_eq!(pattern.get_name(), Some("Glider"));
+        assert_eq!(pattern.get_author(), Some("Richard K. Guy"));
+        assert_eq!(
+            pattern.get_comments(),
+            ["The smallest, most common, and first discovered spaceship."]
+        );
+        assert_eq!(pattern.get_rule(), None);
+        let grid = pattern.get_grid();
+        // short rows are padded with dead cells
+        assert_eq!((grid.get_width(), grid.get_height()), (3, 3));
+        assert_eq!(
+            grid.live_cells().collect::<Vec<_>>(),
+            vec![(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]
+        );
+    }
+
+    #[test]
+    fn test_parse_stars_and_blank_rows() {
+        let pattern = parse_plaintext("*.*\n\n.*").unwrap();
+        assert_eq!(pattern.get_grid().get_height(), 3);
+        assert_eq!(
+            pattern.get_grid().live_cells().collect::<Vec<_>>(),
+            vec![(0, 0), (2, 0), (1, 2)]
+        );
+    }
+
+    #[test]
+    fn test_rule_comment() {
+        let pattern = parse_plaintext("!Rule: B36/S23\nOO").unwrap();
+        assert_eq!(pattern.get_rule(), Some("B36/S23".parse().unwrap()));
+        assert!(parse_plaintext("!Rule: B9\nOO").is_err());
+    }
+
+    #[test]
+    fn test_write_keeps_size() {
+        let mut cells = Grid::new(4, 3);
+        cells.set(1, 1, true);
+        let mut pattern = Pattern::new(cells);
+        pattern.set_name(Some("dot".to_string()));
+        let text = write_plaintext(&pattern);
+        assert_eq!(text, "!Name: dot\n....\n.O..\n....\n");
+        assert_eq!(parse_plaintext(&text).unwrap(), pattern);
+    }
+}
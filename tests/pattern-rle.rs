@@ -0,0 +1,115 @@
+#[cfg(test)]
+mod tests {
+    use rust_of_life::grid::Grid;
+    use rust_of_life::pattern::{parse_rle, write_rle, Pattern};
+    use rust_of_life::rule::Rule;
+
+    const GLIDER: &str = "#N Glider
+#O Richard K. Guy
+#C The smallest, most common, and first discovered spaceship.
+#C www.conwaylife.com/wiki/index.php?title=Glider
+x = 3, y = 3, rule = B3/S23
+bo$2bo$3o!
+";
+
+    #[test]
+    fn test_parse_glider() {
+        let pattern = parse_rle(GLIDER).unwrap();
+        assert_eq!(pattern.get_name(), Some("Glider"));
+        assert_eq!(pattern.get_author(), Some("Richard K. Guy"));
+        assert_eq!(pattern.get_comments().len(), 2);
+        assert_eq!(pattern.get_rule(), Some(Rule::conway()));
+        let grid = pattern.get_grid();
+        assert_eq!((grid.get_width(), grid.get_height()), (3, 3));
+        assert_eq!(
+            grid.live_cells().collect::<Vec<_>>(),
+            vec![(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]
+        );
+    }
+
+    #[test]
+    fn test_parse_multiline_runs_and_blank_rows() {
+        let text = "x = 5, y = 4\n2o\n3$\n4bo!\nignored text after the end";
+        let pattern = parse_rle(text).unwrap();
+        assert_eq!(pattern.get_rule(), None);
+        assert_eq!(
+            pattern.get_grid().live_cells().collect::<Vec<_>>(),
+            vec![(0, 0), (1, 0), (4, 3)]
+        );
+    }
+
+    #[test]
+    fn test_parse_header_variants() {
+        let pattern = parse_rle("x=2,y=1,rule=23/36:T100,100\n2o!").unwrap();
+        assert_eq!(pattern.get_rule(), Some("B36/S23".parse().unwrap()));
+        // cells beyond the declared size grow the pattern
+        let pattern = parse_rle("x = 1, y = 1\n3o!").unwrap();
+        assert_eq!(pattern.get_grid().get_width(), 3);
+    }
+
+    #[test]
+    fn test_parse_multi_state_cells_are_alive() {
+        let pattern = parse_rle("x = 4, y = 1, rule = B3/S23\n.A.pB!").unwrap();
+        assert_eq!(
+            pattern.get_grid().live_cells().collect::<Vec<_>>(),
+            vec![(1, 0), (3, 0)]
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse_rle("").is_err());
+        assert!(parse_rle("bo$2bo$3o!").is_err());
+        assert!(parse_rle("x = 3\nbo!").is_err());
+        assert!(parse_rle("x = 3, y = 3, rule = B9\nbo!").is_err());
+        assert!(parse_rle("x = 3, y = 3\nb?o!").is_err());
+    }
+
+    #[test]
+    fn test_parse_rejects_huge_patterns() {
+        // run counts past usize::MAX, headers and runs too large for a grid
+        assert!(parse_rle("x = 3, y = 3\n99999999999999999999999o!").is_err());
+        assert!(parse_rle("x = 4000000000, y = 4000000000\no!").is_err());
+        assert!(parse_rle("x = 3, y = 3\n4000000000o!").is_err());
+        assert!(parse_rle("x = 3, y = 3\n100000$100000o!").is_err());
+        assert!(parse_rle("x = 3, y = 3\n18446744073709551615bo!").is_err());
+        // long runs of dead cells and rows cost nothing
+        let pattern = parse_rle("x = 1, y = 1\no4000000000b4000000000$!").unwrap();
+        assert_eq!(pattern.get_grid().population(), 1);
+    }
+
+    #[test]
+    fn test_write_glider() {
+        let pattern = parse_rle(GLIDER).unwrap();
+        assert_eq!(write_rle(&pattern), GLIDER);
+        // trailing dead cells are optional when reading
+        let padded = GLIDER.replace("bo$2bo", "bob$2bo");
+        assert_eq!(parse_rle(&padded).unwrap(), pattern);
+    }
+
+    #[test]
+    fn test_write_collapses_empty_rows_and_wraps_lines() {
+        let mut grid = Grid::new(200, 4);
+        grid.set(0, 0, true);
+        for x in (0..200).step_by(2) {
+            grid.set(x, 3, true);
+        }
+        let text = write_rle(&Pattern::new(grid.clone()));
+        assert!(text.starts_with("x = 200, y = 4, rule = B3/S23\no3$"));
+        assert!(text.lines().all(|line| line.len() <= 70));
+        assert_eq!(parse_rle(&text).unwrap().get_grid(), &grid);
+    }
+
+    #[test]
+    fn test_round_trip_keeps_metadata() {
+        let mut grid = Grid::new(4, 3);
+        grid.set(3, 2, true);
+        let mut pattern = Pattern::new(grid);
+        pattern.set_name(Some("corner".to_string()));
+        pattern.set_author(Some("someone".to_string()));
+        pattern.add_comment("first".to_string());
+        pattern.add_comment("second".to_string());
+        pattern.set_rule(Some("B3678/S34678".parse().unwrap()));
+        assert_eq!(parse_rle(&write_rle(&pattern)).unwrap(), pattern);
+    }
+}
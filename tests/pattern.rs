@@ -0,0 +1,150 @@
+#[cfg(test)]
+mod tests {
+    use rust_of_life::grid::Grid;
+    use rust_of_life::pattern::{
+        load_pattern_file, parse_pattern, save_pattern_file, write_pattern, Pattern, PatternFormat,
+    };
+
+    #[test]
+    fn test_from_grid_crops_to_live_cells() {
+        let mut grid = Grid::new(10, 10);
+        grid.set(3, 4, true);
+        grid.set(5, 7, true);
+        let pattern = Pattern::from_grid(&grid);
+        assert_eq!(pattern.get_grid().get_width(), 3);
+        assert_eq!(pattern.get_grid().get_height(), 4);
+        assert_eq!(
+            pattern.get_grid().live_cells().collect::<Vec<_>>(),
+            vec![(0, 0), (2, 3)]
+        );
+        assert_eq!(
+            Pattern::from_grid(&Grid::new(5, 5)).get_grid().population(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_place_centered() {
+        let mut cells = Grid::new(3, 1);
+        cells.set(0, 0, true);
+        cells.set(2, 0, true);
+        let pattern = Pattern::new(cells);
+        let mut grid = Grid::new(9, 9);
+        grid.set(0, 0, true);
+        assert_eq!(pattern.place_centered(&mut grid), 0);
+        assert_eq!(grid.live_cells().collect::<Vec<_>>(), vec![(3, 4), (5, 4)]);
+    }
+
+    #[test]
+    fn test_place_centered_drops_cells_outside() {
+        let mut cells = Grid::new(5, 1);
+        cells.set(0, 0, true);
+        cells.set(2, 0, true);
+        cells.set(4, 0, true);
+        let mut grid = Grid::new(3, 3);
+        let pattern = Pattern::new(cells);
+        assert_eq!(pattern.place_centered(&mut grid), 2);
+        assert_eq!(grid.live_cells().collect::<Vec<_>>(), vec![(1, 1)]);
+        assert_eq!(
+            pattern.get_dropped_warning(&grid, 2),
+            "Warning: 2 live cells of the 5x1 pattern do not fit in the 3x3 grid and were dropped"
+        );
+    }
+
+    #[test]
+    fn test_save_and_load_file() {
+        let path = std::env::temp_dir().join("rust_of_life-test_save_and_load_file.rle");
+        let path = path.to_str().unwrap();
+        let mut cells = Grid::new(2, 2);
+        cells.set(0, 0, true);
+        cells.set(1, 1, true);
+        let mut pattern = Pattern::new(cells);
+        pattern.set_name(Some("diagonal".to_string()));
+        pattern.set_rule(Some("B36/S23".parse().unwrap()));
+        save_pattern_file(path, &pattern).unwrap();
+        assert_eq!(load_pattern_file(path).unwrap(), pattern);
+        std::fs::remove_file(path).unwrap();
+        assert!(load_pattern_file(path).is_err());
+    }
+
+    #[test]
+    fn test_detect_format() {
+        assert_eq!(
+            PatternFormat::detect("#N Glider\nx = 3, y = 3\nbo$2bo$3o!"),
+            PatternFormat::Rle
+        );
+        assert_eq!(
+            PatternFormat::detect("!Name: Glider\n.O.\n..O\nOOO"),
+            PatternFormat::Plaintext
+        );
+        assert_eq!(PatternFormat::detect("\n.O\nOO"), PatternFormat::Plaintext);
+        assert_eq!(
+            PatternFormat::detect("#Life 1.05\n#P 0 0\n*"),
+            PatternFormat::Life105
+        );
+        assert_eq!(
+            PatternFormat::detect("#Life 1.06\n0 0"),
+            PatternFormat::Life106
+        );
+        assert_eq!(
+            PatternFormat::detect("[M2] (golly 2.0)\n.*$\n"),
+            PatternFormat::Macrocell
+        );
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(
+            PatternFormat::from_path("glider.RLE"),
+            Some(PatternFormat::Rle)
+        );
+        assert_eq!(
+            PatternFormat::from_path("dir/glider.cells"),
+            Some(PatternFormat::Plaintext)
+        );
+        assert_eq!(
+            PatternFormat::from_path("glider.lif"),
+            Some(PatternFormat::Life106)
+        );
+        assert_eq!(
+            PatternFormat::from_path("universe.mc"),
+            Some(PatternFormat::Macrocell)
+        );
+        assert_eq!(PatternFormat::from_path("glider"), None);
+        for format in PatternFormat::ALL {
+            assert_eq!(format.to_string().parse::<PatternFormat>(), Ok(format));
+        }
+    }
+
+    #[test]
+    fn test_round_trip_every_format() {
+        let mut cells = Grid::new(3, 3);
+        for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            cells.set(x, y, true);
+        }
+        let mut pattern = Pattern::new(cells);
+        pattern.set_name(Some("Glider".to_string()));
+        pattern.set_author(Some("Richard K. Guy".to_string()));
+        pattern.set_rule(Some("B36/S23".parse().unwrap()));
+        pattern.add_comment("The smallest spaceship.".to_string());
+        for format in PatternFormat::ALL {
+            let text = write_pattern(&pattern, format);
+            assert_eq!(PatternFormat::detect(&text), format);
+            assert_eq!(parse_pattern(&text).unwrap(), pattern, "{}", format);
+        }
+    }
+
+    #[test]
+    fn test_save_uses_extension() {
+        let path = std::env::temp_dir().join("rust_of_life-test_save_uses_extension.cells");
+        let path = path.to_str().unwrap();
+        let mut cells = Grid::new(2, 1);
+        cells.set(0, 0, true);
+        cells.set(1, 0, true);
+        let pattern = Pattern::new(cells);
+        save_pattern_file(path, &pattern).unwrap();
+        assert_eq!(std::fs::read_to_string(path).unwrap(), "OO\n");
+        assert_eq!(load_pattern_file(path).unwrap(), pattern);
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,59 @@
+#[cfg(test)]
+mod tests {
+    use rust_of_life::rule::Rule;
+
+    #[test]
+    fn test_default_rule_is_conway() {
+        let rule = Rule::default();
+        assert_eq!(rule, Rule::conway());
+        assert_eq!(rule.get_birth(), vec![3]);
+        assert_eq!(rule.get_survival(), vec![2, 3]);
+        assert_eq!(rule.to_string(), "B3/S23");
+    }
+
+    #[test]
+    fn test_parse_bs_notation() {
+        let highlife: Rule = "B36/S23".parse().unwrap();
+        assert_eq!(highlife.get_birth(), vec![3, 6]);
+        assert_eq!(highlife.get_survival(), vec![2, 3]);
+        assert_eq!("b36s23".parse::<Rule>().unwrap(), highlife);
+        assert_eq!("S23/B36".parse::<Rule>().unwrap(), highlife);
+    }
+
+    #[test]
+    fn test_parse_survival_birth_notation() {
+        let day_and_night: Rule = "34678/3678".parse().unwrap();
+        assert_eq!(day_and_night.to_string(), "B3678/S34678");
+        let seeds: Rule = "/2".parse().unwrap();
+        assert_eq!(seeds.to_string(), "B2/S");
+        assert_eq!("B2/S".parse::<Rule>().unwrap(), seeds);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!("".parse::<Rule>().is_err());
+        assert!("B39/S23".parse::<Rule>().is_err());
+        assert!("B3/B6".parse::<Rule>().is_err());
+        assert!("23".parse::<Rule>().is_err());
+        assert!("X3/S23".parse::<Rule>().is_err());
+    }
+
+    #[test]
+    fn test_parse_non_ascii_is_an_error() {
+        for rule in [
+            "Bé", "B3é/S23", "B3/Sé", "B3/S23é", "Bé3", "23/3é", "é3/S23", "B３/S23",
+        ] {
+            assert!(rule.parse::<Rule>().is_err(), "{}", rule);
+        }
+    }
+
+    #[test]
+    fn test_next_state() {
+        let rule = Rule::conway();
+        assert!(rule.next_state(false, 3));
+        assert!(!rule.next_state(false, 2));
+        assert!(rule.next_state(true, 2));
+        assert!(!rule.next_state(true, 4));
+        assert!(!rule.next_state(true, 9));
+    }
+}
@@ -0,0 +1,64 @@
+#[cfg(test)]
+mod tests {
+
+    use rust_of_life::scheduler::{
+        faster_speed, format_speed, parse_speed, slower_speed, Scheduler, MAX_FRAME_TIME,
+    };
+    use std::time::Duration;
+
+    #[test]
+    fn test_advance_counts_whole_generations() {
+        let mut scheduler = Scheduler::new();
+        // 60 frames of 1/60 s at 10 generations per second
+        let total: u64 = (0..60)
+            .map(|_| scheduler.advance(Duration::from_micros(16_667), 10.0))
+            .sum();
+        assert_eq!(total, 10);
+    }
+
+    #[test]
+    fn test_advance_carries_fractions() {
+        let mut scheduler = Scheduler::new();
+        for _ in 0..9 {
+            assert_eq!(scheduler.advance(Duration::from_millis(100), 1.0), 0);
+        }
+        assert_eq!(scheduler.advance(Duration::from_millis(101), 1.0), 1);
+        scheduler.reset();
+        assert_eq!(scheduler.advance(Duration::from_millis(500), 1.0), 0);
+    }
+
+    #[test]
+    fn test_advance_several_generations_per_frame() {
+        let mut scheduler = Scheduler::new();
+        assert_eq!(scheduler.advance(Duration::from_millis(20), 1000.0), 20);
+        assert_eq!(scheduler.advance(Duration::ZERO, f64::INFINITY), u64::MAX);
+    }
+
+    #[test]
+    fn test_advance_drops_long_frames() {
+        let mut scheduler = Scheduler::new();
+        let due = scheduler.advance(Duration::from_secs(10), 100.0);
+        assert_eq!(due, (MAX_FRAME_TIME.as_secs_f64() * 100.0) as u64);
+    }
+
+    #[test]
+    fn test_speed_steps() {
+        assert_eq!(faster_speed(10.0), 20.0);
+        assert_eq!(faster_speed(7.0), 10.0);
+        assert_eq!(slower_speed(10.0), 5.0);
+        assert_eq!(slower_speed(0.1), 0.1);
+        assert!(faster_speed(100000.0).is_infinite());
+        assert!(faster_speed(f64::INFINITY).is_infinite());
+        assert_eq!(slower_speed(f64::INFINITY), 100000.0);
+    }
+
+    #[test]
+    fn test_parse_and_format_speed() {
+        assert_eq!(parse_speed("0.5"), Ok(0.5));
+        assert!(parse_speed("max").unwrap().is_infinite());
+        assert!(parse_speed("0").is_err());
+        assert!(parse_speed("fast").is_err());
+        assert_eq!(format_speed(2.5), "2.5");
+        assert_eq!(format_speed(f64::INFINITY), "max");
+    }
+}
@@ -0,0 +1,37 @@
+#![cfg(feature = "sdl")]
+
+#[cfg(test)]
+mod tests {
+
+    use rust_of_life::game::Game;
+    use rust_of_life::grid::Grid;
+    use rust_of_life::sdl_lib::handle_event;
+
+    #[test]
+    fn test_handle_event_drop_file() {
+        let path = std::env::temp_dir().join("rust_of_life-test_handle_event_drop_file.rle");
+        std::fs::write(&path, "x = 3, y = 1, rule = B36/S23\n3o!\n").unwrap();
+
+        let sdl_context = sdl2::init().unwrap();
+        let mut event_pump = sdl_context.event_pump().unwrap();
+        let mut list_color = Grid::new(11, 11);
+        let mut game_info = Game::new();
+        sdl_context
+            .event()
+            .unwrap()
+            .push_event(sdl2::event::Event::DropFile {
+                timestamp: 0,
+                window_id: 0,
+                filename: path.to_str().unwrap().to_string(),
+            })
+            .unwrap();
+
+        handle_event(&mut event_pump, &mut list_color, &mut game_info);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            list_color.live_cells().collect::<Vec<_>>(),
+            vec![(4, 5), (5, 5), (6, 5)]
+        );
+        assert_eq!(game_info.get_rule().to_string(), "B36/S23");
+    }
+}
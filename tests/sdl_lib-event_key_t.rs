@@ -0,0 +1,34 @@
+#![cfg(feature = "sdl")]
+
+#[cfg(test)]
+mod tests {
+
+    use rust_of_life::game::Game;
+    use rust_of_life::grid::Grid;
+    use rust_of_life::sdl_lib::handle_event;
+    use rust_of_life::topology::Topology;
+    use sdl2::keyboard::Keycode;
+
+    #[test]
+    fn test_handle_event_key_t() {
+        let sdl_context = sdl2::init().unwrap();
+        let mut event_pump = sdl_context.event_pump().unwrap();
+        let mut list_color = Grid::new(10, 10);
+        let mut game_info = Game::new();
+        sdl_context
+            .event()
+            .unwrap()
+            .push_event(sdl2::event::Event::KeyDown {
+                timestamp: 0,
+                window_id: 0,
+                keycode: Some(Keycode::T),
+                scancode: None,
+                keymod: sdl2::keyboard::Mod::NOMOD,
+                repeat: false,
+            })
+            .unwrap();
+
+        handle_event(&mut event_pump, &mut list_color, &mut game_info);
+        assert_eq!(game_info.get_topology(), Topology::Torus);
+    }
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "sdl")]
+
 //! Tests for error handling in generate_texture
 
 use rust_of_life::sdl_lib::{init_font, init_ttf_context};
@@ -0,0 +1,53 @@
+#![cfg(feature = "sdl")]
+
+#[cfg(test)]
+mod tests {
+    use rust_of_life::frontend::Label;
+    use rust_of_life::sdl_lib::{self, Hud};
+    use sdl2::render::TextureCreator;
+    use sdl2::video::WindowContext;
+
+    fn label(text: &str, y: i32) -> Label {
+        Label {
+            text: text.to_string(),
+            x: 0,
+            y,
+        }
+    }
+
+    #[test]
+    fn test_hud_renders_only_changed_labels() {
+        let ttf_context = sdl_lib::init_ttf_context().unwrap();
+        let font = sdl_lib::init_font("./assets/Roboto-Medium.ttf", 40, &ttf_context).unwrap();
+        let sdl_context = sdl2::init().unwrap();
+        let video_subsystem = sdl_context.video().unwrap();
+        let window = video_subsystem.window("test", 800, 600).build().unwrap();
+        let mut canvas = window.into_canvas().build().unwrap();
+        let texture_creator: TextureCreator<WindowContext> = canvas.texture_creator();
+        let mut hud = Hud::new(&texture_creator, sdl_lib::BLACK);
+
+        hud.update(
+            &font,
+            &[label("iteration: 0", 0), label("population: 3", 100)],
+        )
+        .unwrap();
+        assert_eq!(hud.get_render_count(), 2);
+
+        // the same text, even moved, keeps its texture
+        hud.update(
+            &font,
+            &[label("iteration: 0", 50), label("population: 3", 150)],
+        )
+        .unwrap();
+        assert_eq!(hud.get_render_count(), 2);
+
+        hud.update(
+            &font,
+            &[label("iteration: 1", 50), label("population: 3", 150)],
+        )
+        .unwrap();
+        assert_eq!(hud.get_render_count(), 3);
+
+        hud.draw(&mut canvas);
+    }
+}
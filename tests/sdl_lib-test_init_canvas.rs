@@ -1,3 +1,5 @@
+#![cfg(feature = "sdl")]
+
 #[cfg(test)]
 mod tests {
     use rust_of_life::sdl_lib;
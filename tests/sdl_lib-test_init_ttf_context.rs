@@ -1,3 +1,5 @@
+#![cfg(feature = "sdl")]
+
 //! Tests for SDL2 TTF context initialization
 
 use rust_of_life::sdl_lib::init_ttf_context;
@@ -0,0 +1,142 @@
+#[cfg(test)]
+mod tests {
+
+    use rust_of_life::census::CensusFormat;
+    use rust_of_life::cli::{parse_args, Command};
+    use rust_of_life::game::Game;
+    use rust_of_life::rule::Rule;
+    use rust_of_life::search::{
+        run_search, run_soup, settled_objects, SearchOptions, DEFAULT_SOUP_COUNT,
+    };
+
+    fn soups(count: u64) -> SearchOptions {
+        let mut options = SearchOptions::default();
+        options.set_soups(count);
+        options.set_top(3);
+        options
+    }
+
+    /// Returns the live cells of `rows`, `o` for a live cell, moved by `(x, y)`.
+    fn cells(rows: &[&str], x: i64, y: i64) -> Vec<(i64, i64)> {
+        rows.iter()
+            .enumerate()
+            .flat_map(|(row, line)| {
+                line.chars()
+                    .enumerate()
+                    .filter(|&(_, c)| c == 'o')
+                    .map(move |(column, _)| (x + column as i64, y + row as i64))
+            })
+            .collect()
+    }
+
+    const GLIDER: &[&str] = &[".o.", "..o", "ooo"];
+    const GLIDER_WEST: &[&str] = &[".o.", "o..", "ooo"];
+    const BLOCK: &[&str] = &["oo", "oo"];
+
+    #[test]
+    fn test_settled_objects() {
+        let conway = Rule::conway();
+        let settled = |parts: &[Vec<(i64, i64)>]| settled_objects(parts.concat(), conway);
+
+        let objects = settled(&[cells(BLOCK, 0, 0), cells(&["ooo"], 10, 0)]).unwrap();
+        let mut codes: Vec<&str> = objects.iter().map(|(_, code)| code.as_str()).collect();
+        codes.sort_unstable();
+        assert_eq!(codes, vec!["xp2_7", "xs4_33"]);
+
+        // a glider flying away is left out, one flying towards the block is not
+        let objects = settled(&[cells(BLOCK, 0, 0), cells(GLIDER, 20, 20)]).unwrap();
+        assert_eq!(objects.len(), 2);
+        assert!(settled(&[cells(BLOCK, 0, 0), cells(GLIDER, -20, -20)]).is_none());
+
+        // the population of two gliders does not change until they meet
+        assert!(settled(&[cells(GLIDER, 0, 0), cells(GLIDER_WEST, 30, 0)]).is_none());
+        assert!(settled(&[cells(GLIDER, 30, 0), cells(GLIDER_WEST, 0, 0)]).is_some());
+        // gliders flying side by side never meet
+        assert!(settled(&[cells(GLIDER, 0, 0), cells(GLIDER, 10, 0)]).is_some());
+    }
+
+    #[test]
+    fn test_run_soup() {
+        let game_info = Game::new();
+        let result = run_soup(1, 16, &game_info, 100_000);
+        assert_eq!(result.get_seed(), 1);
+        assert!(result.get_lifespan().is_some());
+        assert!(result.get_census().get_total() > 0);
+        // the same seed gives the same soup
+        assert_eq!(run_soup(1, 16, &game_info, 100_000), result);
+
+        // a soup cut short does not settle and has no census
+        let result = run_soup(1, 16, &game_info, 10);
+        assert_eq!(result.get_lifespan(), None);
+        assert_eq!(result.get_census().get_total(), 0);
+    }
+
+    #[test]
+    fn test_run_search_is_reproducible() {
+        let mut game_info = Game::new();
+        game_info.set_thread_count(1);
+        let report = run_search(&game_info, 8, 42, &soups(12)).unwrap();
+        assert_eq!(report.get_soups(), 12);
+        assert_eq!(report.get_longest().len(), 3);
+        assert!(report
+            .get_longest()
+            .windows(2)
+            .all(|pair| pair[0].0 >= pair[1].0));
+        assert!(report.get_unsettled().is_empty());
+
+        game_info.set_thread_count(4);
+        let threaded = run_search(&game_info, 8, 42, &soups(12)).unwrap();
+        assert_eq!(threaded.get_census(), report.get_census());
+        assert_eq!(threaded.get_longest(), report.get_longest());
+        assert_eq!(threaded.get_samples(), report.get_samples());
+        assert!(threaded
+            .get_samples()
+            .keys()
+            .all(|apgcode| apgcode.starts_with("xp") || apgcode.starts_with("xq")));
+        assert!(!threaded.get_samples().contains_key("xp2_7"));
+        let text = threaded.to_string();
+        assert!(text.contains("soups: 12 of 8x8 cells at 50%"));
+        assert!(text.contains(
+            "look again: rust_of_life -e sparse -s 8 -r B3/S23 --density 50% --seed <SEED>\n"
+        ));
+        assert!(text.contains("spaceships:"));
+        assert!(text.contains("uncommon oscillators:"));
+
+        // a soup given up counts as unsettled
+        let mut options = soups(2);
+        options.set_generations(Some(5));
+        let report = run_search(&game_info, 8, 42, &options).unwrap();
+        assert_eq!(report.get_unsettled(), &[42, 43]);
+        assert_eq!(report.get_census().get_total(), 0);
+
+        game_info.set_rule("B03/S23".parse::<Rule>().unwrap());
+        assert!(run_search(&game_info, 8, 42, &soups(1)).is_err());
+    }
+
+    #[test]
+    fn test_parse_search_command() {
+        let args = [
+            "search", "--soups", "500", "-s", "20", "--seed", "7", "--top=5", "-n", "3000",
+            "--census", "ash.json",
+        ];
+        let Ok(Command::Search(options, search)) =
+            parse_args(args.iter().map(|arg| arg.to_string()))
+        else {
+            panic!("expected a search command");
+        };
+        assert_eq!(options.get_size_grid(), Some(20));
+        assert_eq!(search.get_soups(), 500);
+        assert_eq!(search.get_top(), 5);
+        assert_eq!(search.get_generations(), Some(3000));
+        assert_eq!(search.get_census_format(), CensusFormat::Json);
+        assert_eq!(SearchOptions::default().get_soups(), DEFAULT_SOUP_COUNT);
+
+        for args in [
+            ["search", "--stats", "out.txt"],
+            ["run", "--soups", "5"],
+            ["search", "--soups", "0"],
+        ] {
+            assert!(parse_args(args.iter().map(|arg| arg.to_string())).is_err());
+        }
+    }
+}
@@ -0,0 +1,111 @@
+#[cfg(test)]
+mod tests {
+
+    use rust_of_life::grid::Grid;
+    use rust_of_life::selection::{Region, Selection};
+
+    fn glider() -> Grid {
+        let mut grid = Grid::new(10, 10);
+        for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            grid.set(x, y, true);
+        }
+        grid
+    }
+
+    #[test]
+    fn test_region_from_corners() {
+        assert_eq!(
+            Region::from_corners((4, 1), (2, 3)),
+            Region::new(2, 1, 3, 3)
+        );
+        let grid = Grid::new(5, 5);
+        assert_eq!(
+            Region::new(3, 3, 4, 4).clip(&grid),
+            Some(Region::new(3, 3, 2, 2))
+        );
+        assert_eq!(Region::new(5, 0, 1, 1).clip(&grid), None);
+    }
+
+    #[test]
+    fn test_drag_copy_and_paste() {
+        let mut grid = glider();
+        let mut selection = Selection::new();
+        assert!(!selection.copy(&grid));
+        assert!(!selection.start_paste());
+
+        // dragging outside the grid stops at its edge
+        selection.start_drag(0, 0, &grid);
+        selection.drag_to(2, -5, &grid);
+        assert_eq!(selection.get_region(), Some(Region::new(0, 0, 3, 1)));
+        selection.drag_to(2, 2, &grid);
+        assert_eq!(selection.get_region(), Some(Region::new(0, 0, 3, 3)));
+        assert!(selection.copy(&grid));
+        assert_eq!(selection.get_clipboard().map(Grid::population), Some(5));
+
+        // the ghost follows the cursor, centered on it
+        assert!(selection.start_paste());
+        selection.set_cursor((6, 6));
+        assert_eq!(selection.get_paste_origin(), (5, 5));
+        assert_eq!(selection.get_ghost_cells()[0], (6, 5));
+        assert!(selection.paste(&mut grid));
+        assert!(!selection.is_pasting());
+        assert!(selection.get_ghost_cells().is_empty());
+        assert_eq!(grid.population(), 10);
+        assert!(grid.get(6, 5) && grid.get(7, 7));
+        assert_eq!(selection.get_region(), Some(Region::new(5, 5, 3, 3)));
+
+        // pasting over the edge keeps the cells inside the grid
+        selection.start_paste();
+        selection.set_cursor((9, 9));
+        selection.paste(&mut grid);
+        assert_eq!(selection.get_region(), Some(Region::new(8, 8, 2, 2)));
+        assert!(grid.get(9, 8));
+    }
+
+    #[test]
+    fn test_cut_clear_and_fill() {
+        let mut grid = glider();
+        let mut selection = Selection::new();
+        selection.set_region(Some(Region::new(0, 0, 3, 2)), &grid);
+        assert!(selection.cut(&mut grid));
+        assert_eq!(grid.population(), 3);
+        assert_eq!(selection.get_clipboard().map(Grid::population), Some(2));
+        assert!(selection.fill(&mut grid));
+        assert_eq!(grid.population(), 9);
+        assert!(selection.clear(&mut grid));
+        assert_eq!(grid.population(), 3);
+        selection.set_region(None, &grid);
+        assert!(!selection.clear(&mut grid));
+    }
+
+    #[test]
+    fn test_transform() {
+        let mut grid = glider();
+        let mut selection = Selection::new();
+        assert!(!selection.transform(&mut grid, Grid::rotate_clockwise));
+
+        // the selection is transformed in place
+        selection.set_region(Some(Region::new(0, 0, 3, 3)), &grid);
+        selection.transform(&mut grid, Grid::flip_horizontal);
+        assert!(grid.get(1, 0) && grid.get(0, 1) && grid.get(0, 2) && grid.get(2, 2));
+        assert_eq!(grid.population(), 5);
+
+        // while pasting, the clipboard is transformed instead of the grid
+        selection.set_region(Some(Region::new(0, 0, 3, 1)), &grid);
+        selection.copy(&grid);
+        selection.start_paste();
+        let before = grid.clone();
+        selection.transform(&mut grid, Grid::rotate_clockwise);
+        assert_eq!(grid, before);
+        let clipboard = selection.get_clipboard().unwrap();
+        assert_eq!((clipboard.get_width(), clipboard.get_height()), (1, 3));
+        assert!(clipboard.get(0, 1));
+
+        // a rotated selection keeps its top-left corner
+        selection.cancel_paste();
+        selection.set_region(Some(Region::new(0, 0, 3, 1)), &grid);
+        selection.transform(&mut grid, Grid::rotate_clockwise);
+        assert_eq!(selection.get_region(), Some(Region::new(0, 0, 1, 3)));
+        assert!(grid.get(0, 1) && !grid.get(1, 0));
+    }
+}
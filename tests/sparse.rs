@@ -0,0 +1,107 @@
+#[cfg(test)]
+mod tests {
+    use rust_of_life::engine::Universe;
+    use rust_of_life::grid::Grid;
+    use rust_of_life::hashlife::HashLife;
+    use rust_of_life::rule::Rule;
+    use rust_of_life::sparse::SparseLife;
+    use rust_of_life::topology::Topology;
+    use rust_of_life::utils::game_of_life;
+
+    const GLIDER: &[(i64, i64)] = &[(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)];
+    const R_PENTOMINO: &[(i64, i64)] = &[(1, 0), (2, 0), (0, 1), (1, 1), (1, 2)];
+
+    fn universe_with(cells: &[(i64, i64)], rule: Rule) -> SparseLife {
+        let mut universe = SparseLife::new(rule);
+        for &(x, y) in cells {
+            universe.set(x, y, true);
+        }
+        universe
+    }
+
+    #[test]
+    fn test_set_get_and_clear() {
+        let mut universe = SparseLife::new(Rule::conway());
+        universe.set(i64::MAX, i64::MIN, true);
+        universe.set(-3, 4, true);
+        universe.set(-3, 4, false);
+        assert!(universe.get(i64::MAX, i64::MIN));
+        assert!(!universe.get(-3, 4));
+        assert_eq!(universe.population(), 1);
+        universe.clear();
+        assert_eq!(universe.population(), 0);
+        assert_eq!(universe.bounding_box(), None);
+    }
+
+    #[test]
+    fn test_coordinates_wrap_around() {
+        let mut universe = SparseLife::new(Rule::conway());
+        for x in [i64::MAX - 1, i64::MAX, i64::MIN] {
+            universe.set(x, i64::MIN, true);
+        }
+        universe.next_generation();
+        assert_eq!(
+            universe.live_cells(),
+            vec![
+                (i64::MAX, i64::MIN),
+                (i64::MAX, i64::MIN + 1),
+                (i64::MAX, i64::MAX)
+            ]
+        );
+        let mut grid = Grid::new(3, 3);
+        universe.write_to_grid(&mut grid, i64::MAX - 1, i64::MIN);
+        assert_eq!(grid.population(), 2);
+    }
+
+    #[test]
+    fn test_glider_escapes_in_every_direction() {
+        let mut universe = universe_with(GLIDER, Rule::conway());
+        universe.step(8);
+        assert_eq!(universe.get_generation(), 256);
+        assert_eq!(universe.bounding_box(), Some((64, 64, 66, 66)));
+
+        // the same glider flipped upside down travels towards negative coordinates
+        let flipped: Vec<(i64, i64)> = GLIDER.iter().map(|&(x, y)| (-x, -y)).collect();
+        let mut universe = universe_with(&flipped, Rule::conway());
+        universe.step(8);
+        assert_eq!(universe.bounding_box(), Some((-66, -66, -64, -64)));
+    }
+
+    #[test]
+    fn test_matches_dense_engine() {
+        let rule = Rule::conway();
+        let mut universe = universe_with(R_PENTOMINO, rule);
+        let mut grid = Grid::new(128, 128);
+        for &(x, y) in R_PENTOMINO {
+            grid.set(x as usize + 64, y as usize + 64, true);
+        }
+        for _ in 0..50 {
+            universe.next_generation();
+            grid = game_of_life(&grid, &rule, Topology::Dead);
+        }
+        let mut view = Grid::new(128, 128);
+        universe.write_to_grid(&mut view, -64, -64);
+        assert_eq!(view, grid);
+    }
+
+    #[test]
+    fn test_matches_hashlife_engine() {
+        let rule: Rule = "B36/S23".parse().unwrap();
+        let mut sparse = universe_with(R_PENTOMINO, rule);
+        let mut hashlife = HashLife::new(rule);
+        for &(x, y) in R_PENTOMINO {
+            hashlife.set(x, y, true);
+        }
+        sparse.step(7);
+        hashlife.step(7);
+        assert_eq!(sparse.live_cells(), hashlife.live_cells());
+    }
+
+    #[test]
+    fn test_isolated_cells_with_s0() {
+        let rule: Rule = "B3/S0".parse().unwrap();
+        let mut universe = universe_with(&[(0, 0), (10, 10)], rule);
+        universe.next_generation();
+        assert_eq!(universe.live_cells(), vec![(0, 0), (10, 10)]);
+    }
+}
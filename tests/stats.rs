@@ -0,0 +1,114 @@
+#[cfg(test)]
+mod tests {
+
+    use rust_of_life::engine::Universe;
+    use rust_of_life::grid::Grid;
+    use rust_of_life::rule::Rule;
+    use rust_of_life::sparse::SparseLife;
+    use rust_of_life::stats::{
+        count_cell_changes, count_changes, GenerationStats, StatsFormat, StatsRecorder,
+    };
+    use rust_of_life::topology::Topology;
+    use rust_of_life::utils::game_of_life;
+    use std::time::Duration;
+
+    fn blinker() -> Grid {
+        let mut grid = Grid::new(10, 10);
+        for x in 3..6 {
+            grid.set(x, 4, true);
+        }
+        grid
+    }
+
+    fn stats(generation: u64, population: u64) -> GenerationStats {
+        GenerationStats::new(
+            generation,
+            population,
+            (1, 2),
+            Some((0, 0, 1, 1)),
+            4,
+            Duration::from_micros(15),
+        )
+    }
+
+    #[test]
+    fn test_count_changes() {
+        let grid = blinker();
+        let next = game_of_life(&grid, &Rule::conway(), Topology::Dead);
+        assert_eq!(count_changes(&grid, &next), (2, 2));
+        assert_eq!(count_changes(&grid, &grid), (0, 0));
+
+        let stats = GenerationStats::of_grid(1, &grid, &next, Duration::ZERO);
+        assert_eq!(stats.get_population(), 3);
+        assert_eq!((stats.get_births(), stats.get_deaths()), (2, 2));
+        assert_eq!(stats.get_bounding_box(), Some((4, 3, 4, 5)));
+        assert_eq!(stats.get_density(), 0.03);
+
+        // the unbounded engines count the changes over the whole step
+        let mut sparse = SparseLife::new(Rule::conway());
+        sparse.load_grid(&grid, -5, 0);
+        let previous = sparse.live_cells();
+        Universe::step(&mut sparse, 1);
+        let stats = GenerationStats::of_universe(&sparse, &previous, 2, Duration::ZERO);
+        assert_eq!(stats.get_generation(), 2);
+        assert_eq!((stats.get_births(), stats.get_deaths()), (0, 0));
+        assert_eq!(stats.get_span(), 2);
+
+        let horizontal = [(3, 4), (4, 4), (5, 4)];
+        let vertical = [(4, 3), (4, 4), (4, 5)];
+        assert_eq!(count_cell_changes(&horizontal, &vertical), (2, 2));
+        assert_eq!(count_cell_changes(&[], &vertical), (3, 0));
+        assert_eq!(count_cell_changes(&horizontal, &[]), (0, 3));
+    }
+
+    #[test]
+    fn test_recorder_keeps_the_last_generations() {
+        let mut recorder = StatsRecorder::new(3);
+        assert!(recorder.is_empty());
+        for generation in 1..=5 {
+            recorder.record(stats(generation, generation * 10));
+        }
+        let generations: Vec<u64> = recorder
+            .get_records()
+            .map(GenerationStats::get_generation)
+            .collect();
+        assert_eq!(generations, vec![3, 4, 5]);
+
+        // going back in time drops the generations that no longer happened
+        recorder.record(stats(4, 7));
+        assert_eq!(recorder.len(), 2);
+        assert_eq!(recorder.get_last().map(|s| s.get_population()), Some(7));
+        recorder.clear();
+        assert!(recorder.get_last().is_none());
+    }
+
+    #[test]
+    fn test_write_stats() {
+        let mut recorder = StatsRecorder::new(10);
+        assert_eq!(recorder.write(StatsFormat::Json), "[]\n");
+        recorder.record(stats(1, 2));
+        recorder.record(GenerationStats::new(2, 0, (0, 2), None, 4, Duration::ZERO));
+        assert_eq!(
+            recorder.write(StatsFormat::Csv),
+            "generation,population,births,deaths,min_x,min_y,max_x,max_y,density,step_us,span\n\
+             1,2,1,2,0,0,1,1,0.5,15,1\n\
+             2,0,0,2,,,,,0,0,1\n"
+        );
+        assert_eq!(
+            recorder.write(StatsFormat::Json),
+            "[\n  {\"generation\": 1, \"population\": 2, \"births\": 1, \"deaths\": 2, \"bounding_box\": [0, 0, 1, 1], \"density\": 0.5, \"step_us\": 15, \"span\": 1},\n  \
+             {\"generation\": 2, \"population\": 0, \"births\": 0, \"deaths\": 2, \"bounding_box\": null, \"density\": 0, \"step_us\": 0, \"span\": 1}\n]\n"
+        );
+    }
+
+    #[test]
+    fn test_stats_format() {
+        assert_eq!(StatsFormat::from_path("run.CSV"), Some(StatsFormat::Csv));
+        assert_eq!(StatsFormat::from_path("run.json"), Some(StatsFormat::Json));
+        assert_eq!(StatsFormat::from_path("run.txt"), None);
+        for format in StatsFormat::ALL {
+            assert_eq!(format.to_string().parse::<StatsFormat>(), Ok(format));
+        }
+        assert!("xlsx".parse::<StatsFormat>().is_err());
+    }
+}
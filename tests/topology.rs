@@ -0,0 +1,62 @@
+#[cfg(test)]
+mod tests {
+    use rust_of_life::topology::Topology;
+
+    #[test]
+    fn test_resolve_inside_is_identity() {
+        for topology in Topology::ALL {
+            assert_eq!(topology.resolve(2, 3, 5, 5), Some((2, 3)));
+        }
+    }
+
+    #[test]
+    fn test_resolve_dead() {
+        assert_eq!(Topology::Dead.resolve(-1, 0, 5, 5), None);
+        assert_eq!(Topology::Dead.resolve(0, 5, 5, 5), None);
+    }
+
+    #[test]
+    fn test_resolve_torus() {
+        assert_eq!(Topology::Torus.resolve(-1, 0, 5, 4), Some((4, 0)));
+        assert_eq!(Topology::Torus.resolve(5, 4, 5, 4), Some((0, 0)));
+    }
+
+    #[test]
+    fn test_resolve_klein_bottle() {
+        // horizontal edges are plainly joined
+        assert_eq!(Topology::KleinBottle.resolve(-1, 1, 5, 4), Some((4, 1)));
+        // vertical edges are joined with a twist
+        assert_eq!(Topology::KleinBottle.resolve(1, -1, 5, 4), Some((3, 3)));
+        assert_eq!(Topology::KleinBottle.resolve(0, 4, 5, 4), Some((4, 0)));
+    }
+
+    #[test]
+    fn test_resolve_cross_surface() {
+        assert_eq!(Topology::CrossSurface.resolve(-1, 1, 5, 4), Some((4, 2)));
+        assert_eq!(Topology::CrossSurface.resolve(1, -1, 5, 4), Some((3, 3)));
+    }
+
+    #[test]
+    fn test_resolve_mirror() {
+        assert_eq!(Topology::Mirror.resolve(-1, 2, 5, 4), Some((0, 2)));
+        assert_eq!(Topology::Mirror.resolve(5, 4, 5, 4), Some((4, 3)));
+    }
+
+    #[test]
+    fn test_next_cycles_through_all() {
+        let mut topology = Topology::Dead;
+        for expected in Topology::ALL.iter().skip(1) {
+            topology = topology.next();
+            assert_eq!(topology, *expected);
+        }
+        assert_eq!(topology.next(), Topology::Dead);
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        for topology in Topology::ALL {
+            assert_eq!(topology.to_string().parse::<Topology>(), Ok(topology));
+        }
+        assert!("sphere".parse::<Topology>().is_err());
+    }
+}
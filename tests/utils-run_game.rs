@@ -1,3 +1,5 @@
+#![cfg(feature = "sdl")]
+
 #[cfg(test)]
 mod tests {
 
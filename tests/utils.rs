@@ -1,17 +1,55 @@
 #[cfg(test)]
 mod tests {
-    use rust_of_life::utils::{game_of_life, get_grid_point_list, get_rect_list};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use rust_of_life::camera::Camera;
+    use rust_of_life::grid::{BandPool, Grid};
+    use rust_of_life::rule::Rule;
+    use rust_of_life::selection::Region;
+    use rust_of_life::topology::Topology;
+    use rust_of_life::utils::{
+        fill_random, fill_random_region, format_density, game_of_life, game_of_life_threaded,
+        get_colored_rect_list, get_grid_point_list, get_rect_list,
+    };
 
-    use sdl2::rect::{FPoint, FRect};
+    use rust_of_life::frontend::{Color, Point, Rect};
 
     #[test]
     fn test_get_grid_point_list() {
-        let points = get_grid_point_list(4, 10.0, 100, 100);
-        assert_eq!(points.len(), 18);
-        assert_eq!(points[0], FPoint::new(0.0, 0.0));
-        assert_eq!(points[1], FPoint::new(0.0, 100.0));
-        assert_eq!(points[2], FPoint::new(10.0, 100.0));
-        assert_eq!(points[3], FPoint::new(10.0, 0.0));
+        let points = get_grid_point_list(&Camera::new(10.0), 4, 4, 100, 100);
+        // 5 vertical lines, the corner between the passes, 5 horizontal lines
+        assert_eq!(points.len(), 21);
+        assert_eq!(points[0], Point::new(0.0, 0.0));
+        assert_eq!(points[1], Point::new(0.0, 40.0));
+        assert_eq!(points[2], Point::new(10.0, 40.0));
+        assert_eq!(points[3], Point::new(10.0, 0.0));
+        assert_eq!(points[9], Point::new(40.0, 40.0));
+        assert_eq!(points[10], Point::new(40.0, 40.0));
+        assert_eq!(points[11], Point::new(40.0, 40.0));
+        assert_eq!(points[12], Point::new(0.0, 40.0));
+        // consecutive points only differ along one axis, so no diagonal is drawn
+        assert!(points
+            .windows(2)
+            .all(|pair| pair[0].x == pair[1].x || pair[0].y == pair[1].y));
+    }
+
+    #[test]
+    fn test_get_grid_point_list_follows_camera() {
+        let mut camera = Camera::new(10.0);
+        camera.pan(25.0, 0.0);
+        let points = get_grid_point_list(&camera, 100, 100, 100, 100);
+        // columns 3 to 12 are in view, from x = 5 to x = 95
+        assert_eq!(points[0], Point::new(5.0, 0.0));
+        assert_eq!(points[1], Point::new(5.0, 100.0));
+        assert_eq!(points[18], Point::new(95.0, 100.0));
+        assert_eq!(points[19], Point::new(95.0, 0.0));
+        assert!(points.iter().all(|p| (0.0..=100.0).contains(&p.x)));
+
+        // cells smaller than a few pixels are drawn without lines
+        assert!(get_grid_point_list(&Camera::new(2.0), 100, 100, 100, 100).is_empty());
+        // nor is a grid out of view
+        camera.pan(-2000.0, 0.0);
+        assert!(get_grid_point_list(&camera, 100, 100, 100, 100).is_empty());
     }
 
     // #[test]
@@ -28,30 +66,161 @@ mod tests {
 
     #[test]
     fn test_game_of_life() {
-        let list = vec![
+        let list = Grid::from_rows(&[
             vec![false, true, false],
             vec![true, true, false],
             vec![false, false, false],
-        ];
-        let expected = vec![
+        ]);
+        let expected = Grid::from_rows(&[
             vec![true, true, false],
             vec![true, true, false],
             vec![false, false, false],
-        ];
-        assert_eq!(game_of_life(list), expected);
+        ]);
+        assert_eq!(
+            game_of_life(&list, &Rule::conway(), Topology::Dead),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_game_of_life_highlife_birth_on_six() {
+        let list = Grid::from_rows(&[
+            vec![true, true, true],
+            vec![true, false, true],
+            vec![true, false, false],
+        ]);
+        let highlife: Rule = "B36/S23".parse().unwrap();
+        assert!(game_of_life(&list, &highlife, Topology::Dead).get(1, 1));
+        assert!(!game_of_life(&list, &Rule::conway(), Topology::Dead).get(1, 1));
+    }
+
+    #[test]
+    fn test_game_of_life_torus_wraps_blinker() {
+        // a vertical blinker on the left edge becomes a horizontal one across the edge
+        let mut list = Grid::new(5, 5);
+        list.set(0, 1, true);
+        list.set(0, 2, true);
+        list.set(0, 3, true);
+        let next = game_of_life(&list, &Rule::conway(), Topology::Torus);
+        assert!(next.get(4, 2) && next.get(0, 2) && next.get(1, 2));
+        assert_eq!(next.population(), 3);
+
+        let dead = game_of_life(&list, &Rule::conway(), Topology::Dead);
+        assert_eq!(dead.population(), 2);
+    }
+
+    #[test]
+    fn test_game_of_life_torus_glider_survives() {
+        let mut list = Grid::new(6, 6);
+        for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            list.set(x, y, true);
+        }
+        let start = list.clone();
+        // a glider moves one cell diagonally every 4 generations: 24 generations is a full lap
+        for _ in 0..24 {
+            list = game_of_life(&list, &Rule::conway(), Topology::Torus);
+        }
+        assert_eq!(list, start);
+    }
+
+    #[test]
+    fn test_game_of_life_threaded_matches_single_thread() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let rows: Vec<Vec<bool>> = (0..67)
+            .map(|_| (0..153).map(|_| rng.gen_bool(0.4)).collect())
+            .collect();
+        let start = Grid::from_rows(&rows);
+        let rule: Rule = "B36/S23".parse().unwrap();
+        for thread_count in [0, 1, 2, 3, 8, 100] {
+            // the same pool computes every generation, whatever the grid
+            let mut pool = BandPool::new(thread_count);
+            for topology in Topology::ALL {
+                let mut list = start.clone();
+                let mut next = Grid::default();
+                for _ in 0..4 {
+                    let expected = game_of_life(&list, &rule, topology);
+                    game_of_life_threaded(&list, &mut next, &rule, topology, &mut pool);
+                    assert_eq!(next, expected, "{} threads, {}", thread_count, topology);
+                    std::mem::swap(&mut list, &mut next);
+                }
+            }
+            let small = start.crop(0, 0, 20, 5);
+            let mut next = Grid::default();
+            game_of_life_threaded(&small, &mut next, &rule, Topology::Torus, &mut pool);
+            assert_eq!(next, game_of_life(&small, &rule, Topology::Torus));
+        }
+    }
+
+    #[test]
+    fn test_fill_random_is_reproducible() {
+        let mut grid = Grid::new(64, 64);
+        fill_random(&mut grid, 0.5, 7);
+        let mut same_seed = Grid::new(64, 64);
+        fill_random(&mut same_seed, 0.5, 7);
+        let mut other_seed = Grid::new(64, 64);
+        fill_random(&mut other_seed, 0.5, 8);
+        assert_eq!(grid, same_seed);
+        assert_ne!(grid, other_seed);
+        assert!((1500..2600).contains(&grid.population()));
+        fill_random(&mut grid, 0.0, 7);
+        assert_eq!(grid.population(), 0);
+        fill_random(&mut grid, 1.0, 7);
+        assert_eq!(grid.population(), 64 * 64);
+    }
+
+    #[test]
+    fn test_fill_random_region() {
+        let mut grid = Grid::new(20, 20);
+        grid.set(0, 0, true);
+        fill_random_region(&mut grid, Region::new(5, 5, 10, 10), 1.0, 3);
+        assert_eq!(grid.population(), 101);
+        assert!(grid.get(5, 5) && grid.get(14, 14) && !grid.get(15, 15));
+
+        // the same seed gives the same soup wherever the region is
+        let mut moved = Grid::new(20, 20);
+        fill_random_region(&mut grid, Region::new(0, 0, 8, 8), 0.3, 11);
+        fill_random_region(&mut moved, Region::new(12, 12, 8, 8), 0.3, 11);
+        assert_eq!(grid.crop(0, 0, 8, 8), moved.crop(12, 12, 8, 8));
+
+        assert_eq!(format_density(0.5), "50%");
+        assert_eq!(format_density(0.125), "12.5%");
     }
 
     #[test]
     fn test_get_rect_list() {
-        let list = vec![
+        let list = Grid::from_rows(&[
             vec![false, true, false],
             vec![true, true, false],
             vec![false, false, false],
-        ];
-        let rects = get_rect_list(&list, 10.0);
+        ]);
+        let rects = get_rect_list(&list, &Camera::new(10.0), 100, 100);
         assert_eq!(rects.len(), 3);
-        assert_eq!(rects[0], FRect::new(10.0, 0.0, 10.0, 10.0));
-        assert_eq!(rects[1], FRect::new(0.0, 10.0, 10.0, 10.0));
-        assert_eq!(rects[2], FRect::new(10.0, 10.0, 10.0, 10.0));
+        assert_eq!(rects[0], Rect::new(10.0, 0.0, 10.0, 10.0));
+        assert_eq!(rects[1], Rect::new(0.0, 10.0, 10.0, 10.0));
+        assert_eq!(rects[2], Rect::new(10.0, 10.0, 10.0, 10.0));
+
+        // zoomed on the bottom-right cell, only the cells in view are kept
+        let mut camera = Camera::new(10.0);
+        camera.zoom_at(2.0, 20.0, 20.0);
+        let rects = get_rect_list(&list, &camera, 20, 20);
+        assert_eq!(rects, vec![Rect::new(0.0, 0.0, 20.0, 20.0)]);
+    }
+
+    #[test]
+    fn test_get_colored_rect_list() {
+        let (red, blue) = (Color::new(255, 0, 0), Color::new(0, 0, 255));
+        let cells = [(0, 0, red), (1, 0, blue), (2, 0, red), (50, 50, blue)];
+        let (rects, colors) =
+            get_colored_rect_list(cells.into_iter(), &Camera::new(10.0), 100, 100);
+        // the cell out of the view is culled, the others are grouped by color
+        assert_eq!(colors, vec![blue, red, red]);
+        assert_eq!(
+            rects,
+            vec![
+                Rect::new(10.0, 0.0, 10.0, 10.0),
+                Rect::new(0.0, 0.0, 10.0, 10.0),
+                Rect::new(20.0, 0.0, 10.0, 10.0)
+            ]
+        );
     }
 }